@@ -23,3 +23,9 @@ export { SealClient, type SealClientOptions } from './client.js';
  * 用于临时加密会话的密钥管理
  */
 export { SessionKey } from './session-key.js';
+
+/**
+ * 导出子密钥派生函数
+ * 用于从明文模式的密钥派生加密密钥、MAC密钥和每个文件的密钥
+ */
+export { deriveSubkey, type SubkeyLabel } from './kdf.js';
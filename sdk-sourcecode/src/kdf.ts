@@ -11,6 +11,7 @@ import { hmac } from '@noble/hashes/hmac';
 import { sha3_256 } from '@noble/hashes/sha3';
 
 import type { GTElement } from './bls12381.js';
+import { UserError } from './error.js';
 
 /**
  * 默认密钥派生函数
@@ -75,3 +76,81 @@ export function deriveKey(purpose: KeyPurpose, baseKey: Uint8Array): Uint8Array
 			return hmac(sha3_256, baseKey, new Uint8Array([1]));
 	}
 }
+
+/**
+ * 派生子密钥时HKDF使用的盐，与Rust实现中的`DST_SUBKEY`相同
+ */
+const DST_SUBKEY = new TextEncoder().encode('SUI-SEAL-SUBKEY-00');
+
+/**
+ * 文件编号的最大值，与Rust实现中`SubkeyLabel::File`的u64相同
+ */
+const MAX_FILE_INDEX = 2n ** 64n - 1n;
+
+/**
+ * 子密钥的标签
+ * 不同的标签得到相互独立的子密钥：
+ * - 'encryption' - 对称加密密钥
+ * - 'mac' - 消息认证码密钥
+ * - { file: n } - 第n个文件的密钥，n必须是0到2^64-1之间的整数
+ * - { custom: bytes } - 应用程序自定义的标签，不能为空
+ */
+export type SubkeyLabel = 'encryption' | 'mac' | { file: number | bigint } | { custom: Uint8Array };
+
+/**
+ * 计算子密钥标签的字节编码，用作HKDF的info
+ * 编码以"seal-subkey/"和标签种类开头，因此自定义标签不会与内置标签冲突
+ *
+ * @param label - 子密钥的标签
+ * @returns 标签的字节编码
+ */
+function subkeyInfo(label: SubkeyLabel): Uint8Array {
+	const encoder = new TextEncoder();
+	if (label === 'encryption' || label === 'mac') {
+		return encoder.encode(`seal-subkey/${label}`);
+	}
+	if ('file' in label) {
+		return encoder.encode(`seal-subkey/file/${BigInt(label.file)}`);
+	}
+	const prefix = encoder.encode('seal-subkey/custom/');
+	const info = new Uint8Array(prefix.length + label.custom.length);
+	info.set(prefix);
+	info.set(label.custom, prefix.length);
+	return info;
+}
+
+/**
+ * 从明文模式的密钥派生带标签的子密钥，与Rust实现中的`derive_subkey`相同
+ * 子密钥为 HKDF-SHA3-256(ikm = key, salt = "SUI-SEAL-SUBKEY-00", info = 标签的编码) 的前32字节
+ *
+ * @param key - 明文模式的密钥(32字节)
+ * @param label - 子密钥的标签
+ * @returns 派生的子密钥(32字节)
+ * @throws {UserError} 如果密钥长度不是32字节、文件编号不是0到2^64-1之间的整数或自定义标签为空
+ */
+export function deriveSubkey(key: Uint8Array, label: SubkeyLabel): Uint8Array {
+	if (key.length !== 32) {
+		throw new UserError('密钥长度必须为32字节');
+	}
+	if (typeof label === 'object' && 'file' in label && !isValidFileIndex(label.file)) {
+		throw new UserError('文件编号必须是0到2^64-1之间的整数');
+	}
+	if (typeof label === 'object' && 'custom' in label && label.custom.length === 0) {
+		throw new UserError('自定义标签不能为空');
+	}
+	return hkdf(sha3_256, key, DST_SUBKEY, subkeyInfo(label), 32);
+}
+
+/**
+ * 检查文件编号是否可以表示为Rust实现中的u64
+ *
+ * @param file - 文件编号
+ * @returns 如果是0到2^64-1之间的整数则返回true
+ */
+function isValidFileIndex(file: number | bigint): boolean {
+	if (typeof file === 'number' && !Number.isInteger(file)) {
+		return false;
+	}
+	const index = BigInt(file);
+	return index >= 0n && index <= MAX_FILE_INDEX;
+}
@@ -0,0 +1,63 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/**
+ * 密钥派生测试文件
+ * 测试子密钥派生与Rust实现的兼容性
+ */
+
+import { toHex } from '@mysten/bcs';
+import { describe, expect, it } from 'vitest';
+
+import { UserError } from '../../src/error';
+import { deriveSubkey } from '../../src/kdf';
+import type { SubkeyLabel } from '../../src/kdf';
+
+// 测试密钥，字节为0..32
+const key = Uint8Array.from({ length: 32 }, (_, i) => i);
+
+describe('Subkey derivation', () => {
+	/**
+	 * 测试固定值，与Rust实现中test_derive_subkey使用的值相同
+	 */
+	it('should match the Rust test vectors', () => {
+		const cases: [SubkeyLabel, string][] = [
+			['encryption', '573dc3c45f65e24438c819b39fd4763f63ba83f6622ac8ffb206d6309b298f3a'],
+			['mac', '3f7eb45d74095fea5801a3cf21ac9b90efcb8cb9e8dd91eac7b9f7dac98be4a6'],
+			[{ file: 0 }, '34737ee5b4055b91b05c64d11ade6f8c1a37d0dfe2c2991b3431b32fa9e10398'],
+			[{ file: 7n }, '95c420298c95ca094ae864b09b44907453fb72c8004b2cf518d17ec52ffc2005'],
+			[
+				{ file: 2n ** 64n - 1n },
+				'e69bf6ef536ca6f1e31906ed85050e122a8be0e22f0b875d31af6de3318a8dd3',
+			],
+			[
+				{ custom: new TextEncoder().encode('file-7') },
+				'dd31b4eff5f211fd8ef3648428399e7257573db7c7f1ca064ec3e772402e9638',
+			],
+		];
+		for (const [label, expected] of cases) {
+			expect(toHex(deriveSubkey(key, label))).toBe(expected);
+		}
+	});
+
+	/**
+	 * 测试自定义标签不会与内置标签冲突，以及无效的输入
+	 */
+	it('should separate custom labels and reject invalid input', () => {
+		expect(deriveSubkey(key, { custom: new TextEncoder().encode('file-7') })).not.toEqual(
+			deriveSubkey(key, { file: 7 }),
+		);
+		expect(() => deriveSubkey(key, { custom: new Uint8Array() })).toThrow();
+		expect(() => deriveSubkey(key.slice(1), 'encryption')).toThrow();
+	});
+
+	/**
+	 * 测试Rust实现中的u64无法表示的文件编号被拒绝，而不是派生出与Rust不一致的子密钥
+	 */
+	it('should reject file indices outside the u64 range', () => {
+		for (const file of [1.5, -1, -1n, 2n ** 64n, 2 ** 64, NaN, Infinity]) {
+			expect(() => deriveSubkey(key, { file })).toThrow(UserError);
+		}
+		expect(deriveSubkey(key, { file: 7 })).toEqual(deriveSubkey(key, { file: 7n }));
+	});
+});
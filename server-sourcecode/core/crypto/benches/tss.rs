@@ -0,0 +1,60 @@
+// Copyright (c), Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! GF(256)运算和阈值秘密共享的基准测试
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use crypto::gf256::GF256;
+use crypto::tss::{combine, split};
+use rand::thread_rng;
+
+/// GF(256)的乘法和除法
+fn gf256(c: &mut Criterion) {
+    let mut group = c.benchmark_group("GF256");
+    let a = GF256::from(0x53);
+    let b = GF256::from(0xca);
+    group.bench_function("mul", |bencher| {
+        bencher.iter(|| black_box(&a) * black_box(&b))
+    });
+    group.bench_function("div", |bencher| {
+        bencher.iter(|| black_box(&a) / black_box(&b))
+    });
+    group.finish();
+}
+
+/// 32字节秘密的分割和重构，阈值为份额数量的一半加1
+fn tss(c: &mut Criterion) {
+    let mut group = c.benchmark_group("TSS");
+    let secret = [7u8; 32];
+    for number_of_shares in [3u8, 10, 50] {
+        let threshold = number_of_shares / 2 + 1;
+        group.bench_with_input(
+            BenchmarkId::new("split", number_of_shares),
+            &number_of_shares,
+            |bencher, n| bencher.iter(|| split(&mut thread_rng(), secret, threshold, *n)),
+        );
+
+        let sharing = split(&mut thread_rng(), secret, threshold, number_of_shares).unwrap();
+        let shares = sharing
+            .indices()
+            .iter()
+            .copied()
+            .zip(sharing.shares().iter().copied())
+            .take(threshold as usize)
+            .collect::<Vec<_>>();
+        group.bench_with_input(
+            BenchmarkId::new("combine", number_of_shares),
+            &shares,
+            |bencher, shares| bencher.iter(|| combine(black_box(shares))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = gf256, tss,
+}
+
+criterion_main!(benches);
@@ -0,0 +1,135 @@
+// Copyright (c), Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/**
+ * 明文压缩模块
+ *
+ * 密文无法再被压缩，因此JSON和文本等冗余较大的内容应在加密前压缩。
+ * 压缩算法存储在加密对象的关键扩展`header::TAG_COMPRESSION`中，`seal_decrypt`在认证之后解压缩。
+ *
+ * 支持的压缩算法:
+ * 1. Deflate - 原始的DEFLATE格式（RFC 1951，不带zlib或gzip头部），浏览器中对应"deflate-raw"
+ * 2. Zstd - Zstandard格式（RFC 8878），压缩率和速度通常都优于DEFLATE
+ *
+ * 解压缩时必须给出输出的最大长度：很小的密文可能解压缩出非常大的数据（解压缩炸弹），
+ * 超过最大长度时立即停止并返回错误。
+ *
+ * 注意压缩后的长度取决于明文的内容。如果攻击者可以控制部分明文并观察密文长度，
+ * 压缩可能泄露明文的其他部分（参见CRIME和BREACH攻击），这种情况下不应使用压缩。
+ */
+
+use fastcrypto::error::{FastCryptoError, FastCryptoResult};
+use flate2::read::{DeflateDecoder, DeflateEncoder};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+
+/// `seal_decrypt`默认允许的最大解压缩长度（64 MiB）
+pub const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 64 << 20;
+
+/// 压缩算法
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Compression {
+    /// 原始的DEFLATE格式
+    Deflate,
+    /// Zstandard格式
+    Zstd,
+}
+
+impl Compression {
+    /**
+     * 使用默认的压缩级别压缩数据
+     *
+     * 参数:
+     * @param data - 要压缩的数据
+     *
+     * 返回:
+     * 压缩后的数据
+     */
+    pub fn compress(&self, data: &[u8]) -> FastCryptoResult<Vec<u8>> {
+        match self {
+            Compression::Deflate => {
+                let mut compressed = vec![];
+                DeflateEncoder::new(data, flate2::Compression::default())
+                    .read_to_end(&mut compressed)
+                    .map_err(compression_error)?;
+                Ok(compressed)
+            }
+            Compression::Zstd => {
+                zstd::encode_all(data, zstd::DEFAULT_COMPRESSION_LEVEL).map_err(compression_error)
+            }
+        }
+    }
+
+    /**
+     * 解压缩数据，输出长度超过`max_size`时返回错误
+     *
+     * 参数:
+     * @param data - 压缩后的数据
+     * @param max_size - 解压缩后允许的最大长度
+     *
+     * 返回:
+     * 解压缩后的数据，如果数据无效或超过最大长度则返回错误
+     */
+    pub fn decompress(&self, data: &[u8], max_size: usize) -> FastCryptoResult<Vec<u8>> {
+        let decoder: Box<dyn Read + '_> = match self {
+            Compression::Deflate => Box::new(DeflateDecoder::new(data)),
+            Compression::Zstd => {
+                Box::new(zstd::stream::read::Decoder::new(data).map_err(compression_error)?)
+            }
+        };
+        // 多读一个字节，这样可以区分恰好达到最大长度和超过最大长度
+        let mut decompressed = vec![];
+        decoder
+            .take((max_size as u64).saturating_add(1))
+            .read_to_end(&mut decompressed)
+            .map_err(compression_error)?;
+        if decompressed.len() > max_size {
+            return Err(FastCryptoError::GeneralError(
+                "Decompressed data exceeds the maximum size".to_string(),
+            ));
+        }
+        Ok(decompressed)
+    }
+}
+
+/// 将压缩库的错误转换为FastCryptoError
+fn compression_error(e: std::io::Error) -> FastCryptoError {
+    FastCryptoError::GeneralError(format!("Compression error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试两种压缩算法：压缩后可以解压缩回原始数据，冗余的数据被压缩，
+    /// 超过最大长度或者无效的数据无法解压缩
+    #[test]
+    fn test_compression() {
+        let data = br#"{"name": "seal", "values": [1, 2, 3]}"#.repeat(100);
+        for compression in [Compression::Deflate, Compression::Zstd] {
+            let compressed = compression.compress(&data).unwrap();
+            assert!(compressed.len() < data.len() / 10);
+            assert_eq!(
+                compression.decompress(&compressed, data.len()).unwrap(),
+                data
+            );
+            assert!(compression.decompress(&compressed, data.len() - 1).is_err());
+            assert!(compression.decompress(&data, data.len()).is_err());
+
+            let empty = compression.compress(&[]).unwrap();
+            assert!(compression.decompress(&empty, 0).unwrap().is_empty());
+            assert_eq!(
+                compression.decompress(&compressed, usize::MAX).unwrap(),
+                data
+            );
+        }
+
+        // 解压缩炸弹：很小的压缩数据解压缩后非常大，超过最大长度时立即停止
+        let bomb = vec![0; 16 << 20];
+        for compression in [Compression::Deflate, Compression::Zstd] {
+            let compressed = compression.compress(&bomb).unwrap();
+            assert!(compressed.len() < bomb.len() / 1000);
+            assert!(compression.decompress(&compressed, 1 << 10).is_err());
+        }
+    }
+}
@@ -5,18 +5,26 @@
  * 数据加密机制(DEM)模块
  * 
  * DEM(Data Encapsulation Mechanism)是混合加密系统中用于加密实际数据的部分。
- * 本模块提供两种对称加密算法的实现:
+ * 本模块提供以下对称加密算法的实现:
  * 1. Aes256Gcm - 基于AES-GCM的认证加密
  * 2. Hmac256Ctr - 基于HMAC-SHA3-256和CTR模式的自定义认证加密
+ * 3. Aes256GcmChunked - 分块的AES-GCM认证加密，支持基于Read/Write的流式处理和按范围随机访问解密
+ * 4. ChaCha20Poly1305 - 基于ChaCha20-Poly1305的认证加密，适用于没有AES硬件加速的设备
+ * 5. Aes256GcmCommitting - 带有密钥承诺的AES-GCM认证加密，密文只能用唯一的密钥解密
+ *
+ * 此外，`Padding`在加密前填充明文以隐藏其长度，填充的字节与明文一起被加密和认证。
+ * 流式处理时使用`PaddedReader`在明文末尾追加填充，使用`UnpaddedWriter`在输出时移除填充。
  * 
  * 这些加密机制通常与密钥封装机制(KEM)一起使用，构成完整的混合加密系统。
  * KEM负责安全地传递对称密钥，而DEM使用该密钥加密实际消息。
  * 
- * 两种实现都支持关联数据(AAD)的认证加密，确保密文和关联数据的完整性和真实性。
+ * 所有实现都支持关联数据(AAD)的认证加密，确保密文和关联数据的完整性和真实性。
  */
 
 use crate::utils::xor_unchecked;
 use crate::KEY_SIZE;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::ChaCha20Poly1305 as ExternalChaCha20Poly1305;
 use fastcrypto::error::FastCryptoError;
 use fastcrypto::hmac::HmacKey;
 use fastcrypto::{
@@ -27,7 +35,11 @@ use fastcrypto::{
     error::FastCryptoResult,
     traits::ToFromBytes,
 };
+use serde::{Deserialize, Serialize};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
 use typenum::U16;
+use zeroize::{Zeroize, Zeroizing};
 
 /// AES-256-GCM认证加密实现
 /// 提供基于AES-GCM的加密和解密功能，包括关联数据的认证
@@ -89,6 +101,510 @@ impl Aes256Gcm {
     }
 }
 
+/// ChaCha20-Poly1305认证加密实现 (RFC 8439)
+/// 在没有AES硬件指令的设备上比AES-GCM快，密文格式为 c || tag，其中tag为16字节
+pub struct ChaCha20Poly1305;
+
+impl ChaCha20Poly1305 {
+    /// 使用固定的12字节nonce。与`Aes256Gcm`一样，由于密钥从不重用，这是安全的。
+    const NONCE: [u8; 12] = [244, 59, 60, 127, 188, 52, 81, 190, 131, 85, 250, 111];
+
+    /**
+     * 使用ChaCha20-Poly1305加密消息
+     *
+     * 参数:
+     * @param msg - 要加密的明文
+     * @param aad - 需要认证但不需要加密的关联数据
+     * @param key - 32字节加密密钥
+     *
+     * 返回:
+     * 包含认证标签的密文
+     */
+    pub fn encrypt(msg: &[u8], aad: &[u8], key: &[u8; KEY_SIZE]) -> Vec<u8> {
+        Self::cipher(key)
+            .encrypt(&Self::NONCE.into(), Payload { msg, aad })
+            .expect("Never fails for messages shorter than 256 GiB")
+    }
+
+    /**
+     * 使用ChaCha20-Poly1305解密密文
+     *
+     * 参数:
+     * @param ciphertext - 包含认证标签的密文
+     * @param aad - 需要认证的关联数据，必须与加密时使用的相同
+     * @param key - 32字节解密密钥
+     *
+     * 返回:
+     * 成功时返回解密的明文，认证失败时返回错误
+     */
+    pub fn decrypt(
+        ciphertext: &[u8],
+        aad: &[u8],
+        key: &[u8; KEY_SIZE],
+    ) -> FastCryptoResult<Vec<u8>> {
+        Self::cipher(key)
+            .decrypt(
+                &Self::NONCE.into(),
+                Payload {
+                    msg: ciphertext,
+                    aad,
+                },
+            )
+            .map_err(|_| FastCryptoError::GeneralError("Decryption failed".to_string()))
+    }
+
+    /// 使用给定密钥创建ChaCha20-Poly1305实例
+    fn cipher(key: &[u8; KEY_SIZE]) -> ExternalChaCha20Poly1305 {
+        ExternalChaCha20Poly1305::new(key.into())
+    }
+}
+
+/**
+ * 带有密钥承诺的AES-256-GCM认证加密
+ *
+ * AES-GCM不是密钥承诺的：可以构造一个密文，使它在两个不同的密钥下都能通过认证并解密为不同的明文。
+ * 在多接收者的场景中，不同的密钥服务器子集可能重建出不同的基础密钥，因此需要密钥承诺。
+ *
+ * 从DEM密钥派生两个独立的密钥：承诺 = hmac(key, 4)，加密密钥 = hmac(key, 5)。
+ * 承诺与密文一起存储，解密时先检查承诺，再使用派生的加密密钥进行AES-256-GCM解密。
+ * 找到两个承诺相同的密钥需要找到HMAC-SHA3-256的碰撞，因此密文只能用唯一的密钥解密。
+ */
+pub struct Aes256GcmCommitting;
+
+impl Aes256GcmCommitting {
+    /**
+     * 加密消息并计算密钥承诺
+     *
+     * 参数:
+     * @param msg - 要加密的明文
+     * @param aad - 需要认证但不需要加密的关联数据
+     * @param key - 32字节加密密钥
+     *
+     * 返回:
+     * 包含认证标签的密文和密钥承诺的元组
+     */
+    pub fn encrypt(msg: &[u8], aad: &[u8], key: &[u8; KEY_SIZE]) -> (Vec<u8>, [u8; KEY_SIZE]) {
+        let ciphertext = Aes256Gcm::encrypt(msg, aad, &derive_key(key, &[5]));
+        (ciphertext, Self::commitment(key))
+    }
+
+    /**
+     * 检查密钥承诺并解密密文
+     *
+     * 参数:
+     * @param ciphertext - 包含认证标签的密文
+     * @param commitment - 加密时计算的密钥承诺
+     * @param aad - 需要认证的关联数据，必须与加密时使用的相同
+     * @param key - 32字节解密密钥
+     *
+     * 返回:
+     * 成功时返回解密的明文。如果密钥与承诺不符或者认证失败，则返回错误
+     */
+    pub fn decrypt(
+        ciphertext: &[u8],
+        commitment: &[u8; KEY_SIZE],
+        aad: &[u8],
+        key: &[u8; KEY_SIZE],
+    ) -> FastCryptoResult<Vec<u8>> {
+        if commitment != &Self::commitment(key) {
+            return Err(FastCryptoError::GeneralError(
+                "Invalid key commitment".to_string(),
+            ));
+        }
+        Aes256Gcm::decrypt(ciphertext, aad, &derive_key(key, &[5]))
+    }
+
+    /// 计算密钥的承诺
+    fn commitment(key: &[u8; KEY_SIZE]) -> [u8; KEY_SIZE] {
+        hmac_sha3_256(key, &[4])
+    }
+}
+
+/**
+ * 分块的AES-256-GCM认证加密
+ *
+ * 将消息切分为固定大小(`chunk_size`)的块，每个块单独使用AES-256-GCM加密并附带16字节认证标签，
+ * 因此可以在不把整个消息读入内存的情况下流式加密和解密大文件。
+ *
+ * 第i个块的IV由DEM密钥派生: ivᵢ = hmac(hmac(key, 3), i || final)[..16]，
+ * 其中final标记该块是否为最后一个块。这样块的重排、删除和截断都会导致认证失败。
+ * 最后一个块可以比`chunk_size`短（包括空块），因此密文总是至少包含一个认证标签。
+ *
+ * 密文格式: c₀ || t₀ || c₁ || t₁ || ... || cₙ || tₙ
+ */
+pub struct Aes256GcmChunked;
+
+impl Aes256GcmChunked {
+    /// 默认的块大小（64 KiB）
+    pub const DEFAULT_CHUNK_SIZE: u32 = 1 << 16;
+
+    /// 允许的最大块大小（16 MiB）
+    ///
+    /// 块大小来自不可信的加密对象，解密时每个块都要完整读入内存，
+    /// 因此必须限制它，避免构造的对象让解密方分配过多内存。
+    pub const MAX_CHUNK_SIZE: u32 = 1 << 24;
+
+    /// 每个块附带的认证标签长度
+    pub const TAG_SIZE: usize = 16;
+
+    /**
+     * 加密内存中的消息
+     *
+     * 参数:
+     * @param msg - 要加密的明文
+     * @param aad - 需要认证但不需要加密的关联数据，每个块都会认证它
+     * @param key - 32字节加密密钥
+     * @param chunk_size - 明文块大小，必须在1到`MAX_CHUNK_SIZE`之间
+     *
+     * 返回:
+     * 所有块的密文和认证标签的拼接
+     */
+    pub fn encrypt(
+        msg: &[u8],
+        aad: &[u8],
+        key: &[u8; KEY_SIZE],
+        chunk_size: u32,
+    ) -> FastCryptoResult<Vec<u8>> {
+        let mut ciphertext = Vec::new();
+        Self::encrypt_stream(msg, &mut ciphertext, aad, key, chunk_size)?;
+        Ok(ciphertext)
+    }
+
+    /**
+     * 解密内存中的密文
+     *
+     * 参数:
+     * @param ciphertext - `encrypt`或`encrypt_stream`生成的密文
+     * @param aad - 关联数据，必须与加密时使用的相同
+     * @param key - 32字节解密密钥
+     * @param chunk_size - 加密时使用的明文块大小，不能超过`MAX_CHUNK_SIZE`
+     *
+     * 返回:
+     * 成功时返回解密的明文，任何块认证失败时返回错误
+     */
+    pub fn decrypt(
+        ciphertext: &[u8],
+        aad: &[u8],
+        key: &[u8; KEY_SIZE],
+        chunk_size: u32,
+    ) -> FastCryptoResult<Vec<u8>> {
+        let mut msg = Vec::new();
+        Self::decrypt_stream(ciphertext, &mut msg, aad, key, chunk_size)?;
+        Ok(msg)
+    }
+
+    /**
+     * 从`reader`读取明文，逐块加密后写入`writer`
+     *
+     * 任何时刻内存中最多只保存两个块。
+     *
+     * 参数:
+     * @param reader - 明文来源
+     * @param writer - 密文输出
+     * @param aad - 需要认证但不需要加密的关联数据
+     * @param key - 32字节加密密钥
+     * @param chunk_size - 明文块大小，必须在1到`MAX_CHUNK_SIZE`之间
+     *
+     * 返回:
+     * 写入`writer`的密文字节数
+     */
+    pub fn encrypt_stream<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+        aad: &[u8],
+        key: &[u8; KEY_SIZE],
+        chunk_size: u32,
+    ) -> FastCryptoResult<u64> {
+        if !Self::is_valid_chunk_size(chunk_size) {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let cipher = Self::cipher(key);
+        let iv_key = derive_key(key, &[3]);
+        let chunk_size = chunk_size as usize;
+
+        let mut written = 0u64;
+        let mut index = 0u64;
+        let mut current = read_chunk(&mut reader, chunk_size)?;
+        loop {
+            // 只有读满一个块时才需要向后读取，以判断当前块是否为最后一个块
+            let next = if current.len() == chunk_size {
+                read_chunk(&mut reader, chunk_size)?
+            } else {
+                vec![]
+            };
+            let is_final = next.is_empty();
+            let ciphertext = cipher.encrypt_authenticated(
+                &Self::chunk_iv(&iv_key, index, is_final),
+                aad,
+                &current,
+            );
+            writer.write_all(&ciphertext).map_err(io_error)?;
+            written += ciphertext.len() as u64;
+            if is_final {
+                break;
+            }
+            current = next;
+            index += 1;
+        }
+        writer.flush().map_err(io_error)?;
+        Ok(written)
+    }
+
+    /**
+     * 从`reader`读取密文，逐块验证并解密后写入`writer`
+     *
+     * 每个块在写入之前都会单独认证，但被截断的密文只有在读到结尾时才能发现，
+     * 因此如果返回错误，调用者应丢弃已经写入`writer`的所有数据。
+     *
+     * 参数:
+     * @param reader - 密文来源
+     * @param writer - 明文输出
+     * @param aad - 关联数据，必须与加密时使用的相同
+     * @param key - 32字节解密密钥
+     * @param chunk_size - 加密时使用的明文块大小，不能超过`MAX_CHUNK_SIZE`
+     *
+     * 返回:
+     * 写入`writer`的明文字节数
+     */
+    pub fn decrypt_stream<R: Read, W: Write>(
+        mut reader: R,
+        mut writer: W,
+        aad: &[u8],
+        key: &[u8; KEY_SIZE],
+        chunk_size: u32,
+    ) -> FastCryptoResult<u64> {
+        if !Self::is_valid_chunk_size(chunk_size) {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let cipher = Self::cipher(key);
+        let iv_key = derive_key(key, &[3]);
+        let chunk_size = chunk_size as usize + Self::TAG_SIZE;
+
+        let mut written = 0u64;
+        let mut index = 0u64;
+        let mut current = read_chunk(&mut reader, chunk_size)?;
+        loop {
+            if current.len() < Self::TAG_SIZE {
+                return Err(FastCryptoError::InvalidInput);
+            }
+            let next = if current.len() == chunk_size {
+                read_chunk(&mut reader, chunk_size)?
+            } else {
+                vec![]
+            };
+            let is_final = next.is_empty();
+            let msg = cipher.decrypt_authenticated(
+                &Self::chunk_iv(&iv_key, index, is_final),
+                aad,
+                &current,
+            )?;
+            writer.write_all(&msg).map_err(io_error)?;
+            written += msg.len() as u64;
+            if is_final {
+                break;
+            }
+            current = next;
+            index += 1;
+        }
+        writer.flush().map_err(io_error)?;
+        Ok(written)
+    }
+
+    /**
+     * 只解密明文中`range`范围内的字节
+     *
+     * 只有与`range`相交的块会被认证和解密，其余块不会被读取。
+     * 由于最后一个块的位置是根据密文总长度确定的，在块边界处被截断的密文只有在
+     * 请求的范围包含（新的）最后一个块时才能被发现，但返回的数据总是经过认证的。
+     *
+     * 参数:
+     * @param ciphertext - `encrypt`或`encrypt_stream`生成的完整密文
+     * @param aad - 关联数据，必须与加密时使用的相同
+     * @param key - 32字节解密密钥
+     * @param chunk_size - 加密时使用的明文块大小
+     * @param range - 明文中的字节范围，不能超过明文长度
+     *
+     * 返回:
+     * 成功时返回`range`范围内的明文
+     */
+    pub fn decrypt_range(
+        ciphertext: &[u8],
+        aad: &[u8],
+        key: &[u8; KEY_SIZE],
+        chunk_size: u32,
+        range: Range<u64>,
+    ) -> FastCryptoResult<Vec<u8>> {
+        Self::decrypt_range_seekable(Cursor::new(ciphertext), aad, key, chunk_size, range)
+    }
+
+    /**
+     * 从可定位的`reader`中只解密明文中`range`范围内的字节
+     *
+     * 与`decrypt_range`相同，但密文从`reader`中按需读取，适用于单独存储的大文件。
+     * 密文总长度通过定位到`reader`末尾获得。
+     *
+     * 参数:
+     * @param reader - 包含完整密文的可定位数据源
+     * @param aad - 关联数据，必须与加密时使用的相同
+     * @param key - 32字节解密密钥
+     * @param chunk_size - 加密时使用的明文块大小
+     * @param range - 明文中的字节范围，不能超过明文长度
+     *
+     * 返回:
+     * 成功时返回`range`范围内的明文
+     */
+    pub fn decrypt_range_seekable<R: Read + Seek>(
+        mut reader: R,
+        aad: &[u8],
+        key: &[u8; KEY_SIZE],
+        chunk_size: u32,
+        range: Range<u64>,
+    ) -> FastCryptoResult<Vec<u8>> {
+        let ciphertext_len = reader.seek(SeekFrom::End(0)).map_err(io_error)?;
+        let layout = ChunkLayout::new(chunk_size, ciphertext_len)?;
+        if range.start > range.end || range.end > layout.plaintext_len() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        if range.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let cipher = Self::cipher(key);
+        let iv_key = derive_key(key, &[3]);
+        let chunks = layout.chunks_for(&range);
+        let offset = layout.plaintext_range(chunks.start).start;
+
+        let mut msg = Vec::new();
+        for index in chunks {
+            let location = layout.chunk_range(index);
+            let mut chunk = vec![0u8; (location.end - location.start) as usize];
+            reader
+                .seek(SeekFrom::Start(location.start))
+                .map_err(io_error)?;
+            reader.read_exact(&mut chunk).map_err(io_error)?;
+            msg.extend(cipher.decrypt_authenticated(
+                &Self::chunk_iv(&iv_key, index, index == layout.num_chunks() - 1),
+                aad,
+                &chunk,
+            )?);
+        }
+        Ok(msg[(range.start - offset) as usize..(range.end - offset) as usize].to_vec())
+    }
+
+    /// 块大小是否在允许的范围 1..=`MAX_CHUNK_SIZE` 内
+    fn is_valid_chunk_size(chunk_size: u32) -> bool {
+        (1..=Self::MAX_CHUNK_SIZE).contains(&chunk_size)
+    }
+
+    /// 使用给定密钥创建AES-256-GCM实例
+    fn cipher(key: &[u8; KEY_SIZE]) -> ExternalAes256Gcm<U16> {
+        ExternalAes256Gcm::new(AesKey::from_bytes(key).expect("Never fails for 32 byte input"))
+    }
+
+    /// 派生第`index`个块的IV，`is_final`标记该块是否为最后一个块
+    fn chunk_iv(iv_key: &[u8; KEY_SIZE], index: u64, is_final: bool) -> InitializationVector<U16> {
+        let mut input = bcs::to_bytes(&index).expect("Never fails");
+        input.push(is_final as u8);
+        GenericByteArray::from_bytes(&hmac_sha3_256(iv_key, &input)[..16]).expect("fixed length")
+    }
+}
+
+/**
+ * 分块密文的偏移表
+ *
+ * 除最后一个块外，每个块都包含`chunk_size`字节明文和一个认证标签，
+ * 因此第i个块在密文中的偏移为 i * (chunk_size + 16)，在明文中的偏移为 i * chunk_size。
+ * 块的数量以及最后一个块的长度由密文总长度决定，不需要额外存储。
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChunkLayout {
+    chunk_size: u64,
+    ciphertext_len: u64,
+    num_chunks: u64,
+}
+
+impl ChunkLayout {
+    /**
+     * 根据块大小和密文总长度计算偏移表
+     *
+     * 参数:
+     * @param chunk_size - 加密时使用的明文块大小，不能超过`Aes256GcmChunked::MAX_CHUNK_SIZE`
+     * @param ciphertext_len - 密文总长度
+     *
+     * 返回:
+     * 偏移表，如果块大小无效或密文长度不可能由给定块大小产生则返回错误
+     */
+    pub fn new(chunk_size: u32, ciphertext_len: u64) -> FastCryptoResult<Self> {
+        if !Aes256GcmChunked::is_valid_chunk_size(chunk_size) || ciphertext_len == 0 {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let chunk_size = chunk_size as u64;
+        let block_size = chunk_size + Aes256GcmChunked::TAG_SIZE as u64;
+        let num_chunks = ciphertext_len.div_ceil(block_size);
+
+        // 最后一个块至少要包含一个认证标签
+        if ciphertext_len - (num_chunks - 1) * block_size < Aes256GcmChunked::TAG_SIZE as u64 {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(Self {
+            chunk_size,
+            ciphertext_len,
+            num_chunks,
+        })
+    }
+
+    /// 块的数量
+    pub fn num_chunks(&self) -> u64 {
+        self.num_chunks
+    }
+
+    /// 明文总长度
+    pub fn plaintext_len(&self) -> u64 {
+        self.ciphertext_len - self.num_chunks * Aes256GcmChunked::TAG_SIZE as u64
+    }
+
+    /// 第`index`个块（包括认证标签）在密文中的字节范围
+    pub fn chunk_range(&self, index: u64) -> Range<u64> {
+        let block_size = self.chunk_size + Aes256GcmChunked::TAG_SIZE as u64;
+        let start = index * block_size;
+        start..(start + block_size).min(self.ciphertext_len)
+    }
+
+    /// 第`index`个块在明文中的字节范围
+    pub fn plaintext_range(&self, index: u64) -> Range<u64> {
+        let start = index * self.chunk_size;
+        start..(start + self.chunk_size).min(self.plaintext_len())
+    }
+
+    /// 与明文字节范围`range`相交的块的索引范围
+    pub fn chunks_for(&self, range: &Range<u64>) -> Range<u64> {
+        if range.is_empty() {
+            return 0..0;
+        }
+        range.start / self.chunk_size..range.end.div_ceil(self.chunk_size)
+    }
+}
+
+/**
+ * 从reader中读取最多`chunk_size`个字节
+ *
+ * 与`Read::read`不同，只有到达输入末尾时才会返回少于`chunk_size`的字节。
+ * 缓冲区按实际读到的字节数增长，而不是按`chunk_size`预先分配。
+ */
+fn read_chunk<R: Read>(reader: &mut R, chunk_size: usize) -> FastCryptoResult<Vec<u8>> {
+    let mut chunk = Vec::new();
+    reader
+        .take(chunk_size as u64)
+        .read_to_end(&mut chunk)
+        .map_err(io_error)?;
+    Ok(chunk)
+}
+
+/// 将I/O错误转换为FastCryptoError
+fn io_error(e: std::io::Error) -> FastCryptoError {
+    FastCryptoError::GeneralError(e.to_string())
+}
+
 /**
  * 使用CTR模式和HMAC-SHA3-256作为PRF的认证加密
  * 
@@ -218,6 +734,25 @@ fn hmac_sha3_256(key: &[u8; KEY_SIZE], data: &[u8]) -> [u8; KEY_SIZE] {
     .digest
 }
 
+/**
+ * 使用HMAC-SHA3-256从密钥派生子密钥
+ *
+ * 与`hmac_sha3_256`相同，但返回的子密钥在释放时清零，栈上的摘要在复制后立即清零。
+ *
+ * 参数:
+ * @param key - 32字节密钥
+ * @param data - 区分子密钥用途的数据
+ *
+ * 返回:
+ * 32字节子密钥
+ */
+fn derive_key(key: &[u8; KEY_SIZE], data: &[u8]) -> Zeroizing<[u8; KEY_SIZE]> {
+    let mut digest = hmac_sha3_256(key, data);
+    let derived = Zeroizing::new(digest);
+    digest.zeroize();
+    derived
+}
+
 /**
  * 将数字转换为字节数组
  * 
@@ -233,10 +768,249 @@ fn to_bytes(n: usize) -> Vec<u8> {
     bcs::to_bytes(&(n as u64)).expect("Never fails")
 }
 
+/**
+ * 隐藏明文长度的填充策略
+ *
+ * 密文的长度会泄露明文的长度，在某些应用中这已经足以推断内容（例如发出的是哪张牌）。
+ * 填充后的明文为 m || 0x80 || 0x00...（ISO/IEC 7816-4），因此可以无歧义地移除填充，
+ * 不同的策略只决定填充后的长度L'，其中L = |m| + 1:
+ * 1. Bucket(b) - L'为b的整数倍，长度不超过b的消息都具有相同的长度
+ * 2. PowerOfTwo - L'为2的幂，最多泄露log(log(L))比特，但最多浪费接近一半的空间
+ * 3. Padme - 将L的二进制表示中低位的E - S位清零后向上取整，其中E = ⌊log₂L⌋，S = ⌊log₂E⌋ + 1。
+ *    泄露O(log(log(L)))比特，且额外开销不超过约12%（Nikitin等，"Reducing Metadata Leakage from Encrypted Files and Communication with PURBs"）
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Padding {
+    /// 填充到给定字节数的整数倍，必须大于0
+    Bucket(u32),
+    /// 填充到2的幂
+    PowerOfTwo,
+    /// Padmé填充
+    Padme,
+}
+
+impl Padding {
+    /// 填充标记
+    const MARKER: u8 = 0x80;
+
+    /**
+     * 计算填充后的长度
+     *
+     * 参数:
+     * @param length - 明文长度（不包括填充标记）
+     *
+     * 返回:
+     * 填充后的长度，至少为length + 1
+     */
+    pub fn padded_length(&self, length: usize) -> FastCryptoResult<usize> {
+        let length = length.checked_add(1).ok_or(FastCryptoError::InvalidInput)?;
+        match self {
+            Padding::Bucket(0) => Err(FastCryptoError::InvalidInput),
+            Padding::Bucket(bucket) => length
+                .checked_next_multiple_of(*bucket as usize)
+                .ok_or(FastCryptoError::InvalidInput),
+            Padding::PowerOfTwo => length
+                .checked_next_power_of_two()
+                .ok_or(FastCryptoError::InvalidInput),
+            Padding::Padme => {
+                let e = length.ilog2();
+                let s = u32::BITS - e.leading_zeros();
+                let mask = (1usize << (e - s)) - 1;
+                length
+                    .checked_add(mask)
+                    .map(|l| l & !mask)
+                    .ok_or(FastCryptoError::InvalidInput)
+            }
+        }
+    }
+
+    /**
+     * 填充明文
+     *
+     * 参数:
+     * @param data - 明文
+     *
+     * 返回:
+     * 填充后的明文 data || 0x80 || 0x00...
+     */
+    pub fn pad(&self, mut data: Vec<u8>) -> FastCryptoResult<Vec<u8>> {
+        let padded_length = self.padded_length(data.len())?;
+        data.push(Self::MARKER);
+        data.resize(padded_length, 0);
+        Ok(data)
+    }
+
+    /**
+     * 移除填充。填充策略不影响移除的方式
+     *
+     * 参数:
+     * @param padded - 填充后的明文
+     *
+     * 返回:
+     * 原始明文，如果填充格式无效则返回错误
+     */
+    pub fn unpad(mut padded: Vec<u8>) -> FastCryptoResult<Vec<u8>> {
+        let end = padded
+            .iter()
+            .rposition(|b| *b != 0)
+            .filter(|i| padded[*i] == Self::MARKER)
+            .ok_or_else(|| FastCryptoError::GeneralError("Invalid padding".to_string()))?;
+        padded.truncate(end);
+        Ok(padded)
+    }
+}
+
+/// 在读取到的明文末尾追加填充，用于流式加密。填充长度在读到内部`reader`的结尾时才能确定，
+/// 结果与对整个明文调用`Padding::pad`相同
+pub struct PaddedReader<R: Read> {
+    reader: R,
+    padding: Padding,
+    /// 已经读取的明文长度
+    length: usize,
+    /// 读到结尾后，剩余的填充字节数以及是否已经输出填充标记
+    remaining: Option<(usize, bool)>,
+}
+
+impl<R: Read> PaddedReader<R> {
+    /**
+     * 创建填充明文的读取器
+     *
+     * 参数:
+     * @param reader - 明文来源
+     * @param padding - 填充策略
+     *
+     * 返回:
+     * 依次读出明文和填充的读取器
+     */
+    pub fn new(reader: R, padding: Padding) -> Self {
+        Self {
+            reader,
+            padding,
+            length: 0,
+            remaining: None,
+        }
+    }
+}
+
+impl<R: Read> Read for PaddedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let (remaining, marker_written) = match self.remaining {
+            Some(remaining) => remaining,
+            None => {
+                let n = self.reader.read(buf)?;
+                if n > 0 {
+                    self.length += n;
+                    return Ok(n);
+                }
+                let padded_length = self.padding.padded_length(self.length).map_err(|_| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid padding")
+                })?;
+                (padded_length - self.length, false)
+            }
+        };
+        let n = remaining.min(buf.len());
+        buf[..n].fill(0);
+        if !marker_written && n > 0 {
+            buf[0] = Padding::MARKER;
+        }
+        self.remaining = Some((remaining - n, marker_written || n > 0));
+        Ok(n)
+    }
+}
+
+/// 移除写入的明文末尾的填充，用于流式解密。末尾的非零字节和零字节会被暂时保留，
+/// 直到之后写入了新的非零字节，或者在`finish`时确认它们是填充，结果与`Padding::unpad`相同
+pub struct UnpaddedWriter<W: Write> {
+    writer: W,
+    /// 目前最后一个非零字节，它可能是填充标记
+    last: Option<u8>,
+    /// `last`之后（或者还没有非零字节时）的零字节数量
+    zeros: u64,
+    /// 已经写入内部`writer`的字节数
+    written: u64,
+}
+
+impl<W: Write> UnpaddedWriter<W> {
+    /**
+     * 创建移除填充的写入器
+     *
+     * 参数:
+     * @param writer - 明文输出
+     *
+     * 返回:
+     * 移除填充后写入`writer`的写入器
+     */
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            last: None,
+            zeros: 0,
+            written: 0,
+        }
+    }
+
+    /**
+     * 检查保留的字节是否为有效的填充，并刷新内部的`writer`
+     *
+     * 返回:
+     * 写入内部`writer`的明文字节数，如果填充格式无效则返回错误
+     */
+    pub fn finish(mut self) -> FastCryptoResult<u64> {
+        if self.last != Some(Padding::MARKER) {
+            return Err(FastCryptoError::GeneralError("Invalid padding".to_string()));
+        }
+        self.writer.flush().map_err(io_error)?;
+        Ok(self.written)
+    }
+
+    /// 写出保留的字节，之后写入了非零字节，因此它们不是填充
+    fn release(&mut self) -> std::io::Result<()> {
+        if let Some(last) = self.last.take() {
+            self.writer.write_all(&[last])?;
+            self.written += 1;
+        }
+        let zeros = [0u8; 1024];
+        while self.zeros > 0 {
+            let n = self.zeros.min(zeros.len() as u64);
+            self.writer.write_all(&zeros[..n as usize])?;
+            self.zeros -= n;
+            self.written += n;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for UnpaddedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match buf.iter().rposition(|b| *b != 0) {
+            None => self.zeros += buf.len() as u64,
+            Some(i) => {
+                self.release()?;
+                self.writer.write_all(&buf[..i])?;
+                self.written += i as u64;
+                self.last = Some(buf[i]);
+                self.zeros = (buf.len() - i - 1) as u64;
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::dem::{Aes256Gcm, Hmac256Ctr};
+    use crate::dem::{
+        Aes256Gcm, Aes256GcmChunked, Aes256GcmCommitting, ChaCha20Poly1305, ChunkLayout,
+        Hmac256Ctr, PaddedReader, Padding, UnpaddedWriter,
+    };
     use crate::{utils::generate_random_bytes, KEY_SIZE};
+    use fastcrypto::error::FastCryptoError;
     use rand::thread_rng;
 
     /// 测试用的示例消息
@@ -387,4 +1161,389 @@ mod tests {
             (ciphertext, mac)
         );
     }
+
+    /// 测试分块AES-GCM在不同消息长度和块大小下的加密和解密
+    /// 覆盖空消息、恰好为块大小整数倍的消息以及最后一块不满的情况
+    #[test]
+    fn test_aes_gcm_chunked() {
+        let mut rng = thread_rng();
+        let key = generate_random_bytes(&mut rng);
+
+        for chunk_size in [1u32, 7, 16, 100, Aes256GcmChunked::DEFAULT_CHUNK_SIZE] {
+            for len in [0, 1, 6, 7, 8, 32, 99, 100, 101, TEST_MSG.len()] {
+                let msg = &TEST_MSG.repeat(2)[..len];
+                let ciphertext =
+                    Aes256GcmChunked::encrypt(msg, TEST_AAD, &key, chunk_size).unwrap();
+
+                // 每个块（包括最后一个可能为空的块）都附带一个认证标签
+                let chunks = len.div_ceil(chunk_size as usize).max(1);
+                assert_eq!(ciphertext.len(), len + chunks * Aes256GcmChunked::TAG_SIZE);
+
+                let decrypted =
+                    Aes256GcmChunked::decrypt(&ciphertext, TEST_AAD, &key, chunk_size).unwrap();
+                assert_eq!(msg, decrypted.as_slice());
+            }
+        }
+    }
+
+    /// 测试分块AES-GCM的流式接口与内存接口产生相同的结果
+    #[test]
+    fn test_aes_gcm_chunked_stream() {
+        let mut rng = thread_rng();
+        let key = generate_random_bytes(&mut rng);
+
+        let mut ciphertext = Vec::new();
+        let written =
+            Aes256GcmChunked::encrypt_stream(TEST_MSG, &mut ciphertext, TEST_AAD, &key, 10)
+                .unwrap();
+        assert_eq!(written, ciphertext.len() as u64);
+        assert_eq!(
+            ciphertext,
+            Aes256GcmChunked::encrypt(TEST_MSG, TEST_AAD, &key, 10).unwrap()
+        );
+
+        let mut decrypted = Vec::new();
+        let written = Aes256GcmChunked::decrypt_stream(
+            ciphertext.as_slice(),
+            &mut decrypted,
+            TEST_AAD,
+            &key,
+            10,
+        )
+        .unwrap();
+        assert_eq!(written, TEST_MSG.len() as u64);
+        assert_eq!(TEST_MSG, decrypted.as_slice());
+    }
+
+    /// 测试分块AES-GCM能检测到篡改、截断、重排和错误的参数
+    #[test]
+    fn test_aes_gcm_chunked_fail() {
+        let mut rng = thread_rng();
+        let key = generate_random_bytes(&mut rng);
+        let chunk_size = 16u32;
+        let block = chunk_size as usize + Aes256GcmChunked::TAG_SIZE;
+        let msg = &TEST_MSG[..64];
+        let ciphertext = Aes256GcmChunked::encrypt(msg, TEST_AAD, &key, chunk_size).unwrap();
+        assert_eq!(ciphertext.len(), 4 * block);
+
+        // 修改任意一个字节
+        let mut modified = ciphertext.clone();
+        modified[block + 3] ^= 1;
+        assert!(Aes256GcmChunked::decrypt(&modified, TEST_AAD, &key, chunk_size).is_err());
+
+        // 在块边界处截断
+        assert!(
+            Aes256GcmChunked::decrypt(&ciphertext[..3 * block], TEST_AAD, &key, chunk_size)
+                .is_err()
+        );
+
+        // 在块中间截断
+        assert!(Aes256GcmChunked::decrypt(
+            &ciphertext[..3 * block + 5],
+            TEST_AAD,
+            &key,
+            chunk_size
+        )
+        .is_err());
+
+        // 交换前两个块
+        let swapped = [
+            &ciphertext[block..2 * block],
+            &ciphertext[..block],
+            &ciphertext[2 * block..],
+        ]
+        .concat();
+        assert!(Aes256GcmChunked::decrypt(&swapped, TEST_AAD, &key, chunk_size).is_err());
+
+        // 错误的AAD、块大小和空密文
+        assert!(
+            Aes256GcmChunked::decrypt(&ciphertext, b"something else", &key, chunk_size).is_err()
+        );
+        assert!(Aes256GcmChunked::decrypt(&ciphertext, TEST_AAD, &key, 2 * chunk_size).is_err());
+        assert!(Aes256GcmChunked::decrypt(&[], TEST_AAD, &key, chunk_size).is_err());
+        assert!(Aes256GcmChunked::encrypt(msg, TEST_AAD, &key, 0).is_err());
+        assert!(Aes256GcmChunked::encrypt(
+            msg,
+            TEST_AAD,
+            &key,
+            Aes256GcmChunked::MAX_CHUNK_SIZE + 1
+        )
+        .is_err());
+    }
+
+    /// 测试头部中过大的块大小会被拒绝，而不是按块大小分配内存
+    #[test]
+    fn test_aes_gcm_chunked_oversized_chunk_size() {
+        let mut rng = thread_rng();
+        let key = generate_random_bytes(&mut rng);
+        let ciphertext = Aes256GcmChunked::encrypt(TEST_MSG, TEST_AAD, &key, 16).unwrap();
+
+        let mut decrypted = Vec::new();
+        assert!(Aes256GcmChunked::decrypt_stream(
+            ciphertext.as_slice(),
+            &mut decrypted,
+            TEST_AAD,
+            &key,
+            u32::MAX
+        )
+        .is_err());
+        assert!(decrypted.is_empty());
+        assert!(Aes256GcmChunked::decrypt(&ciphertext, TEST_AAD, &key, u32::MAX).is_err());
+        assert!(ChunkLayout::new(u32::MAX, ciphertext.len() as u64).is_err());
+
+        // 最大块大小本身是允许的
+        let ciphertext =
+            Aes256GcmChunked::encrypt(TEST_MSG, TEST_AAD, &key, Aes256GcmChunked::MAX_CHUNK_SIZE)
+                .unwrap();
+        assert_eq!(
+            Aes256GcmChunked::decrypt(
+                &ciphertext,
+                TEST_AAD,
+                &key,
+                Aes256GcmChunked::MAX_CHUNK_SIZE
+            )
+            .unwrap(),
+            TEST_MSG
+        );
+    }
+
+    /// 测试分块密文的偏移表
+    #[test]
+    fn test_chunk_layout() {
+        // 3个完整的块和一个5字节的块
+        let layout = ChunkLayout::new(10, 3 * 26 + 21).unwrap();
+        assert_eq!(layout.num_chunks(), 4);
+        assert_eq!(layout.plaintext_len(), 35);
+        assert_eq!(layout.chunk_range(1), 26..52);
+        assert_eq!(layout.chunk_range(3), 78..99);
+        assert_eq!(layout.plaintext_range(3), 30..35);
+        assert_eq!(layout.chunks_for(&(0..10)), 0..1);
+        assert_eq!(layout.chunks_for(&(9..11)), 0..2);
+        assert_eq!(layout.chunks_for(&(12..35)), 1..4);
+        assert_eq!(layout.chunks_for(&(5..5)), 0..0);
+
+        // 空消息只有一个认证标签
+        let layout = ChunkLayout::new(10, 16).unwrap();
+        assert_eq!(layout.num_chunks(), 1);
+        assert_eq!(layout.plaintext_len(), 0);
+
+        // 最后一个块短于认证标签
+        assert!(ChunkLayout::new(10, 26 + 15).is_err());
+        assert!(ChunkLayout::new(10, 0).is_err());
+        assert!(ChunkLayout::new(0, 26).is_err());
+    }
+
+    /// 测试分块AES-GCM按范围解密
+    /// 验证任意范围的结果与完整解密后截取的结果一致，并且只认证被访问的块
+    #[test]
+    fn test_aes_gcm_chunked_range() {
+        let mut rng = thread_rng();
+        let key = generate_random_bytes(&mut rng);
+        let chunk_size = 10u32;
+        let ciphertext = Aes256GcmChunked::encrypt(TEST_MSG, TEST_AAD, &key, chunk_size).unwrap();
+        let len = TEST_MSG.len() as u64;
+
+        for (start, end) in [
+            (0, 0),
+            (0, 1),
+            (3, 17),
+            (10, 20),
+            (9, 11),
+            (0, len),
+            (len - 1, len),
+            (len, len),
+        ] {
+            assert_eq!(
+                &TEST_MSG[start as usize..end as usize],
+                Aes256GcmChunked::decrypt_range(
+                    &ciphertext,
+                    TEST_AAD,
+                    &key,
+                    chunk_size,
+                    start..end
+                )
+                .unwrap()
+                    .as_slice()
+            );
+        }
+
+        // 超出明文长度的范围
+        assert!(Aes256GcmChunked::decrypt_range(
+            &ciphertext,
+            TEST_AAD,
+            &key,
+            chunk_size,
+            0..len + 1
+        )
+        .is_err());
+
+        // 修改第二个块只影响包含它的范围
+        let mut modified = ciphertext.clone();
+        modified[30] ^= 1;
+        assert!(
+            Aes256GcmChunked::decrypt_range(&modified, TEST_AAD, &key, chunk_size, 0..10).is_ok()
+        );
+        assert!(
+            Aes256GcmChunked::decrypt_range(&modified, TEST_AAD, &key, chunk_size, 5..15).is_err()
+        );
+
+        // 在块边界处截断后，新的最后一个块无法通过认证
+        let truncated = &ciphertext[..4 * 26];
+        assert!(
+            Aes256GcmChunked::decrypt_range(truncated, TEST_AAD, &key, chunk_size, 30..40).is_err()
+        );
+    }
+
+    /// 测试ChaCha20-Poly1305的基本加密和解密功能
+    #[test]
+    fn test_chacha20_poly1305() {
+        let mut rng = thread_rng();
+        let key = generate_random_bytes(&mut rng);
+
+        let ciphertext = ChaCha20Poly1305::encrypt(TEST_MSG, TEST_AAD, &key);
+        assert_eq!(ciphertext.len(), TEST_MSG.len() + 16);
+
+        let decrypted = ChaCha20Poly1305::decrypt(&ciphertext, TEST_AAD, &key).unwrap();
+        assert_eq!(TEST_MSG, decrypted.as_slice());
+    }
+
+    /// 测试ChaCha20-Poly1305在AAD或密文被修改时的失败处理
+    #[test]
+    fn test_chacha20_poly1305_fail() {
+        let mut rng = thread_rng();
+        let key = generate_random_bytes(&mut rng);
+        let ciphertext = ChaCha20Poly1305::encrypt(TEST_MSG, TEST_AAD, &key);
+
+        assert!(ChaCha20Poly1305::decrypt(&ciphertext, b"something else", &key).is_err());
+
+        let mut modified = ciphertext.clone();
+        modified[0] ^= 1;
+        assert!(ChaCha20Poly1305::decrypt(&modified, TEST_AAD, &key).is_err());
+
+        assert!(ChaCha20Poly1305::decrypt(&ciphertext[..15], TEST_AAD, &key).is_err());
+    }
+
+    /// ChaCha20-Poly1305的回归测试
+    /// 使用固定的密钥和输入，确保加密结果与预期一致
+    #[test]
+    fn regression_test_chacha20_poly1305() {
+        let key: [u8; KEY_SIZE] =
+            hex::decode("43041389faab1f789fa56722b1def4c3ec6da22675e9bd8ad7329cd931bc840a")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let ciphertext = hex::decode("390227d59ab543ff11088d4d4f316f1c801165e488b897460bd8fcaa48ab1d14334c24a92004c0b4f103b7cf7e9b609087b1aca1bb564efc5051ce793749baa8c29cb5e88ea54c8c8befdd98ce608782cb29652473da783b01b8ff4a811afc9ed9a23e853f084b00a5174ef9f55885c504fc81").unwrap();
+
+        assert_eq!(
+            TEST_MSG,
+            ChaCha20Poly1305::decrypt(&ciphertext, TEST_AAD, &key)
+                .unwrap()
+                .as_slice()
+        );
+        assert_eq!(
+            ChaCha20Poly1305::encrypt(TEST_MSG, TEST_AAD, &key),
+            ciphertext
+        );
+    }
+
+    /// 测试带有密钥承诺的AES-GCM：其他密钥因承诺不符而被拒绝，修改承诺或AAD时解密失败
+    #[test]
+    fn test_aes_gcm_committing() {
+        let mut rng = thread_rng();
+        let key = generate_random_bytes(&mut rng);
+        let (ciphertext, commitment) = Aes256GcmCommitting::encrypt(TEST_MSG, TEST_AAD, &key);
+        assert_eq!(
+            TEST_MSG,
+            Aes256GcmCommitting::decrypt(&ciphertext, &commitment, TEST_AAD, &key)
+                .unwrap()
+                .as_slice()
+        );
+
+        let other_key = generate_random_bytes(&mut rng);
+        assert!(matches!(
+            Aes256GcmCommitting::decrypt(&ciphertext, &commitment, TEST_AAD, &other_key),
+            Err(FastCryptoError::GeneralError(message)) if message == "Invalid key commitment"
+        ));
+
+        let mut modified = commitment;
+        modified[0] ^= 1;
+        assert!(Aes256GcmCommitting::decrypt(&ciphertext, &modified, TEST_AAD, &key).is_err());
+        assert!(
+            Aes256GcmCommitting::decrypt(&ciphertext, &commitment, b"something else", &key)
+                .is_err()
+        );
+    }
+
+    /// 测试填充策略：填充后的长度符合策略，并且填充可以被移除
+    #[test]
+    fn test_padding() {
+        // (明文长度, 填充后的长度)
+        let cases = [
+            (
+                Padding::Bucket(16),
+                [(0, 16), (15, 16), (16, 32), (100, 112)],
+            ),
+            (Padding::PowerOfTwo, [(0, 1), (7, 8), (8, 16), (1000, 1024)]),
+            (
+                Padding::Padme,
+                [(8, 10), (99, 104), (999, 1024), (9998, 10240)],
+            ),
+        ];
+        for (padding, lengths) in cases {
+            for (length, expected) in lengths {
+                let data = vec![0xffu8; length];
+                let padded = padding.pad(data.clone()).unwrap();
+                assert_eq!(padded.len(), expected);
+                assert_eq!(Padding::unpad(padded).unwrap(), data);
+            }
+        }
+
+        // 以0结尾的明文
+        let data = vec![1, 0, 0];
+        let padded = Padding::PowerOfTwo.pad(data.clone()).unwrap();
+        assert_eq!(padded, vec![1, 0, 0, 0x80]);
+        assert_eq!(Padding::unpad(padded).unwrap(), data);
+
+        assert!(Padding::Bucket(0).pad(vec![]).is_err());
+        assert!(Padding::unpad(vec![]).is_err());
+        assert!(Padding::unpad(vec![1, 0, 0]).is_err());
+    }
+
+    /// 测试流式填充：按小块读取和写入时，结果与`Padding::pad`和`Padding::unpad`相同
+    #[test]
+    fn test_streaming_padding() {
+        use std::io::{Read, Write};
+
+        let messages = [vec![], vec![0; 5], vec![1, 0x80, 0, 0], TEST_MSG.to_vec()];
+        for padding in [Padding::Bucket(16), Padding::PowerOfTwo, Padding::Padme] {
+            for data in &messages {
+                let mut reader = PaddedReader::new(data.as_slice(), padding);
+                let mut padded = vec![];
+                let mut buf = [0u8; 3];
+                loop {
+                    let n = reader.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    padded.extend_from_slice(&buf[..n]);
+                }
+                assert_eq!(padded, padding.pad(data.clone()).unwrap());
+
+                let mut unpadded = vec![];
+                let mut writer = UnpaddedWriter::new(&mut unpadded);
+                for chunk in padded.chunks(3) {
+                    writer.write_all(chunk).unwrap();
+                }
+                assert_eq!(writer.finish().unwrap(), data.len() as u64);
+                assert_eq!(&unpadded, data);
+            }
+        }
+
+        assert!(PaddedReader::new(&[][..], Padding::Bucket(0))
+            .read_to_end(&mut vec![])
+            .is_err());
+        let mut writer = UnpaddedWriter::new(vec![]);
+        writer.write_all(&[1, 0, 0]).unwrap();
+        assert!(writer.finish().is_err());
+    }
 }
@@ -0,0 +1,682 @@
+// Copyright (c), Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/**
+ * 分布式密钥生成 (DKG) 模块
+ *
+ * 本模块实现了基于Feldman可验证秘密共享的联合密钥生成协议（Joint-Feldman DKG，即Pedersen在1991年提出的协议），
+ * 使N个密钥服务器运营者共同生成一个IBE主密钥的(t, N)阈值份额。协议结束后：
+ * - 每个参与方只持有主密钥的一个份额，任何参与方都不知道完整的主密钥
+ * - 主密钥对应的`ibe::PublicKey`是公开的，可以像普通密钥服务器的公钥一样使用
+ * - 每个参与方的份额都有公开的验证密钥 g2^{x_j}，可用于验证部分结果
+ *
+ * 协议流程:
+ * 1. 每个参与方生成用于接收份额的加密密钥对，并公开其公钥（`generate_party_key`）
+ * 2. 每个参与方作为分发者选择随机多项式，广播系数的承诺和加密后的份额（`deal`）
+ * 3. 每个参与方解密发给自己的份额，根据承诺验证后求和得到最终份额（`finalize`）
+ *
+ * 份额使用接收方的G1公钥进行类ECIES加密，分发者的消息使用其参与方私钥进行BLS签名（签名在G2中），
+ * `finalize`拒绝签名无效的消息，因此分发者的消息可以通过未认证的公开信道（例如文件）交换。
+ * 签名以参与方的公钥为信任根：第0轮的公钥必须通过认证信道分发，例如运营者之间当面或通过已知的渠道核对公钥，
+ * 否则攻击者可以替换某个参与方的公钥，从而冒充该参与方分发份额并解密发给它的份额。
+ *
+ * 每次运行协议都使用一个会话ID，它被包含在分发者签名的消息中，`finalize`拒绝会话ID不同的消息。
+ * 参与方的公钥可能在多次运行中重复使用，否则之前某次运行中签名的消息可以在新的运行中被重放。
+ * 委员会必须为每次运行约定一个不同的会话ID，例如运行日期和计数器。
+ *
+ * 本实现没有投诉阶段：如果任何分发者的消息无效，`finalize`会返回指明该分发者的错误，
+ * 委员会应排除该分发者后重新运行协议。
+ *
+ * 已知的局限：本协议没有实现Gennaro等人（GJKR 1999）提出的额外承诺阶段。
+ * 最后发送消息的（rushing）分发者可以在看到其他分发者的承诺之后再选择自己的多项式，
+ * 或者在排除某些分发者后的重新运行中有选择地参与，从而使主公钥的分布偏离均匀分布。
+ * 这不会让攻击者得知主密钥或任何诚实参与方的份额，但主密钥不能被视为均匀随机的。
+ */
+
+use crate::ibe;
+use crate::secret::Secret;
+use crate::utils::xor;
+use fastcrypto::error::FastCryptoError::{GeneralError, InvalidInput};
+use fastcrypto::error::FastCryptoResult;
+use fastcrypto::groups::bls12381::{G1Element, G2Element, Scalar};
+use fastcrypto::groups::{GroupElement, HashToGroupElement, Pairing, Scalar as GenericScalar};
+use fastcrypto::hash::{HashFunction, Sha3_256};
+use fastcrypto::serde_helpers::ToFromByteArray;
+use fastcrypto::traits::AllowedRng;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::iter::repeat_with;
+
+/// 份额加密密钥派生的域分隔标签
+const DST_DKG_SHARE: &[u8] = b"SUI-SEAL-DKG-SHARE-00";
+
+/// 分发者消息签名的域分隔标签
+const DST_DKG_MESSAGE: &[u8] = b"SUI-SEAL-DKG-MESSAGE-00";
+
+/// `simulate`使用的会话ID，所有参与方都在同一进程中，不存在重放的问题
+const SIMULATION_SESSION_ID: &[u8] = b"simulate";
+
+/// 参与方用于接收份额的私钥
+pub type PartySecretKey = Secret<Scalar>;
+
+/// 参与方的公开信息，包括索引和用于接收份额的公钥
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Party {
+    /// 参与方的索引，从1开始，也是其份额在多项式上的求值点
+    pub index: u8,
+    /// 用于加密发给该参与方的份额的公钥
+    pub public_key: G1Element,
+}
+
+/// 发给某个参与方的加密份额
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedShare {
+    /// 接收方的索引
+    pub recipient: u8,
+    /// 临时公钥 g1^r
+    pub ephemeral_key: G1Element,
+    /// 份额与派生密钥异或后的结果
+    pub ciphertext: [u8; 32],
+}
+
+/// 分发者广播的消息
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DealerMessage {
+    /// 本次运行的会话ID，使消息不能在其他运行中重放
+    pub session_id: Vec<u8>,
+    /// 分发者的索引
+    pub dealer: u8,
+    /// 多项式系数的Feldman承诺 g2^{a_k}，第一个元素是分发者对主公钥的贡献
+    pub commitments: Vec<G2Element>,
+    /// 发给每个参与方的加密份额
+    pub encrypted_shares: Vec<EncryptedShare>,
+    /// 分发者使用其参与方私钥对消息的BLS签名，参见`signing_message`
+    pub signature: G2Element,
+}
+
+/// DKG的结果，即某个参与方持有的主密钥份额
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KeyShare {
+    /// 参与方的索引
+    pub index: u8,
+    /// 重构主密钥所需的最小份额数量
+    pub threshold: u8,
+    /// 主密钥的份额 x_j
+    pub share: ibe::MasterKey,
+    /// 主密钥对应的公钥
+    pub public_key: ibe::PublicKey,
+    /// 所有参与方份额的验证密钥 g2^{x_j}
+    pub public_shares: Vec<(u8, ibe::PublicKey)>,
+}
+
+/**
+ * 生成参与方用于接收份额的加密密钥对
+ *
+ * 参数:
+ * @param rng - 随机数生成器
+ * @param index - 参与方的索引，必须非零
+ *
+ * 返回:
+ * 参与方的私钥和公开信息
+ */
+pub fn generate_party_key<R: AllowedRng>(
+    rng: &mut R,
+    index: u8,
+) -> FastCryptoResult<(PartySecretKey, Party)> {
+    if index == 0 {
+        return Err(InvalidInput);
+    }
+    let secret_key = Secret::new(Scalar::rand(rng));
+    let public_key = G1Element::generator() * secret_key.expose();
+    Ok((secret_key, Party { index, public_key }))
+}
+
+/**
+ * 作为分发者生成DKG消息
+ *
+ * 选择一个度为`threshold - 1`的随机多项式，广播其系数的承诺，并为每个参与方加密其份额。
+ * 消息使用分发者的参与方私钥签名，接收方据此确认消息来自该分发者。
+ *
+ * 参数:
+ * @param rng - 随机数生成器
+ * @param secret_key - 分发者自己的参与方私钥，用于签名消息
+ * @param session_id - 本次运行的会话ID
+ * @param dealer - 分发者自己的索引
+ * @param threshold - 重构主密钥所需的最小份额数量
+ * @param parties - 所有参与方（包括分发者自己）的公开信息
+ *
+ * 返回:
+ * 要广播给所有参与方的消息
+ */
+pub fn deal<R: AllowedRng>(
+    rng: &mut R,
+    secret_key: &PartySecretKey,
+    session_id: &[u8],
+    dealer: u8,
+    threshold: u8,
+    parties: &[Party],
+) -> FastCryptoResult<DealerMessage> {
+    validate_parties(threshold, parties)?;
+    let public_key = G1Element::generator() * secret_key.expose();
+    if !parties
+        .iter()
+        .any(|p| p.index == dealer && p.public_key == public_key)
+    {
+        return Err(InvalidInput);
+    }
+
+    let coefficients = repeat_with(|| Secret::new(Scalar::rand(rng)))
+        .take(threshold as usize)
+        .collect_vec();
+    let commitments = coefficients
+        .iter()
+        .map(|a| G2Element::generator() * a.expose())
+        .collect_vec();
+    let encrypted_shares = parties
+        .iter()
+        .map(|party| {
+            let share = Secret::new(evaluate(&coefficients, party.index));
+            encrypt_share(rng, dealer, party, &share)
+        })
+        .collect_vec();
+    let signature = G2Element::hash_to_group_element(&signing_message(
+        parties,
+        session_id,
+        dealer,
+        &commitments,
+        &encrypted_shares,
+    )) * secret_key.expose();
+
+    Ok(DealerMessage {
+        session_id: session_id.to_vec(),
+        dealer,
+        commitments,
+        encrypted_shares,
+        signature,
+    })
+}
+
+/**
+ * 处理所有分发者的消息并计算自己的主密钥份额
+ *
+ * 验证每条消息的会话ID和分发者签名，解密其中发给自己的份额，并根据分发者的承诺验证份额。
+ * 所有份额都有效时，最终份额为所有份额之和，主公钥为所有分发者常数项承诺之和。
+ *
+ * 参数:
+ * @param index - 自己的索引
+ * @param secret_key - 自己用于接收份额的私钥
+ * @param session_id - 本次运行的会话ID，会话ID不同的消息被拒绝
+ * @param threshold - 重构主密钥所需的最小份额数量
+ * @param parties - 所有参与方的公开信息
+ * @param messages - 所有分发者的消息，每个参与方恰好一条
+ *
+ * 返回:
+ * 自己的主密钥份额，如果某个分发者的消息无效则返回指明该分发者的错误
+ */
+pub fn finalize(
+    index: u8,
+    secret_key: &PartySecretKey,
+    session_id: &[u8],
+    threshold: u8,
+    parties: &[Party],
+    messages: &[DealerMessage],
+) -> FastCryptoResult<KeyShare> {
+    validate_parties(threshold, parties)?;
+    let me = parties
+        .iter()
+        .find(|p| p.index == index)
+        .ok_or(InvalidInput)?;
+    if me.public_key != G1Element::generator() * secret_key.expose() {
+        return Err(InvalidInput);
+    }
+    if messages.len() != parties.len()
+        || messages.iter().any(|m| m.session_id != session_id)
+        || !messages
+            .iter()
+            .map(|m| m.dealer)
+            .sorted()
+            .eq(parties.iter().map(|p| p.index).sorted())
+    {
+        return Err(InvalidInput);
+    }
+
+    let mut share = Secret::new(Scalar::zero());
+    for message in messages {
+        let invalid_dealer =
+            || GeneralError(format!("Invalid message from dealer {}", message.dealer));
+        let dealer = parties
+            .iter()
+            .find(|p| p.index == message.dealer)
+            .expect("上面已经检查过每个分发者都是参与方");
+        if !verify_signature(parties, dealer, message) {
+            return Err(invalid_dealer());
+        }
+        if message.commitments.len() != threshold as usize
+            || message.encrypted_shares.len() != parties.len()
+        {
+            return Err(invalid_dealer());
+        }
+        let encrypted_share = message
+            .encrypted_shares
+            .iter()
+            .find(|s| s.recipient == index)
+            .ok_or_else(invalid_dealer)?;
+        let received = decrypt_share(message.dealer, secret_key, encrypted_share)
+            .map_err(|_| invalid_dealer())?;
+        if G2Element::generator() * received.expose()
+            != evaluate_commitments(&message.commitments, index)
+        {
+            return Err(invalid_dealer());
+        }
+        share = Secret::new(*share.expose() + received.expose());
+    }
+
+    // 所有分发者的承诺之和是最终多项式的承诺
+    let commitments = (0..threshold as usize)
+        .map(|k| {
+            messages
+                .iter()
+                .fold(G2Element::zero(), |acc, m| acc + m.commitments[k])
+        })
+        .collect_vec();
+    let public_shares = parties
+        .iter()
+        .map(|p| (p.index, evaluate_commitments(&commitments, p.index)))
+        .collect();
+
+    Ok(KeyShare {
+        index,
+        threshold,
+        share,
+        public_key: commitments[0],
+        public_shares,
+    })
+}
+
+/**
+ * 在本地模拟完整的DKG协议
+ *
+ * 所有参与方在同一进程中运行，用于测试和演示。
+ *
+ * 参数:
+ * @param rng - 随机数生成器
+ * @param threshold - 重构主密钥所需的最小份额数量
+ * @param number_of_parties - 参与方数量，索引为1..=number_of_parties
+ *
+ * 返回:
+ * 每个参与方的主密钥份额
+ */
+pub fn simulate<R: AllowedRng>(
+    rng: &mut R,
+    threshold: u8,
+    number_of_parties: u8,
+) -> FastCryptoResult<Vec<KeyShare>> {
+    let (secret_keys, parties): (Vec<_>, Vec<_>) = (1..=number_of_parties)
+        .map(|i| generate_party_key(rng, i))
+        .collect::<FastCryptoResult<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    let messages = parties
+        .iter()
+        .zip(&secret_keys)
+        .map(|(p, sk)| deal(rng, sk, SIMULATION_SESSION_ID, p.index, threshold, &parties))
+        .collect::<FastCryptoResult<Vec<_>>>()?;
+    parties
+        .iter()
+        .zip(&secret_keys)
+        .map(|(p, sk)| {
+            finalize(
+                p.index,
+                sk,
+                SIMULATION_SESSION_ID,
+                threshold,
+                &parties,
+                &messages,
+            )
+        })
+        .collect()
+}
+
+/**
+ * 计算在0处插值的拉格朗日系数
+ *
+ * 对于给定的求值点集合，返回系数λ_i，使得对于任意度小于点数的多项式f，
+ * 有 f(0) = Σ λ_i · f(i)。
+ *
+ * 参数:
+ * @param indices - 互不相同的非零求值点
+ *
+ * 返回:
+ * 与`indices`顺序相同的拉格朗日系数
+ */
+pub fn lagrange_coefficients(indices: &[u8]) -> FastCryptoResult<Vec<Scalar>> {
+    lagrange_coefficients_at(0, indices)
+}
+
+/// 计算在点x处插值的拉格朗日系数，即 f(x) = Σ λ_i · f(i)
+pub(crate) fn lagrange_coefficients_at(x: u8, indices: &[u8]) -> FastCryptoResult<Vec<Scalar>> {
+    if indices.is_empty() || indices.contains(&0) || !indices.iter().all_unique() {
+        return Err(InvalidInput);
+    }
+    let x = Scalar::from(x as u128);
+    indices
+        .iter()
+        .map(|&i| {
+            let (numerator, denominator) = indices.iter().filter(|&&j| j != i).fold(
+                (Scalar::generator(), Scalar::generator()),
+                |(num, den), &j| {
+                    let j = Scalar::from(j as u128);
+                    (num * (j - x), den * (j - Scalar::from(i as u128)))
+                },
+            );
+            numerator / denominator
+        })
+        .collect()
+}
+
+/// 检查参数和参与方列表是否有效
+pub(crate) fn validate_parties(threshold: u8, parties: &[Party]) -> FastCryptoResult<()> {
+    if threshold == 0
+        || threshold as usize > parties.len()
+        || parties.iter().any(|p| p.index == 0)
+        || !parties.iter().map(|p| p.index).all_unique()
+    {
+        return Err(InvalidInput);
+    }
+    Ok(())
+}
+
+/// 使用Horner方法在点x处计算多项式的值
+pub(crate) fn evaluate(coefficients: &[Secret<Scalar>], x: u8) -> Scalar {
+    let x = Scalar::from(x as u128);
+    coefficients
+        .iter()
+        .rev()
+        .fold(Scalar::zero(), |acc, a| acc * x + a.expose())
+}
+
+/// 在群的指数上计算多项式在点x处的值，即 Σ C_k · x^k
+pub(crate) fn evaluate_commitments(commitments: &[G2Element], x: u8) -> G2Element {
+    let x = Scalar::from(x as u128);
+    commitments
+        .iter()
+        .rev()
+        .fold(G2Element::zero(), |acc, c| acc * x + c)
+}
+
+/// 分发者签名的消息：域分隔标签、按索引排序的参与方列表和会话ID（使签名只在本次DKG中有效），
+/// 以及消息中除签名以外的其他字段
+fn signing_message(
+    parties: &[Party],
+    session_id: &[u8],
+    dealer: u8,
+    commitments: &[G2Element],
+    encrypted_shares: &[EncryptedShare],
+) -> Vec<u8> {
+    let parties = parties.iter().sorted_by_key(|p| p.index).collect_vec();
+    let mut message = DST_DKG_MESSAGE.to_vec();
+    message.extend(
+        bcs::to_bytes(&(parties, session_id, dealer, commitments, encrypted_shares))
+            .expect("Never fails"),
+    );
+    message
+}
+
+/// 验证分发者对消息的签名，即 e(g1, signature) = e(public_key, H(message))
+fn verify_signature(parties: &[Party], dealer: &Party, message: &DealerMessage) -> bool {
+    let hash = G2Element::hash_to_group_element(&signing_message(
+        parties,
+        &message.session_id,
+        message.dealer,
+        &message.commitments,
+        &message.encrypted_shares,
+    ));
+    G1Element::generator().pairing(&message.signature) == dealer.public_key.pairing(&hash)
+}
+
+/// 从共享的DH值派生份额加密密钥
+fn share_key(dealer: u8, recipient: u8, ephemeral_key: &G1Element, shared: &G1Element) -> [u8; 32] {
+    let mut hash = Sha3_256::default();
+    hash.update(DST_DKG_SHARE);
+    hash.update([dealer, recipient]);
+    hash.update(ephemeral_key.to_byte_array());
+    hash.update(shared.to_byte_array());
+    hash.finalize().digest
+}
+
+/// 使用接收方的公钥加密份额
+pub(crate) fn encrypt_share<R: AllowedRng>(
+    rng: &mut R,
+    dealer: u8,
+    recipient: &Party,
+    share: &Secret<Scalar>,
+) -> EncryptedShare {
+    let r = Secret::new(Scalar::rand(rng));
+    let ephemeral_key = G1Element::generator() * r.expose();
+    let key = Secret::new(share_key(
+        dealer,
+        recipient.index,
+        &ephemeral_key,
+        &(recipient.public_key * r.expose()),
+    ));
+    let plaintext = Secret::new(share.expose().to_byte_array());
+    EncryptedShare {
+        recipient: recipient.index,
+        ephemeral_key,
+        ciphertext: xor(plaintext.expose(), key.expose()),
+    }
+}
+
+/// 使用自己的私钥解密份额
+pub(crate) fn decrypt_share(
+    dealer: u8,
+    secret_key: &PartySecretKey,
+    encrypted_share: &EncryptedShare,
+) -> FastCryptoResult<Secret<Scalar>> {
+    let key = Secret::new(share_key(
+        dealer,
+        encrypted_share.recipient,
+        &encrypted_share.ephemeral_key,
+        &(encrypted_share.ephemeral_key * secret_key.expose()),
+    ));
+    let plaintext = Secret::new(xor(&encrypted_share.ciphertext, key.expose()));
+    Scalar::from_byte_array(plaintext.expose()).map(Secret::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        create_full_id, seal_decrypt, seal_encrypt, EncryptionInput, IBEPublicKeys,
+        IBEUserSecretKeys, ObjectID,
+    };
+    use rand::thread_rng;
+
+    /// 从足够数量的份额重构主密钥，仅用于测试
+    fn reconstruct(shares: &[&KeyShare]) -> Scalar {
+        let indices = shares.iter().map(|s| s.index).collect_vec();
+        lagrange_coefficients(&indices)
+            .unwrap()
+            .iter()
+            .zip(shares)
+            .fold(Scalar::zero(), |acc, (l, s)| acc + *l * s.share.expose())
+    }
+
+    /// 测试完整的DKG流程
+    /// 1. 所有参与方得到相同的主公钥和验证密钥
+    /// 2. 每个份额与其验证密钥一致
+    /// 3. 任意阈值数量的份额都重构出与主公钥对应的主密钥，少于阈值则不能
+    /// 4. 生成的主密钥可以用于Seal加密和解密
+    #[test]
+    fn test_dkg() {
+        let shares = simulate(&mut thread_rng(), 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        let public_key = shares[0].public_key;
+        assert!(shares.iter().all(|s| s.public_key == public_key));
+        assert!(shares
+            .iter()
+            .all(|s| s.public_shares == shares[0].public_shares));
+        for share in &shares {
+            assert_eq!(
+                share.public_shares[share.index as usize - 1],
+                (share.index, ibe::public_key_from_master_key(&share.share))
+            );
+        }
+
+        let master_key = ibe::MasterKey::new(reconstruct(&[&shares[0], &shares[2], &shares[4]]));
+        assert_eq!(ibe::public_key_from_master_key(&master_key), public_key);
+        assert_eq!(
+            reconstruct(&[&shares[1], &shares[2], &shares[3]]),
+            *master_key.expose()
+        );
+        assert_ne!(reconstruct(&[&shares[0], &shares[1]]), *master_key.expose());
+
+        // 使用DKG生成的公钥加密，使用重构的主密钥解密
+        let package_id = ObjectID::random();
+        let service = ObjectID::random();
+        let (encrypted, _) = seal_encrypt(
+            package_id,
+            vec![1, 2, 3],
+            vec![service],
+            &IBEPublicKeys::BonehFranklinBLS12381(vec![public_key]),
+            1,
+            EncryptionInput::Aes256Gcm {
+                data: b"Hello, committee!".to_vec(),
+                aad: None,
+            },
+        )
+        .unwrap();
+        let usk = ibe::extract(&master_key, &create_full_id(&package_id, &[1, 2, 3]));
+        assert_eq!(
+            seal_decrypt(
+                &encrypted,
+                &IBEUserSecretKeys::BonehFranklinBLS12381([(service, usk)].into()),
+                None
+            )
+            .unwrap(),
+            b"Hello, committee!"
+        );
+    }
+
+    const SESSION_ID: &[u8] = b"test-dkg-1";
+
+    /// 使用给定的私钥重新签名消息，用于模拟恶意或冒充的分发者
+    fn sign(message: &mut DealerMessage, parties: &[Party], secret_key: &PartySecretKey) {
+        message.signature = G2Element::hash_to_group_element(&signing_message(
+            parties,
+            &message.session_id,
+            message.dealer,
+            &message.commitments,
+            &message.encrypted_shares,
+        )) * secret_key.expose();
+    }
+
+    /// 测试无效或伪造的消息被拒绝，并且错误指明了出错的分发者
+    #[test]
+    fn test_invalid_dealer() {
+        let mut rng = thread_rng();
+        let (secret_keys, parties): (Vec<_>, Vec<_>) = (1..=3)
+            .map(|i| generate_party_key(&mut rng, i).unwrap())
+            .unzip();
+        let mut messages = parties
+            .iter()
+            .zip(&secret_keys)
+            .map(|(p, sk)| deal(&mut rng, sk, SESSION_ID, p.index, 2, &parties).unwrap())
+            .collect_vec();
+        assert!(finalize(1, &secret_keys[0], SESSION_ID, 2, &parties, &messages).is_ok());
+
+        // 分发者只能使用自己的私钥签名
+        assert!(deal(&mut rng, &secret_keys[1], SESSION_ID, 1, 2, &parties).is_err());
+
+        // 冒充分发者2：使用其他私钥签名的消息被拒绝
+        let (forger, _) = generate_party_key(&mut rng, 2).unwrap();
+        let mut forged = messages.clone();
+        sign(&mut forged[1], &parties, &forger);
+        match finalize(1, &secret_keys[0], SESSION_ID, 2, &parties, &forged) {
+            Err(GeneralError(e)) => assert_eq!(e, "Invalid message from dealer 2"),
+            _ => panic!(),
+        }
+
+        // 参与方的顺序不影响签名
+        let reversed = parties.iter().rev().cloned().collect_vec();
+        assert!(finalize(1, &secret_keys[0], SESSION_ID, 2, &reversed, &messages).is_ok());
+
+        // 错误的私钥或缺少消息
+        assert!(finalize(1, &secret_keys[1], SESSION_ID, 2, &parties, &messages).is_err());
+        assert!(finalize(1, &secret_keys[0], SESSION_ID, 2, &parties, &messages[..2]).is_err());
+
+        // 分发者2发给参与方1的份额在传输中被篡改，签名不再有效，所有参与方都拒绝该消息
+        let mut tampered = messages.clone();
+        tampered[1].encrypted_shares[0].ciphertext[31] ^= 1;
+        for (index, sk) in [(1, &secret_keys[0]), (3, &secret_keys[2])] {
+            match finalize(index, sk, SESSION_ID, 2, &parties, &tampered) {
+                Err(GeneralError(e)) => assert_eq!(e, "Invalid message from dealer 2"),
+                _ => panic!(),
+            }
+        }
+
+        // 恶意的分发者2签名了发给参与方1的错误份额，参与方1根据承诺发现错误
+        messages[1].encrypted_shares[0].ciphertext[31] ^= 1;
+        sign(&mut messages[1], &parties, &secret_keys[1]);
+        match finalize(1, &secret_keys[0], SESSION_ID, 2, &parties, &messages) {
+            Err(GeneralError(e)) => assert_eq!(e, "Invalid message from dealer 2"),
+            _ => panic!(),
+        }
+        // 其他参与方不受影响
+        assert!(finalize(3, &secret_keys[2], SESSION_ID, 2, &parties, &messages).is_ok());
+
+        // 分发者3的承诺数量与阈值不符
+        messages[2].commitments.pop();
+        assert!(finalize(3, &secret_keys[2], SESSION_ID, 2, &parties, &messages).is_err());
+    }
+
+    /// 测试之前某次运行中的消息不能在使用相同参与方的新运行中重放
+    #[test]
+    fn test_replayed_dealer_message() {
+        let mut rng = thread_rng();
+        let (secret_keys, parties): (Vec<_>, Vec<_>) = (1..=3)
+            .map(|i| generate_party_key(&mut rng, i).unwrap())
+            .unzip();
+        let deal_all = |rng: &mut rand::rngs::ThreadRng, session_id: &[u8]| {
+            parties
+                .iter()
+                .zip(&secret_keys)
+                .map(|(p, sk)| deal(rng, sk, session_id, p.index, 2, &parties).unwrap())
+                .collect_vec()
+        };
+        let old_run = deal_all(&mut rng, b"test-dkg-0");
+        let mut messages = deal_all(&mut rng, SESSION_ID);
+        assert!(finalize(1, &secret_keys[0], SESSION_ID, 2, &parties, &messages).is_ok());
+
+        // 分发者2的消息来自之前的运行
+        messages[1] = old_run[1].clone();
+        assert!(matches!(
+            finalize(1, &secret_keys[0], SESSION_ID, 2, &parties, &messages),
+            Err(InvalidInput)
+        ));
+
+        // 只修改会话ID无法通过签名验证
+        messages[1].session_id = SESSION_ID.to_vec();
+        match finalize(1, &secret_keys[0], SESSION_ID, 2, &parties, &messages) {
+            Err(GeneralError(e)) => assert_eq!(e, "Invalid message from dealer 2"),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn test_lagrange_coefficients() {
+        // f(x) = 5 + 3x + 2x^2
+        let f = |x: u128| Scalar::from(5 + 3 * x + 2 * x * x);
+        let indices = [1u8, 4, 7];
+        let coefficients = lagrange_coefficients(&indices).unwrap();
+        let value = indices
+            .iter()
+            .zip(&coefficients)
+            .fold(Scalar::zero(), |acc, (&i, l)| acc + f(i as u128) * l);
+        assert_eq!(value, Scalar::from(5u128));
+
+        assert!(lagrange_coefficients(&[]).is_err());
+        assert!(lagrange_coefficients(&[0, 1]).is_err());
+        assert!(lagrange_coefficients(&[1, 1]).is_err());
+    }
+}
@@ -11,19 +11,27 @@
  * 1. 密钥对生成 - 创建公钥、私钥和验证密钥
  * 2. 消息加密 - 使用接收者的公钥加密消息
  * 3. 消息解密 - 使用私钥解密密文
+ * 4. 加密正确性证明 - 非交互式Chaum-Pedersen证明，证明被加密的消息等于 base^s，
+ *    其中s是与公开的验证元素 vg^s 对应的秘密标量
  * 
  * 该实现是通用的，可以与任何满足GroupElement接口的群一起使用，
  * 比如椭圆曲线群。
  */
 
-use fastcrypto::groups::{GroupElement, Scalar};
+use crate::secret::Secret;
+use fastcrypto::error::{FastCryptoError, FastCryptoResult};
+use fastcrypto::groups::{FiatShamirChallenge, GroupElement, Scalar};
+use fastcrypto::hash::{HashFunction, Sha3_512};
 use fastcrypto::traits::AllowedRng;
 use serde::{Deserialize, Serialize};
 
+/// 加密正确性证明的Fiat-Shamir挑战的域分隔标签
+const DST_ENCRYPTION_PROOF: &[u8] = b"SUI-SEAL-ELGAMAL-PROOF-00";
+
 /// ElGamal私钥
-/// 由群G的标量元素组成
-#[derive(Serialize, Deserialize)]
-pub struct SecretKey<G: GroupElement>(G::ScalarType);
+/// 由群G的标量元素组成，释放时自动清零。运算只通过引用使用私钥标量，不会在栈上留下副本
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SecretKey<G: GroupElement>(Secret<G::ScalarType>);
 
 /// ElGamal公钥
 /// 由群G的元素组成，等于生成元乘以私钥
@@ -42,6 +50,15 @@ pub struct VerificationKey<G: GroupElement>(G);
 #[derive(Serialize, Deserialize)]
 pub struct Encryption<G: GroupElement>(pub G, pub G);
 
+/// 加密正确性证明
+/// 包含Fiat-Shamir挑战c以及对加密随机数r和秘密标量s的响应 (z_r, z_s)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptionProof<S> {
+    challenge: S,
+    z_r: S,
+    z_s: S,
+}
+
 /**
  * 生成ElGamal密钥对
  * 
@@ -57,12 +74,10 @@ pub struct Encryption<G: GroupElement>(pub G, pub G);
 pub fn genkey<G: GroupElement, VG: GroupElement<ScalarType = G::ScalarType>, R: AllowedRng>(
     rng: &mut R,
 ) -> (SecretKey<G>, PublicKey<G>, VerificationKey<VG>) {
-    let sk = G::ScalarType::rand(rng);
-    (
-        SecretKey(sk),
-        PublicKey(G::generator() * sk),
-        VerificationKey(VG::generator() * sk),
-    )
+    let sk = Secret::new(G::ScalarType::rand(rng));
+    let pk = PublicKey(G::generator() * sk.expose());
+    let vk = VerificationKey(VG::generator() * sk.expose());
+    (SecretKey(sk), pk, vk)
 }
 
 /**
@@ -104,5 +119,158 @@ pub fn encrypt<G: GroupElement, R: AllowedRng>(
  * 解密后的明文消息（群元素）
  */
 pub fn decrypt<G: GroupElement>(sk: &SecretKey<G>, e: &Encryption<G>) -> G {
-    e.1 - e.0 * sk.0
+    e.1 - e.0 * sk.0.expose()
+}
+
+/**
+ * 加密 base^s 并生成加密正确性证明
+ *
+ * 对于公开的 base、vg^s 和接收者公钥pk，证明者知道 (r, s) 使得：
+ * c1 = g^r，c2 = pk^r + base^s，public_key = vg^s，其中vg是群VG的生成元。
+ * 验证者因此可以在不解密的情况下确认密文中的消息就是 base^s。
+ *
+ * 参数:
+ * @param rng - 随机数生成器
+ * @param base - 被秘密标量相乘的公开群元素
+ * @param secret - 秘密标量s
+ * @param public_key - 与秘密标量对应的公开验证元素 vg^s
+ * @param pk - 接收者的公钥
+ *
+ * 返回:
+ * 加密结果和加密正确性证明
+ */
+pub fn encrypt_and_prove<G, VG, R>(
+    rng: &mut R,
+    base: &G,
+    secret: &G::ScalarType,
+    public_key: &VG,
+    pk: &PublicKey<G>,
+) -> (Encryption<G>, EncryptionProof<G::ScalarType>)
+where
+    G: GroupElement + Serialize,
+    VG: GroupElement<ScalarType = G::ScalarType> + Serialize,
+    G::ScalarType: FiatShamirChallenge,
+    R: AllowedRng,
+{
+    let r = Secret::new(G::ScalarType::rand(rng));
+    let encryption = Encryption(
+        G::generator() * r.expose(),
+        pk.0 * r.expose() + *base * secret,
+    );
+
+    let w_r = Secret::new(G::ScalarType::rand(rng));
+    let w_s = Secret::new(G::ScalarType::rand(rng));
+    let challenge = fiat_shamir_challenge(
+        base,
+        public_key,
+        pk,
+        &encryption,
+        &(
+            G::generator() * w_r.expose(),
+            pk.0 * w_r.expose() + *base * w_s.expose(),
+            VG::generator() * w_s.expose(),
+        ),
+    );
+    let proof = EncryptionProof {
+        challenge,
+        z_r: challenge * r.expose() + w_r.expose(),
+        z_s: challenge * secret + w_s.expose(),
+    };
+    (encryption, proof)
+}
+
+/**
+ * 验证加密正确性证明
+ *
+ * 从响应和挑战重新计算证明者的承诺，并检查重新计算的挑战与证明中的一致。
+ *
+ * 参数:
+ * @param encryption - 要验证的密文
+ * @param proof - 加密正确性证明
+ * @param base - 被秘密标量相乘的公开群元素
+ * @param public_key - 与秘密标量对应的公开验证元素 vg^s
+ * @param pk - 加密时使用的接收者公钥
+ *
+ * 返回:
+ * 如果证明有效则返回Ok，否则返回错误
+ */
+pub fn verify_encryption<G, VG>(
+    encryption: &Encryption<G>,
+    proof: &EncryptionProof<G::ScalarType>,
+    base: &G,
+    public_key: &VG,
+    pk: &PublicKey<G>,
+) -> FastCryptoResult<()>
+where
+    G: GroupElement + Serialize,
+    VG: GroupElement<ScalarType = G::ScalarType> + Serialize,
+    G::ScalarType: FiatShamirChallenge,
+{
+    let commitments = (
+        G::generator() * proof.z_r - encryption.0 * proof.challenge,
+        pk.0 * proof.z_r + *base * proof.z_s - encryption.1 * proof.challenge,
+        VG::generator() * proof.z_s - *public_key * proof.challenge,
+    );
+    if fiat_shamir_challenge(base, public_key, pk, encryption, &commitments) != proof.challenge {
+        return Err(FastCryptoError::GeneralError(
+            "Invalid encryption proof".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 将证明的陈述和承诺哈希为挑战标量
+fn fiat_shamir_challenge<G, VG>(
+    base: &G,
+    public_key: &VG,
+    pk: &PublicKey<G>,
+    encryption: &Encryption<G>,
+    commitments: &(G, G, VG),
+) -> G::ScalarType
+where
+    G: GroupElement + Serialize,
+    VG: GroupElement<ScalarType = G::ScalarType> + Serialize,
+    G::ScalarType: FiatShamirChallenge,
+{
+    let mut hash = Sha3_512::default();
+    hash.update(DST_ENCRYPTION_PROOF);
+    hash.update(
+        bcs::to_bytes(&(base, public_key, &pk.0, encryption, commitments)).expect("Never fails"),
+    );
+    G::ScalarType::fiat_shamir_reduction_to_group_element(&hash.finalize().digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastcrypto::groups::bls12381::{G1Element, G2Element, Scalar as BlsScalar};
+    use fastcrypto::groups::HashToGroupElement;
+    use rand::thread_rng;
+
+    /// 测试加密正确性证明
+    /// 1. 有效的证明可以通过验证，并且密文解密为 base^s
+    /// 2. 使用其他底数、公钥或篡改过的密文时验证失败
+    #[test]
+    fn test_encryption_proof() {
+        let mut rng = thread_rng();
+        let (sk, pk, _vk) = genkey::<G1Element, G2Element, _>(&mut rng);
+        let secret = BlsScalar::rand(&mut rng);
+        let public_key = G2Element::generator() * secret;
+        let base = G1Element::hash_to_group_element(b"id");
+
+        let (encryption, proof) = encrypt_and_prove(&mut rng, &base, &secret, &public_key, &pk);
+        assert!(verify_encryption(&encryption, &proof, &base, &public_key, &pk).is_ok());
+        assert_eq!(decrypt(&sk, &encryption), base * secret);
+
+        let other_base = G1Element::hash_to_group_element(b"other id");
+        assert!(verify_encryption(&encryption, &proof, &other_base, &public_key, &pk).is_err());
+        let other_key = G2Element::generator() * BlsScalar::rand(&mut rng);
+        assert!(verify_encryption(&encryption, &proof, &base, &other_key, &pk).is_err());
+        let (_, other_pk, _) = genkey::<G1Element, G2Element, _>(&mut rng);
+        assert!(verify_encryption(&encryption, &proof, &base, &public_key, &other_pk).is_err());
+
+        // 把密文中的消息替换为其他值
+        let modified = Encryption(encryption.0, encryption.1 + G1Element::generator());
+        assert!(verify_encryption(&modified, &proof, &base, &public_key, &pk).is_err());
+    }
 }
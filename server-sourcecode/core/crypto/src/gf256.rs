@@ -11,7 +11,7 @@
  * 例如，二进制值10011010可以看作多项式x⁷ + x⁴ + x³ + x。
  * 
  * 主要特点:
- * 1. 基于查表优化的乘法和除法操作
+ * 1. 常数时间的乘法和除法操作，不使用以秘密值为索引的查表，避免缓存计时侧信道
  * 2. 基于异或的加法和减法操作
  * 3. 支持所有基本域运算，包括乘法逆元
  * 4. 提供随机元素生成功能
@@ -35,80 +35,56 @@ use std::{
 #[derive(Clone, Debug, Copy, Eq, PartialEq, Hash)]
 pub struct GF256(pub(crate) u8);
 
-/// 指数表 Eᵢ = gⁱ，其中g = 0x03是域乘法群的生成元。
-/// 该表用于高效计算乘法：如果a = g^m，b = g^n，则a*b = g^(m+n)
-const EXP: [u8; 255] = [
-    0x01, 0x03, 0x05, 0x0f, 0x11, 0x33, 0x55, 0xff, 0x1a, 0x2e, 0x72, 0x96, 0xa1, 0xf8, 0x13, 0x35,
-    0x5f, 0xe1, 0x38, 0x48, 0xd8, 0x73, 0x95, 0xa4, 0xf7, 0x02, 0x06, 0x0a, 0x1e, 0x22, 0x66, 0xaa,
-    0xe5, 0x34, 0x5c, 0xe4, 0x37, 0x59, 0xeb, 0x26, 0x6a, 0xbe, 0xd9, 0x70, 0x90, 0xab, 0xe6, 0x31,
-    0x53, 0xf5, 0x04, 0x0c, 0x14, 0x3c, 0x44, 0xcc, 0x4f, 0xd1, 0x68, 0xb8, 0xd3, 0x6e, 0xb2, 0xcd,
-    0x4c, 0xd4, 0x67, 0xa9, 0xe0, 0x3b, 0x4d, 0xd7, 0x62, 0xa6, 0xf1, 0x08, 0x18, 0x28, 0x78, 0x88,
-    0x83, 0x9e, 0xb9, 0xd0, 0x6b, 0xbd, 0xdc, 0x7f, 0x81, 0x98, 0xb3, 0xce, 0x49, 0xdb, 0x76, 0x9a,
-    0xb5, 0xc4, 0x57, 0xf9, 0x10, 0x30, 0x50, 0xf0, 0x0b, 0x1d, 0x27, 0x69, 0xbb, 0xd6, 0x61, 0xa3,
-    0xfe, 0x19, 0x2b, 0x7d, 0x87, 0x92, 0xad, 0xec, 0x2f, 0x71, 0x93, 0xae, 0xe9, 0x20, 0x60, 0xa0,
-    0xfb, 0x16, 0x3a, 0x4e, 0xd2, 0x6d, 0xb7, 0xc2, 0x5d, 0xe7, 0x32, 0x56, 0xfa, 0x15, 0x3f, 0x41,
-    0xc3, 0x5e, 0xe2, 0x3d, 0x47, 0xc9, 0x40, 0xc0, 0x5b, 0xed, 0x2c, 0x74, 0x9c, 0xbf, 0xda, 0x75,
-    0x9f, 0xba, 0xd5, 0x64, 0xac, 0xef, 0x2a, 0x7e, 0x82, 0x9d, 0xbc, 0xdf, 0x7a, 0x8e, 0x89, 0x80,
-    0x9b, 0xb6, 0xc1, 0x58, 0xe8, 0x23, 0x65, 0xaf, 0xea, 0x25, 0x6f, 0xb1, 0xc8, 0x43, 0xc5, 0x54,
-    0xfc, 0x1f, 0x21, 0x63, 0xa5, 0xf4, 0x07, 0x09, 0x1b, 0x2d, 0x77, 0x99, 0xb0, 0xcb, 0x46, 0xca,
-    0x45, 0xcf, 0x4a, 0xde, 0x79, 0x8b, 0x86, 0x91, 0xa8, 0xe3, 0x3e, 0x42, 0xc6, 0x51, 0xf3, 0x0e,
-    0x12, 0x36, 0x5a, 0xee, 0x29, 0x7b, 0x8d, 0x8c, 0x8f, 0x8a, 0x85, 0x94, 0xa7, 0xf2, 0x0d, 0x17,
-    0x39, 0x4b, 0xdd, 0x7c, 0x84, 0x97, 0xa2, 0xfd, 0x1c, 0x24, 0x6c, 0xb4, 0xc7, 0x52, 0xf6,
-];
-
-/// 对数表 Lᵢ = LOG[i + 1]，使得g^Lᵢ = i，其中g = 0x03。
-/// 该表用于高效计算域元素的离散对数，便于乘法和除法操作
-const LOG: [u8; 255] = [
-    0x00, 0x19, 0x01, 0x32, 0x02, 0x1a, 0xc6, 0x4b, 0xc7, 0x1b, 0x68, 0x33, 0xee, 0xdf, 0x03, 0x64,
-    0x04, 0xe0, 0x0e, 0x34, 0x8d, 0x81, 0xef, 0x4c, 0x71, 0x08, 0xc8, 0xf8, 0x69, 0x1c, 0xc1, 0x7d,
-    0xc2, 0x1d, 0xb5, 0xf9, 0xb9, 0x27, 0x6a, 0x4d, 0xe4, 0xa6, 0x72, 0x9a, 0xc9, 0x09, 0x78, 0x65,
-    0x2f, 0x8a, 0x05, 0x21, 0x0f, 0xe1, 0x24, 0x12, 0xf0, 0x82, 0x45, 0x35, 0x93, 0xda, 0x8e, 0x96,
-    0x8f, 0xdb, 0xbd, 0x36, 0xd0, 0xce, 0x94, 0x13, 0x5c, 0xd2, 0xf1, 0x40, 0x46, 0x83, 0x38, 0x66,
-    0xdd, 0xfd, 0x30, 0xbf, 0x06, 0x8b, 0x62, 0xb3, 0x25, 0xe2, 0x98, 0x22, 0x88, 0x91, 0x10, 0x7e,
-    0x6e, 0x48, 0xc3, 0xa3, 0xb6, 0x1e, 0x42, 0x3a, 0x6b, 0x28, 0x54, 0xfa, 0x85, 0x3d, 0xba, 0x2b,
-    0x79, 0x0a, 0x15, 0x9b, 0x9f, 0x5e, 0xca, 0x4e, 0xd4, 0xac, 0xe5, 0xf3, 0x73, 0xa7, 0x57, 0xaf,
-    0x58, 0xa8, 0x50, 0xf4, 0xea, 0xd6, 0x74, 0x4f, 0xae, 0xe9, 0xd5, 0xe7, 0xe6, 0xad, 0xe8, 0x2c,
-    0xd7, 0x75, 0x7a, 0xeb, 0x16, 0x0b, 0xf5, 0x59, 0xcb, 0x5f, 0xb0, 0x9c, 0xa9, 0x51, 0xa0, 0x7f,
-    0x0c, 0xf6, 0x6f, 0x17, 0xc4, 0x49, 0xec, 0xd8, 0x43, 0x1f, 0x2d, 0xa4, 0x76, 0x7b, 0xb7, 0xcc,
-    0xbb, 0x3e, 0x5a, 0xfb, 0x60, 0xb1, 0x86, 0x3b, 0x52, 0xa1, 0x6c, 0xaa, 0x55, 0x29, 0x9d, 0x97,
-    0xb2, 0x87, 0x90, 0x61, 0xbe, 0xdc, 0xfc, 0xbc, 0x95, 0xcf, 0xcd, 0x37, 0x3f, 0x5b, 0xd1, 0x53,
-    0x39, 0x84, 0x3c, 0x41, 0xa2, 0x6d, 0x47, 0x14, 0x2a, 0x9e, 0x5d, 0x56, 0xf2, 0xd3, 0xab, 0x44,
-    0x11, 0x92, 0xd9, 0x23, 0x20, 0x2e, 0x89, 0xb4, 0x7c, 0xb8, 0x26, 0x77, 0x99, 0xe3, 0xa5, 0x67,
-    0x4a, 0xed, 0xde, 0xc5, 0x31, 0xfe, 0x18, 0x0d, 0x63, 0x8c, 0x80, 0xc0, 0xf7, 0x70, 0x07,
-];
+/// Rijndael不可约多项式x⁸ + x⁴ + x³ + x + 1去掉最高位后的值
+const REDUCTION: u8 = 0x1b;
 
 /**
- * 计算GF256元素的离散对数
- * 
- * 返回非零元素x在有限域中的离散对数值
- * 即：如果x = g^y，则返回y
- * 
+ * 常数时间的GF256乘法
+ *
+ * 使用移位和异或逐位计算无进位乘法，并在每一步模不可约多项式约简。
+ * 分支和内存访问都不依赖于输入的值，因此不会通过缓存或分支预测泄露秘密。
+ *
  * 参数:
- * @param x - 要计算对数的非零元素
- * 
+ * @param a - 第一个乘数
+ * @param b - 第二个乘数
+ *
  * 返回:
- * 元素的离散对数值
- * 
- * 注意：对于x=0，函数会panic，因为0没有对数
+ * a * b
  */
-fn log(x: &GF256) -> u16 {
-    assert_ne!(x.0, 0);
-    LOG[x.0 as usize - 1] as u16
+fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut result = 0u8;
+    for _ in 0..8 {
+        // 如果b的最低位为1，则加上a
+        result ^= a & (b & 1).wrapping_neg();
+        // a乘以x，如果最高位溢出则约简
+        let carry = (a >> 7).wrapping_neg();
+        a = (a << 1) ^ (carry & REDUCTION);
+        b >>= 1;
+    }
+    result
 }
 
 /**
- * 计算GF256中的指数
- * 
- * 返回g^x，其中g=0x03是有限域的生成元
- * 
+ * 常数时间的GF256乘法逆元
+ *
+ * 由于乘法群的阶为255，x⁻¹ = x²⁵⁴。指数是公开的常数，因此平方-乘算法的执行路径不依赖于x。
+ * 对于x = 0，结果为0。
+ *
  * 参数:
- * @param x - 指数值
- * 
+ * @param x - 要求逆的元素
+ *
  * 返回:
- * 计算得到的GF256元素
+ * x的乘法逆元，x = 0时为0
  */
-fn exp(x: u16) -> GF256 {
-    GF256(EXP[x as usize % 255])
+fn inv(x: u8) -> u8 {
+    // 254 = 0b11111110，所以x²⁵⁴ = x² · x⁴ · ... · x¹²⁸
+    let mut square = x;
+    let mut result = 1u8;
+    for _ in 1..8 {
+        square = mul(square, square);
+        result = mul(result, square);
+    }
+    result
 }
 
 /**
@@ -167,28 +143,22 @@ impl Neg for &GF256 {
 /**
  * GF256元素的乘法实现
  * 
- * 使用查表方法高效实现乘法：
- * 如果a = g^m，b = g^n，则a*b = g^(m+n)
+ * 使用常数时间的移位和异或实现，不使用以元素值为索引的查表
  */
 impl Mul<&GF256> for &GF256 {
     type Output = GF256;
 
     fn mul(self, rhs: &GF256) -> Self::Output {
-        if self.0 == 0 || rhs.0 == 0 {
-            GF256::zero()
-        } else {
-            exp(log(self) + log(rhs))
-        }
+        GF256(mul(self.0, rhs.0))
     }
 }
 
 /**
  * GF256元素的除法实现
  * 
- * 使用查表方法高效实现除法：
- * 如果a = g^m，b = g^n，则a/b = g^(m-n)
+ * 计算a * b⁻¹，其中逆元b⁻¹ = b²⁵⁴使用常数时间的乘法计算
  * 
- * 注意：除数不能为0，否则会返回错误
+ * 注意：除数不能为0，否则会返回错误。只有除数是否为0会影响执行路径
  */
 #[allow(clippy::suspicious_arithmetic_impl)]
 impl Div<&GF256> for &GF256 {
@@ -197,10 +167,8 @@ impl Div<&GF256> for &GF256 {
     fn div(self, rhs: &GF256) -> Self::Output {
         if rhs.0 == 0 {
             return Err(InvalidInput);
-        } else if self.0 == 0 {
-            return Ok(GF256::zero());
         }
-        Ok(exp(255 + log(self) - log(rhs)))
+        Ok(GF256(mul(self.0, inv(rhs.0))))
     }
 }
 
@@ -288,4 +256,43 @@ mod tests {
         assert_eq!(&a * &b, GF256(0x01));
         assert_eq!((&a / &b).unwrap(), GF256(0xb5));
     }
+
+    /// 测试常数时间实现与基于对数表的实现对所有输入给出相同的结果。
+    /// 对数表由生成元g = 0x03构造，只使用乘以x（xtime）和异或，不依赖于被测试的乘法
+    #[test]
+    fn test_against_log_tables() {
+        let xtime = |x: u8| (x << 1) ^ if x & 0x80 != 0 { 0x1b } else { 0 };
+        let mut exp = [0u8; 255];
+        let mut log = [0u16; 256];
+        let mut x = 1u8;
+        for (i, e) in exp.iter_mut().enumerate() {
+            *e = x;
+            log[x as usize] = i as u16;
+            x ^= xtime(x);
+        }
+        assert_eq!(x, 1);
+
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                let (ga, gb) = (GF256(a), GF256(b));
+                let expected_product = if a == 0 || b == 0 {
+                    0
+                } else {
+                    exp[((log[a as usize] + log[b as usize]) % 255) as usize]
+                };
+                assert_eq!(&ga * &gb, GF256(expected_product));
+
+                if b == 0 {
+                    assert!((&ga / &gb).is_err());
+                } else {
+                    let expected_quotient = if a == 0 {
+                        0
+                    } else {
+                        exp[((255 + log[a as usize] - log[b as usize]) % 255) as usize]
+                    };
+                    assert_eq!((&ga / &gb).unwrap(), GF256(expected_quotient));
+                }
+            }
+        }
+    }
 }
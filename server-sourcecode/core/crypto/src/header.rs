@@ -0,0 +1,174 @@
+// Copyright (c), Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/**
+ * 加密对象头部扩展模块
+ *
+ * 从版本1开始，`EncryptedObject`在末尾包含一个可扩展的头部区域，由一组TLV（类型-长度-值）
+ * 扩展组成。每个扩展由16位的标签和任意长度的值组成，BCS编码为 tag || uleb128(len) || value。
+ *
+ * 标签的最高位表示该扩展是否为关键扩展：
+ * - 非关键扩展（例如创建时间、内容类型提示）可以被不认识它们的解析器安全地忽略
+ * - 关键扩展会改变解密的语义，不认识它们的解析器必须拒绝整个对象
+ *
+ * 扩展必须按标签严格递增排列，这样每个对象只有唯一的编码。
+ *
+ * 扩展本身默认不被认证：只有参与对称密钥派生的扩展（上下文绑定、填充和压缩）被间接认证，
+ * 其他扩展可以被修改而不被发现。使用上下文绑定时头部摘要覆盖所有关键扩展，
+ * 非关键扩展在任何情况下都不被认证，参见`EncryptedObject`。
+ */
+
+use fastcrypto::error::{FastCryptoError, FastCryptoResult};
+use serde::{Deserialize, Serialize};
+
+/// 标签的最高位，表示不认识该扩展的解析器必须拒绝整个对象
+pub const CRITICAL_BIT: u16 = 0x8000;
+
+/// 对象的创建时间，值为BCS编码的u64（自Unix纪元起的毫秒数）
+pub const TAG_CREATED_AT: u16 = 0x0001;
+
+/// 明文的内容类型提示（例如MIME类型），值为UTF-8字符串
+pub const TAG_CONTENT_TYPE: u16 = 0x0002;
+
+/// 数据加密机制的算法标识符，值为一个字节，参见`Ciphertext::algorithm_id`
+pub const TAG_DEM_ALGORITHM: u16 = 0x0003;
+
+/// 多身份对象中除主身份以外的其他身份，值为BCS编码的`IdentityEncryption`列表。
+/// 这是关键扩展：只认识主身份的解析器可能错误地认为持有其他身份私钥的用户无法解密
+pub const TAG_ALTERNATIVE_IDENTITIES: u16 = CRITICAL_BIT | 0x0001;
+
+/// 主身份的密钥服务器上的访问树，值为BCS编码的`tss::AccessTree`。
+/// 这是关键扩展：不认识它的解析器会把共享当作单层阈值共享，从而重建出错误的密钥
+pub const TAG_ACCESS_TREE: u16 = CRITICAL_BIT | 0x0002;
+
+/// 数据加密机制的额外认证数据绑定了头部摘要，值为空，参见`EncryptedObject::context_digest`。
+/// 这是关键扩展：不认识它的解析器会使用错误的额外认证数据，从而无法解密
+pub const TAG_CONTEXT_BINDING: u16 = CRITICAL_BIT | 0x0003;
+
+/// 明文在加密前被填充，值为BCS编码的`dem::Padding`。
+/// 这是关键扩展：不认识它的解析器会把填充的字节当作明文的一部分
+pub const TAG_PADDING: u16 = CRITICAL_BIT | 0x0004;
+
+/// 明文在加密前（以及填充前）被压缩，值为BCS编码的`compression::Compression`。
+/// 这是关键扩展：不认识它的解析器会返回压缩后的数据
+pub const TAG_COMPRESSION: u16 = CRITICAL_BIT | 0x0005;
+
+/// 本实现支持的关键扩展标签
+const KNOWN_CRITICAL_TAGS: &[u16] = &[
+    TAG_ALTERNATIVE_IDENTITIES,
+    TAG_ACCESS_TREE,
+    TAG_CONTEXT_BINDING,
+    TAG_PADDING,
+    TAG_COMPRESSION,
+];
+
+/// 加密对象头部中的一个TLV扩展
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Extension {
+    /// 扩展的标签，最高位为关键扩展标志
+    pub tag: u16,
+    /// 扩展的值，其含义由标签决定
+    pub value: Vec<u8>,
+}
+
+impl Extension {
+    /// 判断该扩展是否为关键扩展
+    pub fn is_critical(&self) -> bool {
+        self.tag & CRITICAL_BIT != 0
+    }
+}
+
+/**
+ * 检查扩展列表是否有效
+ *
+ * 参数:
+ * @param extensions - 要检查的扩展列表
+ *
+ * 返回:
+ * 如果标签不是严格递增的，或者包含不认识的关键扩展，则返回错误
+ */
+pub fn validate_extensions(extensions: &[Extension]) -> FastCryptoResult<()> {
+    if extensions.windows(2).any(|w| w[0].tag >= w[1].tag) {
+        return Err(FastCryptoError::InvalidInput);
+    }
+    if let Some(ext) = extensions
+        .iter()
+        .find(|ext| ext.is_critical() && !KNOWN_CRITICAL_TAGS.contains(&ext.tag))
+    {
+        return Err(FastCryptoError::GeneralError(format!(
+            "Unsupported critical extension {:#06x}",
+            ext.tag
+        )));
+    }
+    Ok(())
+}
+
+/**
+ * 查找给定标签的扩展值
+ *
+ * 参数:
+ * @param extensions - 扩展列表
+ * @param tag - 要查找的标签
+ *
+ * 返回:
+ * 扩展的值，如果不存在则返回None
+ */
+pub fn find_extension(extensions: &[Extension], tag: u16) -> Option<&[u8]> {
+    extensions
+        .iter()
+        .find(|ext| ext.tag == tag)
+        .map(|ext| ext.value.as_slice())
+}
+
+/**
+ * 插入或替换给定标签的扩展，并保持标签有序
+ *
+ * 参数:
+ * @param extensions - 扩展列表
+ * @param tag - 扩展的标签
+ * @param value - 扩展的值
+ */
+pub fn set_extension(extensions: &mut Vec<Extension>, tag: u16, value: Vec<u8>) {
+    match extensions.binary_search_by_key(&tag, |ext| ext.tag) {
+        Ok(i) => extensions[i].value = value,
+        Err(i) => extensions.insert(i, Extension { tag, value }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extensions() {
+        let mut extensions = vec![];
+        set_extension(&mut extensions, TAG_CONTENT_TYPE, b"video/mp4".to_vec());
+        set_extension(&mut extensions, TAG_CREATED_AT, vec![1]);
+        set_extension(&mut extensions, TAG_CREATED_AT, vec![2]);
+        assert_eq!(
+            extensions.iter().map(|ext| ext.tag).collect::<Vec<_>>(),
+            vec![TAG_CREATED_AT, TAG_CONTENT_TYPE]
+        );
+        assert_eq!(
+            find_extension(&extensions, TAG_CREATED_AT),
+            Some(&[2u8][..])
+        );
+        assert_eq!(find_extension(&extensions, TAG_DEM_ALGORITHM), None);
+        assert!(validate_extensions(&extensions).is_ok());
+
+        // 未知的非关键扩展可以被忽略
+        set_extension(&mut extensions, 0x7fff, vec![]);
+        assert!(validate_extensions(&extensions).is_ok());
+
+        // 未知的关键扩展必须被拒绝
+        set_extension(&mut extensions, CRITICAL_BIT | 0x7fff, vec![]);
+        assert!(validate_extensions(&extensions).is_err());
+
+        // 无序或重复的标签
+        let ext = Extension {
+            tag: TAG_CREATED_AT,
+            value: vec![],
+        };
+        assert!(validate_extensions(&[ext.clone(), ext]).is_err());
+    }
+}
@@ -18,22 +18,36 @@
  * 2. 需要可信第三方（密钥生成中心）生成用户私钥
  * 3. 支持批量加密和确定性加密
  * 4. 提供密钥持有证明机制
+ * 5. 阈值提取：主密钥份额的持有者分别提取部分用户私钥，聚合后得到普通的用户私钥
+ * 6. 提取并加密用户私钥，同时证明被加密的正是 H(ID)^s
+ * 7. 为固定的身份和公钥集合预计算配对，用于在同一身份下批量加密许多对象
+ * 8. 使用随机线性组合批量验证多个用户私钥
+ *
+ * 启用`parallel`特性时，对多个公钥的配对计算在线程池上并行执行。
  */
 
-use crate::utils::xor;
+use crate::dkg::lagrange_coefficients;
+use crate::elgamal;
+use crate::secret::Secret;
+use crate::utils::{maybe_par_iter, xor};
 use crate::{DST_POP, KEY_SIZE};
 use fastcrypto::error::FastCryptoError::{GeneralError, InvalidInput};
 use fastcrypto::error::FastCryptoResult;
 use fastcrypto::groups::bls12381::{G1Element, G2Element, GTElement, Scalar};
-use fastcrypto::groups::{GroupElement, HashToGroupElement, Pairing, Scalar as GenericScalar};
+use fastcrypto::groups::{
+    GroupElement, HashToGroupElement, MultiScalarMul, Pairing, Scalar as GenericScalar,
+};
 use fastcrypto::hmac::{hkdf_sha3_256, HkdfIkm};
 use fastcrypto::serde_helpers::ToFromByteArray;
 use fastcrypto::traits::AllowedRng;
 use fastcrypto::traits::ToFromBytes;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::collections::HashMap;
 use sui_types::base_types::ObjectID;
 
-/// 主密钥类型，用于生成系统参数和用户私钥
-pub type MasterKey = Scalar;
+/// 主密钥类型，用于生成系统参数和用户私钥，释放时自动清零
+pub type MasterKey = Secret<Scalar>;
 /// 系统公钥类型，公开发布
 pub type PublicKey = G2Element;
 /// 用户私钥类型，由主密钥和用户身份派生
@@ -47,6 +61,12 @@ pub type Ciphertext = [u8; KEY_SIZE];
 /// 随机性类型，用于确定性加密
 pub type Randomness = Scalar;
 
+/// 部分用户私钥，由主密钥份额派生，包含份额索引和 H(ID)^{x_j}
+pub type PartialUserSecretKey = (u8, UserSecretKey);
+
+/// 加密用户私钥的正确性证明
+pub type EncryptedUserSecretKeyProof = elgamal::EncryptionProof<Scalar>;
+
 /// 密钥派生的附加信息类型
 /// 包含密钥服务器的对象ID和分享索引
 pub type Info = (ObjectID, u8);
@@ -64,8 +84,9 @@ pub type Info = (ObjectID, u8);
  * 由主密钥和公钥组成的元组
  */
 pub fn generate_key_pair<R: AllowedRng>(rng: &mut R) -> (MasterKey, PublicKey) {
-    let sk = Scalar::rand(rng);
-    (sk, public_key_from_master_key(&sk))
+    let sk = MasterKey::new(Scalar::rand(rng));
+    let pk = public_key_from_master_key(&sk);
+    (sk, pk)
 }
 
 /**
@@ -81,7 +102,7 @@ pub fn generate_key_pair<R: AllowedRng>(rng: &mut R) -> (MasterKey, PublicKey) {
  * 派生的公钥
  */
 pub fn public_key_from_master_key(master_key: &MasterKey) -> PublicKey {
-    G2Element::generator() * master_key
+    G2Element::generator() * master_key.expose()
 }
 
 /**
@@ -98,7 +119,7 @@ pub fn public_key_from_master_key(master_key: &MasterKey) -> PublicKey {
  * 用户的私钥
  */
 pub fn extract(master_key: &MasterKey, id: &[u8]) -> UserSecretKey {
-    G1Element::hash_to_group_element(id) * master_key
+    G1Element::hash_to_group_element(id) * master_key.expose()
 }
 
 /**
@@ -129,6 +150,209 @@ pub fn verify_user_secret_key(
     }
 }
 
+/// 待验证的用户私钥，包含私钥、用户身份ID和对应密钥服务器的公钥
+pub type UserSecretKeyClaim<'a> = (&'a UserSecretKey, &'a [u8], &'a PublicKey);
+
+/**
+ * 批量验证多个用户私钥
+ * 
+ * 使用随机线性组合将所有配对等式合并为一个：
+ * e(Σ rᵢ·USKᵢ, g) = Π_pk e(Σ_{PKᵢ = pk} rᵢ·H(IDᵢ), pk)，
+ * 因此只需要一个配对加上每个不同公钥一个配对，而不是每个私钥两个配对。
+ * 相同的身份只哈希到曲线一次。如果批量验证失败，则逐个验证以找出无效的私钥。
+ * 
+ * 参数:
+ * @param rng - 随机数生成器，用于生成线性组合的系数
+ * @param keys - 待验证的用户私钥
+ * 
+ * 返回:
+ * 如果所有私钥都有效则返回Ok(())，否则返回包含无效私钥位置的错误
+ */
+pub fn verify_user_secret_keys_batch<R: AllowedRng>(
+    rng: &mut R,
+    keys: &[UserSecretKeyClaim],
+) -> FastCryptoResult<()> {
+    if keys.is_empty() {
+        return Ok(());
+    }
+
+    let coefficients = keys.iter().map(|_| Scalar::rand(rng)).collect::<Vec<_>>();
+    let user_secret_keys = keys.iter().map(|(usk, _, _)| **usk).collect::<Vec<_>>();
+    let lhs = G1Element::multi_scalar_mul(&coefficients, &user_secret_keys)?
+        .pairing(&G2Element::generator());
+
+    // 按公钥分组，每组计算一个配对
+    let mut hashes = HashMap::new();
+    let mut groups: Vec<(&PublicKey, Vec<Scalar>, Vec<G1Element>)> = vec![];
+    for ((_, id, pk), r) in keys.iter().zip(coefficients) {
+        let gid = *hashes
+            .entry(*id)
+            .or_insert_with(|| G1Element::hash_to_group_element(id));
+        match groups.iter_mut().find(|(group_pk, _, _)| *group_pk == *pk) {
+            Some((_, scalars, points)) => {
+                scalars.push(r);
+                points.push(gid);
+            }
+            None => groups.push((pk, vec![r], vec![gid])),
+        }
+    }
+    let rhs = groups
+        .iter()
+        .map(|(pk, scalars, points)| {
+            G1Element::multi_scalar_mul(scalars, points).map(|p| p.pairing(pk))
+        })
+        .collect::<FastCryptoResult<Vec<_>>>()?
+        .into_iter()
+        .fold(GTElement::zero(), |acc, x| acc + x);
+
+    if lhs == rhs {
+        return Ok(());
+    }
+    Err(GeneralError(format!(
+        "Invalid user secret keys with indices {:?}",
+        find_invalid_user_secret_keys(keys)
+    )))
+}
+
+/**
+ * 逐个验证用户私钥，返回无效私钥在输入中的位置
+ * 
+ * 参数:
+ * @param keys - 待验证的用户私钥
+ * 
+ * 返回:
+ * 无效私钥的位置列表
+ */
+pub fn find_invalid_user_secret_keys(keys: &[UserSecretKeyClaim]) -> Vec<usize> {
+    maybe_par_iter!(0..keys.len())
+        .filter(|&i| {
+            let (usk, id, pk) = keys[i];
+            verify_user_secret_key(usk, id, pk).is_err()
+        })
+        .collect()
+}
+
+/**
+ * 提取用户私钥，使用客户端的ElGamal公钥加密，并证明被加密的是正确的用户私钥
+ * 
+ * 参数:
+ * @param rng - 随机数生成器
+ * @param master_key - 系统的主密钥
+ * @param public_key - 与主密钥对应的系统公钥
+ * @param id - 用户身份ID
+ * @param enc_pk - 客户端的ElGamal公钥
+ * 
+ * 返回:
+ * 加密的用户私钥和加密正确性证明
+ */
+pub fn extract_and_encrypt<R: AllowedRng>(
+    rng: &mut R,
+    master_key: &MasterKey,
+    public_key: &PublicKey,
+    id: &[u8],
+    enc_pk: &elgamal::PublicKey<UserSecretKey>,
+) -> (
+    elgamal::Encryption<UserSecretKey>,
+    EncryptedUserSecretKeyProof,
+) {
+    elgamal::encrypt_and_prove(
+        rng,
+        &G1Element::hash_to_group_element(id),
+        master_key.expose(),
+        public_key,
+        enc_pk,
+    )
+}
+
+/**
+ * 在解密之前验证加密的用户私钥
+ * 
+ * 证明有效时，使用对应的ElGamal私钥解密得到的一定是 H(ID)^s，
+ * 即能通过`verify_user_secret_key`验证的用户私钥。
+ * 
+ * 参数:
+ * @param encrypted_key - 加密的用户私钥
+ * @param proof - 加密正确性证明
+ * @param id - 用户身份ID
+ * @param public_key - 密钥服务器的系统公钥
+ * @param enc_pk - 请求时使用的ElGamal公钥
+ * 
+ * 返回:
+ * 如果证明有效则返回Ok(())，否则返回错误
+ */
+pub fn verify_encrypted_user_secret_key(
+    encrypted_key: &elgamal::Encryption<UserSecretKey>,
+    proof: &EncryptedUserSecretKeyProof,
+    id: &[u8],
+    public_key: &PublicKey,
+    enc_pk: &elgamal::PublicKey<UserSecretKey>,
+) -> FastCryptoResult<()> {
+    elgamal::verify_encryption(
+        encrypted_key,
+        proof,
+        &G1Element::hash_to_group_element(id),
+        public_key,
+        enc_pk,
+    )
+}
+
+/**
+ * 使用主密钥份额提取部分用户私钥
+ * 
+ * 主密钥份额通常由分布式密钥生成得到（参见`dkg`模块）。
+ * 部分用户私钥计算为：H(ID)^{x_j}，其中x_j是索引为j的主密钥份额。
+ * 
+ * 参数:
+ * @param master_share - 主密钥份额
+ * @param index - 份额索引
+ * @param id - 用户身份ID
+ * 
+ * 返回:
+ * 带有份额索引的部分用户私钥
+ */
+pub fn extract_partial(master_share: &MasterKey, index: u8, id: &[u8]) -> PartialUserSecretKey {
+    (index, extract(master_share, id))
+}
+
+/**
+ * 验证部分用户私钥的有效性
+ * 
+ * 与`verify_user_secret_key`相同，但使用份额的验证密钥 g2^{x_j} 代替系统公钥。
+ * 
+ * 参数:
+ * @param partial - 要验证的部分用户私钥
+ * @param id - 用户身份ID
+ * @param public_share - 对应份额的验证密钥
+ * 
+ * 返回:
+ * 如果部分私钥有效则返回Ok(())，否则返回错误
+ */
+pub fn verify_partial(
+    partial: &PartialUserSecretKey,
+    id: &[u8],
+    public_share: &PublicKey,
+) -> FastCryptoResult<()> {
+    verify_user_secret_key(&partial.1, id, public_share)
+}
+
+/**
+ * 聚合部分用户私钥
+ * 
+ * 在指数上进行拉格朗日插值：USK = Σ λ_j · H(ID)^{x_j} = H(ID)^s，
+ * 因此结果与使用完整主密钥`extract`得到的用户私钥相同，客户端无法区分两者。
+ * 需要至少阈值数量的有效部分私钥，否则结果是错误的，但不会返回错误。
+ * 
+ * 参数:
+ * @param partials - 部分用户私钥集合，索引必须互不相同且非零
+ * 
+ * 返回:
+ * 聚合后的用户私钥
+ */
+pub fn aggregate_partials(partials: &[PartialUserSecretKey]) -> FastCryptoResult<UserSecretKey> {
+    let (indices, points): (Vec<u8>, Vec<UserSecretKey>) = partials.iter().copied().unzip();
+    G1Element::multi_scalar_mul(&lagrange_coefficients(&indices)?, &points)
+}
+
 /**
  * 批量确定性加密
  * 
@@ -162,7 +386,7 @@ pub fn encrypt_batched_deterministic(
     let nonce = G2Element::generator() * randomness;
     Ok((
         nonce,
-        (0..batch_size)
+        maybe_par_iter!(0..batch_size)
             .map(|i| {
                 xor(
                     &kdf(&gid_r.pairing(&public_keys[i]), &nonce, &gid, &infos[i]),
@@ -173,6 +397,76 @@ pub fn encrypt_batched_deterministic(
     ))
 }
 
+/// 为固定的身份和公钥集合预计算的配对 e(H(id), pk_i)。
+/// 由于 e(H(id)^r, pk_i) = e(H(id), pk_i)^r，使用它加密时不需要哈希到曲线和计算配对，
+/// 只需要在GT中做一次标量乘法，适用于在同一身份下批量加密许多对象。
+pub struct PreparedIdentity {
+    gid: G1Element,
+    pairings: Vec<GTElement>,
+}
+
+/**
+ * 为给定的身份和公钥预计算配对
+ * 
+ * 参数:
+ * @param public_keys - 接收者的公钥数组
+ * @param id - 用户身份ID
+ * 
+ * 返回:
+ * 预计算的身份
+ */
+pub fn prepare_identity(public_keys: &[PublicKey], id: &[u8]) -> PreparedIdentity {
+    let gid = G1Element::hash_to_group_element(id);
+    let pairings = maybe_par_iter!(public_keys)
+        .map(|pk| gid.pairing(pk))
+        .collect();
+    PreparedIdentity { gid, pairings }
+}
+
+/**
+ * 使用预计算的身份进行批量确定性加密
+ * 
+ * 结果与使用相同身份和公钥调用`encrypt_batched_deterministic`完全相同。
+ * 
+ * 参数:
+ * @param randomness - 加密使用的随机性
+ * @param plaintexts - 要加密的明文数组，与预计算时的公钥顺序相同
+ * @param prepared - 预计算的身份
+ * @param infos - 用于密钥派生的附加信息
+ * 
+ * 返回:
+ * 成功时返回(随机数, 密文数组)，失败时返回错误
+ */
+pub fn encrypt_batched_prepared(
+    randomness: &Randomness,
+    plaintexts: &[Plaintext],
+    prepared: &PreparedIdentity,
+    infos: &[Info],
+) -> FastCryptoResult<(Nonce, Vec<Ciphertext>)> {
+    let batch_size = plaintexts.len();
+    if batch_size != prepared.pairings.len() || batch_size != infos.len() {
+        return Err(InvalidInput);
+    }
+
+    let nonce = G2Element::generator() * randomness;
+    Ok((
+        nonce,
+        maybe_par_iter!(0..batch_size)
+            .map(|i| {
+                xor(
+                    &kdf(
+                        &(prepared.pairings[i] * randomness),
+                        &nonce,
+                        &prepared.gid,
+                        &infos[i],
+                    ),
+                    &plaintexts[i],
+                )
+            })
+            .collect(),
+    ))
+}
+
 /**
  * 使用用户私钥解密消息
  * 
@@ -254,6 +548,47 @@ pub fn decrypt_deterministic(
     ))
 }
 
+/**
+ * 使用随机性批量进行确定性解密
+ * 
+ * 与对每个密文调用`decrypt_deterministic`的结果相同，但身份只哈希一次，
+ * 启用`parallel`特性时配对并行计算。
+ * 
+ * 参数:
+ * @param randomness - 加密时使用的随机性
+ * @param ciphertexts - 要解密的密文数组
+ * @param public_keys - 接收者的公钥数组
+ * @param id - 用户身份ID
+ * @param infos - 用于密钥派生的附加信息
+ * 
+ * 返回:
+ * 成功时返回解密后的明文数组，失败时返回错误
+ */
+pub fn decrypt_batched_deterministic(
+    randomness: &Randomness,
+    ciphertexts: &[Ciphertext],
+    public_keys: &[PublicKey],
+    id: &[u8],
+    infos: &[Info],
+) -> FastCryptoResult<Vec<Plaintext>> {
+    let batch_size = ciphertexts.len();
+    if batch_size != public_keys.len() || batch_size != infos.len() {
+        return Err(InvalidInput);
+    }
+
+    let gid = G1Element::hash_to_group_element(id);
+    let gid_r = gid * randomness;
+    let nonce = G2Element::generator() * randomness;
+    Ok(maybe_par_iter!(0..batch_size)
+        .map(|i| {
+            xor(
+                &ciphertexts[i],
+                &kdf(&gid_r.pairing(&public_keys[i]), &nonce, &gid, &infos[i]),
+            )
+        })
+        .collect())
+}
+
 /**
  * 密钥派生函数
  * 
@@ -352,7 +687,7 @@ pub fn create_proof_of_possession(master_key: &MasterKey, message: &[u8]) -> Pro
     let mut full_msg = DST_POP.to_vec();
     full_msg.extend(bcs::to_bytes(&public_key).expect("valid pk"));
     full_msg.extend(message);
-    G1Element::hash_to_group_element(&full_msg) * master_key
+    G1Element::hash_to_group_element(&full_msg) * master_key.expose()
 }
 
 #[cfg(test)]
@@ -379,4 +714,120 @@ mod tests {
                 .unwrap();
         assert_eq!(expected, derived_key);
     }
+
+    /// 测试预计算身份的批量加密与普通批量加密结果相同，并且可以被批量解密
+    #[test]
+    fn test_encrypt_batched_prepared() {
+        let mut rng = rand::thread_rng();
+        let id = b"prepared identity";
+        let public_keys = (0..3)
+            .map(|_| generate_key_pair(&mut rng).1)
+            .collect::<Vec<_>>();
+        let plaintexts = (0..3).map(|i| [i; KEY_SIZE]).collect::<Vec<_>>();
+        let infos = (0..3)
+            .map(|i| (ObjectID::random(), i + 1))
+            .collect::<Vec<_>>();
+        let randomness = Randomness::rand(&mut rng);
+
+        let expected =
+            encrypt_batched_deterministic(&randomness, &plaintexts, &public_keys, id, &infos)
+                .unwrap();
+        let prepared = prepare_identity(&public_keys, id);
+        assert_eq!(
+            encrypt_batched_prepared(&randomness, &plaintexts, &prepared, &infos).unwrap(),
+            expected
+        );
+        assert_eq!(
+            decrypt_batched_deterministic(&randomness, &expected.1, &public_keys, id, &infos)
+                .unwrap(),
+            plaintexts
+        );
+        assert!(
+            encrypt_batched_prepared(&randomness, &plaintexts[..2], &prepared, &infos).is_err()
+        );
+    }
+
+    /// 测试批量验证用户私钥：有效的私钥通过验证，无效的私钥被逐个验证找出
+    #[test]
+    fn test_verify_user_secret_keys_batch() {
+        let mut rng = rand::thread_rng();
+        let ids = [b"id 1".to_vec(), b"id 2".to_vec()];
+        let key_pairs = (0..3)
+            .map(|_| generate_key_pair(&mut rng))
+            .collect::<Vec<_>>();
+        let mut user_secret_keys = ids
+            .iter()
+            .flat_map(|id| key_pairs.iter().map(move |(sk, _)| extract(sk, id)))
+            .collect::<Vec<_>>();
+        let public_keys = key_pairs.iter().map(|(_, pk)| *pk).collect::<Vec<_>>();
+        fn claims<'a>(
+            user_secret_keys: &'a [UserSecretKey],
+            ids: &'a [Vec<u8>],
+            public_keys: &'a [PublicKey],
+        ) -> Vec<UserSecretKeyClaim<'a>> {
+            user_secret_keys
+                .iter()
+                .enumerate()
+                .map(|(i, usk)| (usk, ids[i / 3].as_slice(), &public_keys[i % 3]))
+                .collect()
+        }
+        assert!(verify_user_secret_keys_batch(&mut rng, &[]).is_ok());
+        let valid = claims(&user_secret_keys, &ids, &public_keys);
+        assert!(verify_user_secret_keys_batch(&mut rng, &valid).is_ok());
+
+        // 交换两个私钥的身份后，两个私钥都无效
+        user_secret_keys[1] = extract(&key_pairs[1].0, &ids[1]);
+        user_secret_keys[4] = extract(&key_pairs[1].0, &ids[0]);
+        let invalid = claims(&user_secret_keys, &ids, &public_keys);
+        assert_eq!(find_invalid_user_secret_keys(&invalid), vec![1, 4]);
+        assert!(verify_user_secret_keys_batch(&mut rng, &invalid).is_err());
+    }
+
+    /// 测试阈值提取
+    /// 1. 每个部分私钥都能通过对应验证密钥的验证
+    /// 2. 任意阈值数量的部分私钥聚合后得到有效的用户私钥
+    /// 3. 无效的部分私钥无法通过验证，少于阈值时聚合结果无效
+    #[test]
+    fn test_threshold_extract() {
+        let shares = crate::dkg::simulate(&mut rand::thread_rng(), 3, 5).unwrap();
+        let public_key = shares[0].public_key;
+        let id = b"some identity";
+
+        let partials = shares
+            .iter()
+            .map(|s| extract_partial(&s.share, s.index, id))
+            .collect::<Vec<_>>();
+        for (partial, (index, public_share)) in partials.iter().zip(&shares[0].public_shares) {
+            assert_eq!(partial.0, *index);
+            assert!(verify_partial(partial, id, public_share).is_ok());
+        }
+
+        let usk = aggregate_partials(&partials[1..4]).unwrap();
+        assert!(verify_user_secret_key(&usk, id, &public_key).is_ok());
+        assert_eq!(
+            aggregate_partials(&[partials[4], partials[0], partials[2]]).unwrap(),
+            usk
+        );
+        assert_eq!(aggregate_partials(&partials).unwrap(), usk);
+        assert!(verify_user_secret_key(
+            &aggregate_partials(&partials[..2]).unwrap(),
+            id,
+            &public_key
+        )
+        .is_err());
+
+        // 部分私钥与其他份额的验证密钥不匹配
+        assert!(verify_partial(&partials[0], id, &shares[0].public_shares[1].1).is_err());
+        assert!(verify_partial(
+            &partials[0],
+            b"other identity",
+            &shares[0].public_shares[0].1
+        )
+        .is_err());
+
+        // 重复或为零的索引
+        assert!(aggregate_partials(&[partials[0], partials[0]]).is_err());
+        assert!(aggregate_partials(&[(0, partials[0].1)]).is_err());
+        assert!(aggregate_partials(&[]).is_err());
+    }
 }
@@ -13,15 +13,35 @@
 //! * 使用足够数量的密钥共享重建密钥并解密数据 (`seal_decrypt`)
 //! * 基于身份的加密，无需复杂的PKI基础设施
 //! * 阈值密钥共享，确保即使部分密钥服务器不可用也能完成解密
-//! * 多种加密模式支持：AES-256-GCM、HMAC-256-CTR以及明文模式
+//! * 多种加密模式支持：AES-256-GCM、HMAC-256-CTR、ChaCha20-Poly1305、分块AES-256-GCM、带有密钥承诺的AES-256-GCM以及明文模式
+//! * 大文件的流式加密和解密 (`seal_encrypt_stream`, `seal_decrypt_stream`)
+//! * 分块密文的随机访问解密 (`decrypt_range`, `decrypt_range_seekable`)
+//! * 在不重新加密数据的情况下将密钥重新分享给新的密钥服务器集合 (`reshare`)
+//! * 多身份加密，持有任意一个身份的用户私钥即可解密 (`seal_encrypt_multi_identity`)
+//! * 嵌套阈值结构的访问树，例如"{A,B,C}中的2个 且 {D,E}中的1个" (`seal_encrypt_with_access_tree`)
+//! * 加权密钥服务器，权重为w的密钥服务器持有w个共享 (`seal_encrypt_weighted`)
+//! * 在同一身份下批量加密许多对象，复用哈希和配对的计算 (`seal_encrypt_batch`)；启用`parallel`特性时并行计算配对
+//! * 找出返回无效私钥或不一致共享的密钥服务器，并在诚实共享足够时仍然解密 (`seal_decrypt_robust`)
+//! * 使用固定桶、2的幂或Padmé策略填充明文，隐藏明文的长度 (`EncryptionInput::Padded`)
+//! * 加密前使用zstd或DEFLATE压缩明文，解密时限制解压缩的长度 (`EncryptionInput::Compressed`, `seal_decrypt_with_max_size`)
+//! * 从明文模式的密钥派生用途分离的子密钥，例如加密密钥、MAC密钥和每个文件的密钥 (`derive_subkey`)
+//! * 将头部摘要绑定到数据加密机制的额外认证数据中，防止密文被移到其他头部下 (`seal_encrypt_context_bound`)
+//! * 发送者使用Ed25519、secp256k1或Sui签名对加密对象签名，接收者在解密前验证发送者 (`seal_encrypt_and_sign`, `verify_and_decrypt`)
 //! 
 //! ## 模块结构
 //! 
+//! * `compression`: 加密前的明文压缩
 //! * `dem`: 数据加密机制，提供对称加密算法
+//! * `dkg`: 密钥服务器委员会的分布式密钥生成
 //! * `elgamal`: 基于椭圆曲线的ElGamal加密实现
 //! * `gf256`: GF(256)有限域的数学运算
+//! * `header`: 加密对象头部的TLV扩展
 //! * `ibe`: 身份基础加密的实现
 //! * `polynomial`: 多项式运算，用于秘密共享
+//! * `refresh`: 主密钥份额的主动刷新与恢复
+//! * `secret`: 释放时自动清零的秘密数据包装类型
+//! * `signed`: 加密对象的发送者签名
+//! * `test_vectors`: 可复现的JSON测试向量生成
 //! * `tss`: 阈值秘密共享实现
 //! * `utils`: 通用工具函数
 //! 
@@ -31,31 +51,58 @@
 //! * 身份基础加密简化了密钥管理流程
 //! * 认证加密保证数据完整性和真实性
 //! * 密钥派生机制增强了系统安全性
+//! * 主密钥、基础密钥和对称密钥在使用后自动清零，并且不会出现在调试输出中
 
 use crate::dem::Hmac256Ctr;
-use crate::ibe::{decrypt_deterministic, encrypt_batched_deterministic};
-use crate::tss::{combine, interpolate, SecretSharing};
-use dem::Aes256Gcm;
-use fastcrypto::error::FastCryptoError::{GeneralError, InvalidInput};
+use crate::ibe::{decrypt_batched_deterministic, encrypt_batched_deterministic};
+use crate::tss::{
+    combine, combine_tree, combine_tree_consistent, decode, interpolate, split_tree,
+    split_weighted, AccessTree, SecretSharing,
+};
+use compression::{Compression, DEFAULT_MAX_DECOMPRESSED_SIZE};
+use dem::{
+    Aes256Gcm, Aes256GcmChunked, Aes256GcmCommitting, ChaCha20Poly1305, PaddedReader, Padding,
+    UnpaddedWriter,
+};
+use fastcrypto::error::FastCryptoError::{self, GeneralError, InvalidInput};
 use fastcrypto::error::FastCryptoResult;
 use fastcrypto::groups::Scalar;
-use fastcrypto::hmac::{hmac_sha3_256, HmacKey};
+use fastcrypto::hash::{HashFunction, Sha3_256};
+use fastcrypto::hmac::{hkdf_sha3_256, hmac_sha3_256, HkdfIkm, HmacKey};
+use fastcrypto::traits::AllowedRng;
+use header::Extension;
 use itertools::Itertools;
-use rand::thread_rng;
-use serde::{Deserialize, Serialize};
-use serde_with::serde_as;
+use rand::rngs::StdRng;
+use rand::{thread_rng, SeedableRng};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use secret::Secret;
+use serde::de::{Error as _, SeqAccess, Visitor};
+use serde::ser::{Error as _, SerializeStruct};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use signed::{Sender, SignedEncryptedObject, SigningKey};
 use std::collections::HashMap;
+use std::io::{Read, Seek, Write};
+use std::ops::Range;
 pub use sui_types::base_types::ObjectID;
 use sui_types::crypto::ToFromBytes;
 use tss::split;
-use utils::generate_random_bytes;
+use utils::{generate_random_bytes, maybe_par_iter};
+use zeroize::{Zeroize, Zeroizing};
 
 // 子模块声明
+pub mod compression; // 明文压缩模块
 pub mod dem;         // 数据加密机制模块
+pub mod dkg;         // 分布式密钥生成模块
 pub mod elgamal;     // ElGamal加密模块
 pub mod gf256;       // GF(256)有限域数学模块
+pub mod header;      // 加密对象头部扩展模块
 pub mod ibe;         // 身份基础加密模块
 mod polynomial;      // 多项式计算模块
+pub mod refresh;     // 主密钥份额刷新模块
+pub mod secret;      // 敏感数据清零模块
+pub mod signed;      // 发送者签名模块
+pub mod test_vectors; // 可复现的测试向量生成模块
 pub mod tss;         // 阈值秘密共享模块
 mod utils;           // 工具函数模块
 
@@ -65,13 +112,36 @@ pub const DST: &[u8] = b"SUI-SEAL-IBE-BLS12381-00";
 /// 用于密钥持有证明的哈希到椭圆曲线群的域分隔标签
 pub const DST_POP: &[u8] = b"SUI-SEAL-IBE-BLS12381-POP-00";
 
+/// 用于计算绑定到数据加密机制的头部摘要的域分隔标签
+pub const DST_CONTEXT: &[u8] = b"SUI-SEAL-CONTEXT-00";
+
+/// 用于从明文模式的密钥派生子密钥的HKDF盐，参见`derive_subkey`
+pub const DST_SUBKEY: &[u8] = b"SUI-SEAL-SUBKEY-00";
+
 /// 密钥大小（字节数）
 pub const KEY_SIZE: usize = 32;
 
+/// 本实现支持的最高加密对象版本
+pub const MAX_SUPPORTED_VERSION: u8 = 1;
+
+/// `seal_decrypt_robust`最多尝试的共享子集数量，超过后改为逐个验证用户私钥，限制大量无效共享导致的计算量
+pub const MAX_ROBUST_DECRYPTION_ATTEMPTS: usize = 1000;
+
 /// 表示一个加密对象，包含加密数据及其密钥共享信息
 /// 与TypeScript类型保持一致
-#[serde_as]
-#[derive(Clone, Debug, Serialize, Deserialize)]
+///
+/// 版本0的对象不包含`extensions`字段，其序列化格式与之前完全相同。
+/// 从版本1开始，对象末尾带有一个TLV扩展列表，参见[header]。
+/// 反序列化时会根据版本号选择格式，并拒绝不支持的版本和关键扩展。
+///
+/// **注意：版本1的头部扩展默认不被认证。** 只有`header::TAG_CONTEXT_BINDING`、
+/// `header::TAG_PADDING`和`header::TAG_COMPRESSION`参与对称密钥的派生，
+/// 移除或修改它们会导致解密失败。其他扩展，包括关键扩展`header::TAG_ALTERNATIVE_IDENTITIES`
+/// 和`header::TAG_ACCESS_TREE`以及所有非关键扩展，都可以被传输路径上的攻击者修改而不被发现，
+/// 修改后的对象最多无法解密，但解析器看到的元数据可能是伪造的。需要认证关键扩展时应使用
+/// `seal_encrypt_context_bound`，此时`EncryptedObject::context_digest`覆盖所有关键扩展；
+/// 非关键扩展在任何情况下都不被认证，不能用于安全决策。
+#[derive(Clone, Debug)]
 pub struct EncryptedObject {
     /// 版本号，用于兼容性检查
     pub version: u8,
@@ -87,6 +157,8 @@ pub struct EncryptedObject {
     pub encrypted_shares: IBEEncryptions,
     /// 实际的密文数据
     pub ciphertext: Ciphertext,
+    /// 头部扩展，只在版本1及以上的对象中序列化
+    pub extensions: Vec<Extension>,
 }
 
 /// 加密数据的密文类型
@@ -110,6 +182,31 @@ pub enum Ciphertext {
     },
     /// 明文模式（不进行加密，只派生密钥）
     Plain,
+    /// 使用分块AES-256-GCM进行加密的数据，参见[dem::Aes256GcmChunked]
+    Aes256GcmChunked {
+        /// 所有块的密文和认证标签。由流式接口生成的对象中为空，密文单独存储
+        blob: Vec<u8>,
+        /// 额外的认证数据（可选）
+        aad: Option<Vec<u8>>,
+        /// 明文块大小
+        chunk_size: u32,
+    },
+    /// 使用ChaCha20-Poly1305进行加密的数据
+    ChaCha20Poly1305 {
+        /// 加密后的数据，包括认证标签
+        blob: Vec<u8>,
+        /// 额外的认证数据（可选）
+        aad: Option<Vec<u8>>,
+    },
+    /// 使用带有密钥承诺的AES-256-GCM进行加密的数据，参见[dem::Aes256GcmCommitting]
+    Aes256GcmCommitting {
+        /// 加密后的数据，包括认证标签
+        blob: Vec<u8>,
+        /// 额外的认证数据（可选）
+        aad: Option<Vec<u8>>,
+        /// 对DEM密钥的承诺，解密时与之不符的密钥会被拒绝
+        commitment: [u8; KEY_SIZE],
+    },
 }
 
 /// IBE加密数据类型
@@ -148,6 +245,109 @@ pub enum EncryptionInput {
     Hmac256Ctr { data: Vec<u8>, aad: Option<Vec<u8>> },
     /// 明文模式（不进行加密，只派生密钥）
     Plain,
+    /// 使用分块AES-256-GCM进行加密的输入
+    Aes256GcmChunked {
+        data: Vec<u8>,
+        aad: Option<Vec<u8>>,
+        chunk_size: u32,
+    },
+    /// 使用ChaCha20-Poly1305进行加密的输入
+    ChaCha20Poly1305 { data: Vec<u8>, aad: Option<Vec<u8>> },
+    /// 使用带有密钥承诺的AES-256-GCM进行加密的输入
+    Aes256GcmCommitting { data: Vec<u8>, aad: Option<Vec<u8>> },
+    /// 在加密前按照给定的策略填充明文，隐藏明文的长度。内部输入不能是`Plain`或`Padded`。
+    /// 填充策略存储在关键扩展`header::TAG_PADDING`中，因此结果是版本1的对象，
+    /// `seal_decrypt`会在认证之后移除填充
+    Padded {
+        input: Box<EncryptionInput>,
+        padding: Padding,
+    },
+    /// 在加密前使用给定的算法压缩明文。内部输入不能是`Plain`、`Padded`或`Compressed`，
+    /// 需要同时填充时应在压缩的输入外层使用`Padded`，这样填充隐藏的是压缩后的长度。
+    /// 压缩算法存储在关键扩展`header::TAG_COMPRESSION`中，`seal_decrypt`会在认证之后解压缩
+    Compressed {
+        input: Box<EncryptionInput>,
+        compression: Compression,
+    },
+}
+
+impl EncryptionInput {
+    /// 使用给定的填充策略填充这个输入，参见`EncryptionInput::Padded`
+    pub fn padded(self, padding: Padding) -> Self {
+        EncryptionInput::Padded {
+            input: Box::new(self),
+            padding,
+        }
+    }
+
+    /// 使用给定的算法压缩这个输入，参见`EncryptionInput::Compressed`
+    pub fn compressed(self, compression: Compression) -> Self {
+        EncryptionInput::Compressed {
+            input: Box::new(self),
+            compression,
+        }
+    }
+
+    /// 需要加密的明文，`Plain`、`Padded`和`Compressed`没有直接的明文
+    fn data_mut(&mut self) -> Option<&mut Vec<u8>> {
+        match self {
+            EncryptionInput::Aes256Gcm { data, .. }
+            | EncryptionInput::Hmac256Ctr { data, .. }
+            | EncryptionInput::Aes256GcmChunked { data, .. }
+            | EncryptionInput::ChaCha20Poly1305 { data, .. }
+            | EncryptionInput::Aes256GcmCommitting { data, .. } => Some(data),
+            EncryptionInput::Plain
+            | EncryptionInput::Padded { .. }
+            | EncryptionInput::Compressed { .. } => None,
+        }
+    }
+}
+
+/// 多身份加密中的一个身份及其密钥服务器，参见`seal_encrypt_multi_identity`
+#[derive(Clone, Debug)]
+pub struct IdentityPolicy {
+    /// 包ID，用于构建完整身份
+    pub package_id: ObjectID,
+    /// 内部ID，与package_id一起构成完整身份
+    pub id: Vec<u8>,
+    /// 用于加密的密钥服务器列表
+    pub key_servers: Vec<ObjectID>,
+    /// 密钥服务器的公钥
+    pub public_keys: IBEPublicKeys,
+    /// TSS的阈值
+    pub threshold: u8,
+}
+
+/// 加密对象中的一个身份及其加密的密钥共享。
+/// 多身份对象的主身份存储在`EncryptedObject`的对应字段中，其他身份存储在
+/// `header::TAG_ALTERNATIVE_IDENTITIES`扩展中，编码为BCS格式的列表。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IdentityEncryption {
+    /// 包ID
+    pub package_id: ObjectID,
+    /// 内部ID
+    pub id: Vec<u8>,
+    /// 密钥服务器地址及其共享索引列表
+    pub services: Vec<(ObjectID, u8)>,
+    /// 解密所需的最小共享数量（阈值）
+    pub threshold: u8,
+    /// 加密的密钥共享
+    pub encrypted_shares: IBEEncryptions,
+}
+
+/// 鲁棒解密的错误，参见`seal_decrypt_robust`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RobustDecryptionError {
+    /// 这些密钥服务器返回了无效的用户私钥，排除它们之后剩余的共享不足以解密
+    MisbehavingKeyServers(Vec<ObjectID>),
+    /// 其他错误，例如加密对象无效或者持有的用户私钥不足
+    Crypto(FastCryptoError),
+}
+
+impl From<FastCryptoError> for RobustDecryptionError {
+    fn from(error: FastCryptoError) -> Self {
+        RobustDecryptionError::Crypto(error)
+    }
 }
 
 /// 加密指定的明文数据。加密过程如下：
@@ -170,460 +370,3588 @@ pub fn seal_encrypt(
     public_keys: &IBEPublicKeys,
     threshold: u8,
     encryption_input: EncryptionInput,
-) -> FastCryptoResult<(EncryptedObject, [u8; KEY_SIZE])> {
+) -> FastCryptoResult<(EncryptedObject, Secret<[u8; KEY_SIZE]>)> {
+    seal_encrypt_with_rng(
+        &mut thread_rng(),
+        package_id,
+        id,
+        key_servers,
+        public_keys,
+        threshold,
+        encryption_input,
+    )
+}
+
+/// 与`seal_encrypt`相同，但使用调用者提供的随机数生成器。
+/// 可用于生成确定性的测试向量，或者在生产环境中使用经过审计的DRBG。
+///
+/// @param rng 随机数生成器，用于生成基础密钥、秘密共享和IBE随机性
+/// @param package_id 包ID，用于构建完整身份
+/// @param id 内部ID，与package_id一起构成完整身份
+/// @param key_servers 用于加密的密钥服务器列表
+/// @param public_keys 密钥服务器的公钥
+/// @param threshold TSS的阈值，至少需要这么多共享才能重建密钥
+/// @param encryption_input 加密输入数据
+/// @return 加密对象和用于加密的派生对称密钥
+pub fn seal_encrypt_with_rng<R: AllowedRng>(
+    rng: &mut R,
+    package_id: ObjectID,
+    id: Vec<u8>,
+    key_servers: Vec<ObjectID>,
+    public_keys: &IBEPublicKeys,
+    threshold: u8,
+    encryption_input: EncryptionInput,
+) -> FastCryptoResult<(EncryptedObject, Secret<[u8; KEY_SIZE]>)> {
     // 验证阈值参数
     let number_of_shares = key_servers.len() as u8;
     if threshold > number_of_shares || threshold == 0 {
         return Err(InvalidInput);
     }
 
-    // 创建随机数生成器
-    let mut rng = thread_rng();
-    // 创建完整ID，用于IBE加密
-    let full_id = create_full_id(&package_id, &id);
-
     // 生成随机基础密钥
-    let base_key = generate_random_bytes(&mut rng);
-
-    // 派生用于对称加密的密钥
-    let dem_key = derive_key(KeyPurpose::DEM, &base_key);
-    // 根据加密模式加密数据
-    let ciphertext = match encryption_input {
-        EncryptionInput::Aes256Gcm { data, aad } => Ciphertext::Aes256Gcm {
-            blob: Aes256Gcm::encrypt(&data, aad.as_ref().unwrap_or(&vec![]), &dem_key),
-            aad,
-        },
-        EncryptionInput::Hmac256Ctr { data, aad } => {
-            let (blob, mac) = Hmac256Ctr::encrypt(&data, aad.as_ref().unwrap_or(&vec![]), &dem_key);
-            Ciphertext::Hmac256Ctr { blob, mac, aad }
-        }
-        EncryptionInput::Plain => Ciphertext::Plain,
-    };
-
-    // 使用阈值秘密共享对基础密钥进行分享
-    let SecretSharing {
-        indices, shares, ..
-    } = split(&mut rng, base_key, threshold, number_of_shares)?;
-
-    // 将密钥服务器ID与共享索引配对
-    let services = key_servers.into_iter().zip(indices).collect::<Vec<_>>();
-
-    // 根据公钥类型选择IBE加密方法
-    let encrypted_shares = match public_keys {
-        IBEPublicKeys::BonehFranklinBLS12381(public_keys) => {
-            // 验证公钥数量是否正确
-            if public_keys.len() != number_of_shares as usize {
-                return Err(InvalidInput);
-            }
-            // 生成随机值用于IBE加密
-            let randomness = ibe::Randomness::rand(&mut rng);
+    let base_key = Secret::random(rng);
 
-            // 使用IBE加密共享
-            // 使用共享索引作为IBE解密的索引参数，允许为同一身份的多个共享使用相同的公钥加密
-            let (nonce, encrypted_shares) = encrypt_batched_deterministic(
-                &randomness,
-                &shares,
-                public_keys,
-                &full_id,
-                &services,
-            )?;
+    // 派生用于对称加密的密钥，并根据加密模式加密数据
+    let (encryption_input, extensions) = preprocess_input(encryption_input)?;
+    let dem_key = derive_dem_key(base_key.expose(), &extensions);
+    let ciphertext = encrypt_data(encryption_input, dem_key.expose(), &[])?;
 
-            // 加密随机值，用于验证
-            let encrypted_randomness = ibe::encrypt_randomness(
-                &randomness,
-                &derive_key(KeyPurpose::EncryptedRandomness, &base_key),
-            );
-            IBEEncryptions::BonehFranklinBLS12381 {
-                nonce,
-                encrypted_shares,
-                encrypted_randomness,
-            }
-        }
-    };
+    // 对基础密钥进行阈值分享并使用IBE加密各个共享
+    let (services, encrypted_shares) = encrypt_base_key(
+        rng,
+        base_key.expose(),
+        0,
+        &create_full_id(&package_id, &id),
+        key_servers,
+        public_keys,
+        threshold,
+    )?;
 
     // 返回加密对象和派生的对称密钥
     Ok((
-        EncryptedObject {
-            version: 0,
-            package_id,
-            id,
-            services,
-            threshold,
-            encrypted_shares,
-            ciphertext,
-        },
+        add_extensions(
+            EncryptedObject {
+                version: 0,
+                package_id,
+                id,
+                services,
+                threshold,
+                encrypted_shares,
+                ciphertext,
+                extensions: vec![],
+            },
+            extensions,
+        ),
         dem_key,
     ))
 }
 
-/// 解密给定的密文。解密过程如下：
-/// 1. 使用用户私钥为给定的随机数解封IBE密钥
-/// 2. 使用IBE密钥解密共享
-/// 3. 使用足够数量的共享重建AES密钥
-/// 4. 使用AES密钥解密密文
+/// 与`seal_encrypt`相同，但将头部摘要绑定到数据加密机制的额外认证数据中。
+/// 结果是包含关键扩展`header::TAG_CONTEXT_BINDING`的版本1对象，`seal_decrypt`会使用
+/// `EncryptedObject::context_digest`重新计算摘要，因此把密文移到其他头部下（例如修改`id`、
+/// `services`或`threshold`，或者在使用相同基础密钥的对象之间复制密文）会导致解密失败。
+/// 绑定扩展本身参与对称密钥的派生，因此移除它也会导致解密失败。
 ///
-/// @param encrypted_object 加密对象，由`seal_encrypt`生成
-/// @param user_secret_keys 用户私钥。假设这些密钥已经过验证，否则解密将失败，或者在使用`Plain`模式的情况下，派生的密钥将不正确
-/// @param public_keys 密钥服务器的公钥。如果提供，所有共享将被解密并检查一致性
-/// @return 解密后的明文数据，或者如果使用了`Plain`模式，则返回派生的密钥
-pub fn seal_decrypt(
-    encrypted_object: &EncryptedObject,
-    user_secret_keys: &IBEUserSecretKeys,
-    public_keys: Option<&IBEPublicKeys>,
-) -> FastCryptoResult<Vec<u8>> {
-    let EncryptedObject {
-        version,
+/// 绑定的对象不能使用`reshare`重新分享，因为新的密钥服务器会改变头部摘要。
+/// `Plain`模式没有需要认证的密文，因此不支持。
+///
+/// @param package_id 包ID，用于构建完整身份
+/// @param id 内部ID，与package_id一起构成完整身份
+/// @param key_servers 用于加密的密钥服务器列表
+/// @param public_keys 密钥服务器的公钥
+/// @param threshold TSS的阈值，至少需要这么多共享才能重建密钥
+/// @param encryption_input 加密输入数据
+/// @return 加密对象和用于加密的派生对称密钥
+pub fn seal_encrypt_context_bound(
+    package_id: ObjectID,
+    id: Vec<u8>,
+    key_servers: Vec<ObjectID>,
+    public_keys: &IBEPublicKeys,
+    threshold: u8,
+    encryption_input: EncryptionInput,
+) -> FastCryptoResult<(EncryptedObject, Secret<[u8; KEY_SIZE]>)> {
+    seal_encrypt_context_bound_with_rng(
+        &mut thread_rng(),
         package_id,
         id,
-        encrypted_shares,
-        services,
+        key_servers,
+        public_keys,
         threshold,
-        ciphertext,
-        ..
-    } = encrypted_object;
+        encryption_input,
+    )
+}
 
-    // 检查版本兼容性
-    if *version != 0 {
+/// 与`seal_encrypt_context_bound`相同，但使用调用者提供的随机数生成器。
+///
+/// @param rng 随机数生成器，用于生成基础密钥、秘密共享和IBE随机性
+/// @param package_id 包ID，用于构建完整身份
+/// @param id 内部ID，与package_id一起构成完整身份
+/// @param key_servers 用于加密的密钥服务器列表
+/// @param public_keys 密钥服务器的公钥
+/// @param threshold TSS的阈值，至少需要这么多共享才能重建密钥
+/// @param encryption_input 加密输入数据
+/// @return 加密对象和用于加密的派生对称密钥
+pub fn seal_encrypt_context_bound_with_rng<R: AllowedRng>(
+    rng: &mut R,
+    package_id: ObjectID,
+    id: Vec<u8>,
+    key_servers: Vec<ObjectID>,
+    public_keys: &IBEPublicKeys,
+    threshold: u8,
+    encryption_input: EncryptionInput,
+) -> FastCryptoResult<(EncryptedObject, Secret<[u8; KEY_SIZE]>)> {
+    let number_of_shares = key_servers.len() as u8;
+    if threshold > number_of_shares
+        || threshold == 0
+        || matches!(encryption_input, EncryptionInput::Plain)
+    {
         return Err(InvalidInput);
     }
+    let (encryption_input, extensions) = preprocess_input(encryption_input)?;
 
-    // 创建完整ID，用于IBE解密
-    let full_id = create_full_id(package_id, id);
+    let base_key = Secret::random(rng);
+    let (services, encrypted_shares) = encrypt_base_key(
+        rng,
+        base_key.expose(),
+        0,
+        &create_full_id(&package_id, &id),
+        key_servers,
+        public_keys,
+        threshold,
+    )?;
 
-    // 根据IBE类型解密共享
-    let shares = match (&encrypted_shares, user_secret_keys) {
-        (
-            IBEEncryptions::BonehFranklinBLS12381 {
-                nonce,
-                encrypted_shares,
-                ..
-            },
-            IBEUserSecretKeys::BonehFranklinBLS12381(user_secret_keys),
-        ) => {
-            // 检查加密对象是否有效
-            // 例如，每个服务是否都有一个对应的加密共享
-            if encrypted_shares.len() != services.len() {
-                return Err(InvalidInput);
-            }
+    // 头部摘要和对称密钥不依赖于密文，因此先构造头部，再使用它们加密数据
+    let mut encrypted_object = EncryptedObject {
+        version: 1,
+        package_id,
+        id,
+        services,
+        threshold,
+        encrypted_shares,
+        ciphertext: Ciphertext::Plain,
+        extensions,
+    };
+    header::set_extension(
+        &mut encrypted_object.extensions,
+        header::TAG_CONTEXT_BINDING,
+        vec![],
+    );
+    let context = encrypted_object.context_digest().expect("Never fails");
+    let dem_key = derive_dem_key(base_key.expose(), &encrypted_object.extensions);
+    encrypted_object.ciphertext = encrypt_data(encryption_input, dem_key.expose(), &context)?;
+    header::set_extension(
+        &mut encrypted_object.extensions,
+        header::TAG_DEM_ALGORITHM,
+        vec![encrypted_object.ciphertext.algorithm_id()],
+    );
+    Ok((encrypted_object, dem_key))
+}
 
-            // 找出我们拥有私钥的服务索引
-            let service_indices: Vec<usize> = services
-                .iter()
-                .enumerate()
-                .filter(|(_, (id, _))| user_secret_keys.contains_key(id))
-                .map(|(i, _)| i)
-                .collect();
-            // 检查我们是否有足够的私钥来达到阈值
-            if service_indices.len() < *threshold as usize {
-                return Err(InvalidInput);
-            }
+/// 与`seal_encrypt`相同，但发送者还会对加密对象的BCS编码签名，
+/// 接收者可以使用`verify_and_decrypt`验证对象确实由预期的发送者创建。
+///
+/// @param package_id 包ID，用于构建完整身份
+/// @param id 内部ID，与package_id一起构成完整身份
+/// @param key_servers 用于加密的密钥服务器列表
+/// @param public_keys 密钥服务器的公钥
+/// @param threshold TSS的阈值，至少需要这么多共享才能重建密钥
+/// @param encryption_input 加密输入数据
+/// @param signing_key 发送者的签名密钥
+/// @return 签名的加密对象和用于加密的派生对称密钥
+pub fn seal_encrypt_and_sign(
+    package_id: ObjectID,
+    id: Vec<u8>,
+    key_servers: Vec<ObjectID>,
+    public_keys: &IBEPublicKeys,
+    threshold: u8,
+    encryption_input: EncryptionInput,
+    signing_key: &SigningKey,
+) -> FastCryptoResult<(SignedEncryptedObject, Secret<[u8; KEY_SIZE]>)> {
+    let (encrypted_object, dem_key) = seal_encrypt(
+        package_id,
+        id,
+        key_servers,
+        public_keys,
+        threshold,
+        encryption_input,
+    )?;
+    Ok((
+        SignedEncryptedObject::sign(encrypted_object, signing_key)?,
+        dem_key,
+    ))
+}
 
-            // 使用私钥解密每个共享
-            service_indices
-                .into_iter()
-                .map(|i| {
-                    let index = services[i].1;
-                    (index, ibe::decrypt(
-                        nonce,
-                        &encrypted_shares[i],
-                        user_secret_keys
-                            .get(&services[i].0)
-                            .expect("这不应该发生：上面已经检查过这个私钥是否可用"),
-                        &full_id,
-                        &services[i],
-                    ))
-                })
-                .collect_vec()
-        }
-    };
+/// 多身份加密：使用同一个基础密钥加密数据，并将基础密钥分别封装给多个身份（可以来自不同的包），
+/// 持有其中任意一个身份的足够用户私钥即可使用`seal_decrypt`解密，例如"订阅者或所有者"。
+///
+/// 第一个身份作为对象的主身份，存储在`package_id`、`id`、`services`等字段中，
+/// 其余身份存储在关键扩展`header::TAG_ALTERNATIVE_IDENTITIES`中，因此结果总是版本1的对象，
+/// 不支持多身份的旧解析器会拒绝它，而不是只尝试主身份。
+///
+/// @param identities 可以解密对象的身份及其密钥服务器，至少一个，最多255个
+/// @param encryption_input 加密输入数据
+/// @return 加密对象和用于加密的派生对称密钥
+pub fn seal_encrypt_multi_identity(
+    identities: Vec<IdentityPolicy>,
+    encryption_input: EncryptionInput,
+) -> FastCryptoResult<(EncryptedObject, Secret<[u8; KEY_SIZE]>)> {
+    seal_encrypt_multi_identity_with_rng(&mut thread_rng(), identities, encryption_input)
+}
+
+/// 与`seal_encrypt_multi_identity`相同，但使用调用者提供的随机数生成器。
+///
+/// @param rng 随机数生成器，用于生成基础密钥、秘密共享和IBE随机性
+/// @param identities 可以解密对象的身份及其密钥服务器，至少一个，最多255个
+/// @param encryption_input 加密输入数据
+/// @return 加密对象和用于加密的派生对称密钥
+pub fn seal_encrypt_multi_identity_with_rng<R: AllowedRng>(
+    rng: &mut R,
+    identities: Vec<IdentityPolicy>,
+    encryption_input: EncryptionInput,
+) -> FastCryptoResult<(EncryptedObject, Secret<[u8; KEY_SIZE]>)> {
+    if identities.is_empty()
+        || identities.len() > u8::MAX as usize
+        || identities
+            .iter()
+            .any(|i| i.threshold > i.key_servers.len() as u8 || i.threshold == 0)
+    {
+        return Err(InvalidInput);
+    }
 
-    // 使用共享重建基础密钥
-    let base_key = combine(&shares)?;
+    let base_key = Secret::random(rng);
+    let (encryption_input, extensions) = preprocess_input(encryption_input)?;
+    let dem_key = derive_dem_key(base_key.expose(), &extensions);
+    let ciphertext = encrypt_data(encryption_input, dem_key.expose(), &[])?;
 
-    // 如果提供了公钥，可以解密所有共享并检查一致性
-    if let Some(public_keys) = public_keys {
-        encrypted_shares.check_share_consistency(
-            &shares,
-            &full_id,
-            services,
-            public_keys,
-            &base_key,
+    // 每个身份使用独立的秘密共享和IBE随机性封装同一个基础密钥
+    let mut identities = identities
+        .into_iter()
+        .enumerate()
+        .map(|(i, policy)| {
+            let (services, encrypted_shares) = encrypt_base_key(
+                rng,
+                base_key.expose(),
+                i as u8,
+                &create_full_id(&policy.package_id, &policy.id),
+                policy.key_servers,
+                &policy.public_keys,
+                policy.threshold,
+            )?;
+            Ok(IdentityEncryption {
+                package_id: policy.package_id,
+                id: policy.id,
+                services,
+                threshold: policy.threshold,
+                encrypted_shares,
+            })
+        })
+        .collect::<FastCryptoResult<Vec<_>>>()?;
+    let alternatives = identities.split_off(1);
+    let primary = identities.pop().expect("至少有一个身份");
+
+    let mut encrypted_object = upgrade_to_v1(&EncryptedObject {
+        version: 0,
+        package_id: primary.package_id,
+        id: primary.id,
+        services: primary.services,
+        threshold: primary.threshold,
+        encrypted_shares: primary.encrypted_shares,
+        ciphertext,
+        extensions,
+    });
+    if !alternatives.is_empty() {
+        encrypted_object.set_extension(
+            header::TAG_ALTERNATIVE_IDENTITIES,
+            bcs::to_bytes(&alternatives).expect("Never fails"),
         )?;
     }
+    Ok((encrypted_object, dem_key))
+}
 
-    // 派生对称密钥并解密密文
-    let dem_key = derive_key(KeyPurpose::DEM, &base_key);
-    match ciphertext {
-        Ciphertext::Aes256Gcm { blob, aad } => {
-            Aes256Gcm::decrypt(blob, aad.as_ref().map_or(&[], |v| v), &dem_key)
-        }
-        Ciphertext::Hmac256Ctr { blob, aad, mac } => {
-            Hmac256Ctr::decrypt(blob, mac, aad.as_ref().map_or(&[], |v| v), &dem_key)
+/// 使用访问树加密：与`seal_encrypt`相同，但基础密钥按照嵌套的阈值结构分享给密钥服务器，
+/// 例如"{A,B,C}中的2个 且 {D,E}中的1个"，可以用于由不同组织运行的密钥服务器组。
+///
+/// 访问树的叶子是`key_servers`中的位置，每个密钥服务器必须恰好出现一次。
+/// 访问树存储在关键扩展`header::TAG_ACCESS_TREE`中，因此结果总是版本1的对象，
+/// `services`中的共享索引为位置加1，`threshold`为满足访问树所需的最少密钥服务器数量。
+///
+/// @param package_id 包ID，用于构建完整身份
+/// @param id 内部ID，与package_id一起构成完整身份
+/// @param key_servers 用于加密的密钥服务器列表
+/// @param public_keys 密钥服务器的公钥
+/// @param access_tree 访问树
+/// @param encryption_input 加密输入数据
+/// @return 加密对象和用于加密的派生对称密钥
+pub fn seal_encrypt_with_access_tree(
+    package_id: ObjectID,
+    id: Vec<u8>,
+    key_servers: Vec<ObjectID>,
+    public_keys: &IBEPublicKeys,
+    access_tree: &AccessTree,
+    encryption_input: EncryptionInput,
+) -> FastCryptoResult<(EncryptedObject, Secret<[u8; KEY_SIZE]>)> {
+    seal_encrypt_with_access_tree_and_rng(
+        &mut thread_rng(),
+        package_id,
+        id,
+        key_servers,
+        public_keys,
+        access_tree,
+        encryption_input,
+    )
+}
+
+/// 与`seal_encrypt_with_access_tree`相同，但使用调用者提供的随机数生成器。
+///
+/// @param rng 随机数生成器，用于生成基础密钥、秘密共享和IBE随机性
+/// @param package_id 包ID，用于构建完整身份
+/// @param id 内部ID，与package_id一起构成完整身份
+/// @param key_servers 用于加密的密钥服务器列表
+/// @param public_keys 密钥服务器的公钥
+/// @param access_tree 访问树
+/// @param encryption_input 加密输入数据
+/// @return 加密对象和用于加密的派生对称密钥
+pub fn seal_encrypt_with_access_tree_and_rng<R: AllowedRng>(
+    rng: &mut R,
+    package_id: ObjectID,
+    id: Vec<u8>,
+    key_servers: Vec<ObjectID>,
+    public_keys: &IBEPublicKeys,
+    access_tree: &AccessTree,
+    encryption_input: EncryptionInput,
+) -> FastCryptoResult<(EncryptedObject, Secret<[u8; KEY_SIZE]>)> {
+    if key_servers.is_empty() || key_servers.len() > u8::MAX as usize {
+        return Err(InvalidInput);
+    }
+    access_tree.validate(key_servers.len())?;
+
+    let base_key = Secret::random(rng);
+    let (encryption_input, extensions) = preprocess_input(encryption_input)?;
+    let dem_key = derive_dem_key(base_key.expose(), &extensions);
+    let ciphertext = encrypt_data(encryption_input, dem_key.expose(), &[])?;
+
+    // 按照访问树分享基础密钥，共享索引为位置加1，保证IBE加密的附加信息互不相同
+    let shares = split_tree(rng, *base_key.expose(), access_tree, key_servers.len())?;
+    let services = key_servers.into_iter().zip(1..).collect_vec();
+    let encrypted_shares = encrypt_shares(
+        rng,
+        base_key.expose(),
+        0,
+        &create_full_id(&package_id, &id),
+        &services,
+        &shares,
+        public_keys,
+    )?;
+
+    let mut encrypted_object = upgrade_to_v1(&EncryptedObject {
+        version: 0,
+        package_id,
+        id,
+        services,
+        threshold: access_tree.min_leaves() as u8,
+        encrypted_shares,
+        ciphertext,
+        extensions,
+    });
+    encrypted_object.set_extension(
+        header::TAG_ACCESS_TREE,
+        bcs::to_bytes(access_tree).expect("Never fails"),
+    )?;
+    Ok((encrypted_object, dem_key))
+}
+
+/// 加权加密：与`seal_encrypt`相同，但权重为w的密钥服务器获得w个共享，
+/// 例如可信度更高的密钥服务器可以计为两个。阈值是共享数量而不是密钥服务器数量。
+///
+/// 权重为w的密钥服务器在`services`中出现w次，共享索引按顺序连续分配，
+/// 因此结果与普通的加密对象格式相同，可以直接使用`seal_decrypt`解密。
+///
+/// @param package_id 包ID，用于构建完整身份
+/// @param id 内部ID，与package_id一起构成完整身份
+/// @param key_servers 用于加密的密钥服务器及其权重列表，权重必须大于0，且总和不超过255
+/// @param public_keys 密钥服务器的公钥，与`key_servers`顺序相同，每个密钥服务器一个
+/// @param threshold TSS的阈值，至少需要这么多共享才能重建密钥
+/// @param encryption_input 加密输入数据
+/// @return 加密对象和用于加密的派生对称密钥
+pub fn seal_encrypt_weighted(
+    package_id: ObjectID,
+    id: Vec<u8>,
+    key_servers: Vec<(ObjectID, u8)>,
+    public_keys: &IBEPublicKeys,
+    threshold: u8,
+    encryption_input: EncryptionInput,
+) -> FastCryptoResult<(EncryptedObject, Secret<[u8; KEY_SIZE]>)> {
+    seal_encrypt_weighted_with_rng(
+        &mut thread_rng(),
+        package_id,
+        id,
+        key_servers,
+        public_keys,
+        threshold,
+        encryption_input,
+    )
+}
+
+/// 与`seal_encrypt_weighted`相同，但使用调用者提供的随机数生成器。
+///
+/// @param rng 随机数生成器，用于生成基础密钥、秘密共享和IBE随机性
+/// @param package_id 包ID，用于构建完整身份
+/// @param id 内部ID，与package_id一起构成完整身份
+/// @param key_servers 用于加密的密钥服务器及其权重列表，权重必须大于0，且总和不超过255
+/// @param public_keys 密钥服务器的公钥，与`key_servers`顺序相同，每个密钥服务器一个
+/// @param threshold TSS的阈值，至少需要这么多共享才能重建密钥
+/// @param encryption_input 加密输入数据
+/// @return 加密对象和用于加密的派生对称密钥
+pub fn seal_encrypt_weighted_with_rng<R: AllowedRng>(
+    rng: &mut R,
+    package_id: ObjectID,
+    id: Vec<u8>,
+    key_servers: Vec<(ObjectID, u8)>,
+    public_keys: &IBEPublicKeys,
+    threshold: u8,
+    encryption_input: EncryptionInput,
+) -> FastCryptoResult<(EncryptedObject, Secret<[u8; KEY_SIZE]>)> {
+    let IBEPublicKeys::BonehFranklinBLS12381(server_public_keys) = public_keys;
+    if server_public_keys.len() != key_servers.len() {
+        return Err(InvalidInput);
+    }
+
+    let base_key = Secret::random(rng);
+    let (encryption_input, extensions) = preprocess_input(encryption_input)?;
+    let dem_key = derive_dem_key(base_key.expose(), &extensions);
+    let ciphertext = encrypt_data(encryption_input, dem_key.expose(), &[])?;
+
+    // 每个密钥服务器获得与其权重相同数量的共享，公钥按共享展开
+    let weights = key_servers.iter().map(|(_, w)| *w).collect_vec();
+    let weighted_shares = split_weighted(rng, *base_key.expose(), threshold, &weights)?;
+    let mut services = vec![];
+    let mut shares = vec![];
+    let mut share_public_keys = vec![];
+    for (((server, _), pk), server_shares) in key_servers
+        .into_iter()
+        .zip(server_public_keys)
+        .zip(weighted_shares)
+    {
+        for (index, share) in server_shares {
+            services.push((server, index));
+            shares.push(share);
+            share_public_keys.push(*pk);
         }
-        Ciphertext::Plain => Ok(dem_key.to_vec()),
     }
+
+    let encrypted_shares = encrypt_shares(
+        rng,
+        base_key.expose(),
+        0,
+        &create_full_id(&package_id, &id),
+        &services,
+        &shares,
+        &IBEPublicKeys::BonehFranklinBLS12381(share_public_keys),
+    )?;
+
+    Ok((
+        add_extensions(
+            EncryptedObject {
+                version: 0,
+                package_id,
+                id,
+                services,
+                threshold,
+                encrypted_shares,
+                ciphertext,
+                extensions: vec![],
+            },
+            extensions,
+        ),
+        dem_key,
+    ))
 }
 
-/// 从DST、包ID和内部ID创建完整ID。结果的格式为：
-/// [len(DST)][DST][package_id][id]
-pub fn create_full_id(package_id: &[u8; 32], id: &[u8]) -> Vec<u8> {
-    assert!(DST.len() < 256);
-    let mut full_id = vec![DST.len() as u8];
-    full_id.extend_from_slice(DST);
-    full_id.extend_from_slice(package_id);
-    full_id.extend_from_slice(id);
-    full_id
+/// 批量加密：在同一个身份和同一组密钥服务器下加密多个对象，例如同一访问策略下的许多文件。
+/// 每个对象使用独立的基础密钥和IBE随机性，结果与逐个调用`seal_encrypt`的格式相同，
+/// 但身份只哈希到曲线一次，每个密钥服务器的配对 e(H(id), pk) 也只计算一次，
+/// 之后每个对象只需要在GT中做标量乘法。启用`parallel`特性时各个对象并行加密。
+///
+/// @param package_id 包ID，用于构建完整身份
+/// @param id 内部ID，与package_id一起构成完整身份
+/// @param key_servers 用于加密的密钥服务器列表
+/// @param public_keys 密钥服务器的公钥
+/// @param threshold TSS的阈值，至少需要这么多共享才能重建密钥
+/// @param encryption_inputs 每个对象的加密输入数据
+/// @return 与`encryption_inputs`顺序相同的加密对象和派生对称密钥
+pub fn seal_encrypt_batch(
+    package_id: ObjectID,
+    id: Vec<u8>,
+    key_servers: Vec<ObjectID>,
+    public_keys: &IBEPublicKeys,
+    threshold: u8,
+    encryption_inputs: Vec<EncryptionInput>,
+) -> FastCryptoResult<Vec<(EncryptedObject, Secret<[u8; KEY_SIZE]>)>> {
+    seal_encrypt_batch_with_rng(
+        &mut thread_rng(),
+        package_id,
+        id,
+        key_servers,
+        public_keys,
+        threshold,
+        encryption_inputs,
+    )
 }
 
-/// 表示派生密钥的不同用途
-pub enum KeyPurpose {
-    /// 用于加密随机性的密钥
-    EncryptedRandomness,
-    /// 用于数据加密机制(DEM)的密钥
-    DEM,
+/// 与`seal_encrypt_batch`相同，但使用调用者提供的随机数生成器。
+/// 每个对象使用由`rng`生成的种子初始化的独立随机数生成器，因此结果不依赖于是否启用`parallel`特性。
+///
+/// @param rng 随机数生成器，用于生成每个对象的随机数生成器种子
+/// @param package_id 包ID，用于构建完整身份
+/// @param id 内部ID，与package_id一起构成完整身份
+/// @param key_servers 用于加密的密钥服务器列表
+/// @param public_keys 密钥服务器的公钥
+/// @param threshold TSS的阈值，至少需要这么多共享才能重建密钥
+/// @param encryption_inputs 每个对象的加密输入数据
+/// @return 与`encryption_inputs`顺序相同的加密对象和派生对称密钥
+pub fn seal_encrypt_batch_with_rng<R: AllowedRng>(
+    rng: &mut R,
+    package_id: ObjectID,
+    id: Vec<u8>,
+    key_servers: Vec<ObjectID>,
+    public_keys: &IBEPublicKeys,
+    threshold: u8,
+    encryption_inputs: Vec<EncryptionInput>,
+) -> FastCryptoResult<Vec<(EncryptedObject, Secret<[u8; KEY_SIZE]>)>> {
+    let number_of_shares = key_servers.len() as u8;
+    if threshold > number_of_shares || threshold == 0 {
+        return Err(InvalidInput);
+    }
+    let IBEPublicKeys::BonehFranklinBLS12381(server_public_keys) = public_keys;
+    if server_public_keys.len() != key_servers.len() {
+        return Err(InvalidInput);
+    }
+
+    // 所有对象共享同一个身份，因此哈希和配对只需要计算一次
+    let prepared = ibe::prepare_identity(server_public_keys, &create_full_id(&package_id, &id));
+    let inputs = encryption_inputs
+        .into_iter()
+        .map(|input| (input, generate_random_bytes(rng)))
+        .collect_vec();
+
+    maybe_par_iter!(inputs)
+        .map(|(encryption_input, seed)| {
+            let mut rng = StdRng::from_seed(seed);
+            let base_key = Secret::random(&mut rng);
+            let (encryption_input, extensions) = preprocess_input(encryption_input)?;
+            let dem_key = derive_dem_key(base_key.expose(), &extensions);
+            let ciphertext = encrypt_data(encryption_input, dem_key.expose(), &[])?;
+
+            let (services, encrypted_shares) = encrypt_base_key_with(
+                &mut rng,
+                base_key.expose(),
+                0,
+                key_servers.clone(),
+                threshold,
+                |randomness, shares, services| {
+                    ibe::encrypt_batched_prepared(randomness, shares, &prepared, services)
+                },
+            )?;
+
+            Ok((
+                add_extensions(
+                    EncryptedObject {
+                        version: 0,
+                        package_id,
+                        id: id.clone(),
+                        services,
+                        threshold,
+                        encrypted_shares,
+                        ciphertext,
+                        extensions: vec![],
+                    },
+                    extensions,
+                ),
+                dem_key,
+            ))
+        })
+        .collect()
 }
 
-/// 从基础密钥为特定用途派生一个密钥
-fn derive_key(purpose: KeyPurpose, derived_key: &[u8; KEY_SIZE]) -> [u8; KEY_SIZE] {
-    let hmac_key = HmacKey::from_bytes(derived_key).expect("固定长度");
-    match purpose {
-        KeyPurpose::EncryptedRandomness => hmac_sha3_256(&hmac_key, &[0]).digest,
-        KeyPurpose::DEM => hmac_sha3_256(&hmac_key, &[1]).digest,
+/// 流式加密：从`reader`读取明文，使用分块AES-256-GCM加密后写入`writer`。
+/// 密钥封装与`seal_encrypt`相同，但密文不保存在返回的加密对象中：
+/// 返回对象的`ciphertext`为`Ciphertext::Aes256GcmChunked`且`blob`为空，
+/// 解密时需要将对象和`writer`中的密文一起传给`seal_decrypt_stream`。
+/// 给出`padding`时明文末尾会被填充，与`EncryptionInput::Padded`相同，结果是版本1的对象。
+///
+/// @param package_id 包ID，用于构建完整身份
+/// @param id 内部ID，与package_id一起构成完整身份
+/// @param key_servers 用于加密的密钥服务器列表
+/// @param public_keys 密钥服务器的公钥
+/// @param threshold TSS的阈值，至少需要这么多共享才能重建密钥
+/// @param aad 额外的认证数据（可选）
+/// @param chunk_size 明文块大小
+/// @param padding 明文的填充策略（可选）
+/// @param reader 明文来源
+/// @param writer 密文输出
+/// @return 加密对象（不含密文）和用于加密的派生对称密钥
+#[allow(clippy::too_many_arguments)]
+pub fn seal_encrypt_stream<R: Read, W: Write>(
+    package_id: ObjectID,
+    id: Vec<u8>,
+    key_servers: Vec<ObjectID>,
+    public_keys: &IBEPublicKeys,
+    threshold: u8,
+    aad: Option<Vec<u8>>,
+    chunk_size: u32,
+    padding: Option<Padding>,
+    reader: R,
+    writer: W,
+) -> FastCryptoResult<(EncryptedObject, Secret<[u8; KEY_SIZE]>)> {
+    seal_encrypt_stream_with_rng(
+        &mut thread_rng(),
+        package_id,
+        id,
+        key_servers,
+        public_keys,
+        threshold,
+        aad,
+        chunk_size,
+        padding,
+        reader,
+        writer,
+    )
+}
+
+/// 与`seal_encrypt_stream`相同，但使用调用者提供的随机数生成器。
+///
+/// @param rng 随机数生成器，用于生成基础密钥、秘密共享和IBE随机性
+/// @param package_id 包ID，用于构建完整身份
+/// @param id 内部ID，与package_id一起构成完整身份
+/// @param key_servers 用于加密的密钥服务器列表
+/// @param public_keys 密钥服务器的公钥
+/// @param threshold TSS的阈值，至少需要这么多共享才能重建密钥
+/// @param aad 额外的认证数据（可选）
+/// @param chunk_size 明文块大小
+/// @param padding 明文的填充策略（可选）
+/// @param reader 明文来源
+/// @param writer 密文输出
+/// @return 加密对象（不含密文）和用于加密的派生对称密钥
+#[allow(clippy::too_many_arguments)]
+pub fn seal_encrypt_stream_with_rng<G: AllowedRng, R: Read, W: Write>(
+    rng: &mut G,
+    package_id: ObjectID,
+    id: Vec<u8>,
+    key_servers: Vec<ObjectID>,
+    public_keys: &IBEPublicKeys,
+    threshold: u8,
+    aad: Option<Vec<u8>>,
+    chunk_size: u32,
+    padding: Option<Padding>,
+    reader: R,
+    writer: W,
+) -> FastCryptoResult<(EncryptedObject, Secret<[u8; KEY_SIZE]>)> {
+    let number_of_shares = key_servers.len() as u8;
+    if threshold > number_of_shares || threshold == 0 {
+        return Err(InvalidInput);
     }
+
+    // 填充策略作为扩展参与对称密钥的派生
+    let mut extensions = vec![];
+    if let Some(padding) = padding {
+        padding.padded_length(0)?;
+        header::set_extension(
+            &mut extensions,
+            header::TAG_PADDING,
+            bcs::to_bytes(&padding).expect("Never fails"),
+        );
+    }
+    let base_key = Secret::random(rng);
+    let dem_key = derive_dem_key(base_key.expose(), &extensions);
+
+    // 先完成密钥封装，这样在写入任何密文之前就能发现无效的参数
+    let (services, encrypted_shares) = encrypt_base_key(
+        rng,
+        base_key.expose(),
+        0,
+        &create_full_id(&package_id, &id),
+        key_servers,
+        public_keys,
+        threshold,
+    )?;
+
+    let aad_bytes = aad.as_deref().unwrap_or_default();
+    match padding {
+        Some(padding) => Aes256GcmChunked::encrypt_stream(
+            PaddedReader::new(reader, padding),
+            writer,
+            aad_bytes,
+            dem_key.expose(),
+            chunk_size,
+        ),
+        None => Aes256GcmChunked::encrypt_stream(
+            reader,
+            writer,
+            aad_bytes,
+            dem_key.expose(),
+            chunk_size,
+        ),
+    }?;
+
+    Ok((
+        add_extensions(
+            EncryptedObject {
+                version: 0,
+                package_id,
+                id,
+                services,
+                threshold,
+                encrypted_shares,
+                ciphertext: Ciphertext::Aes256GcmChunked {
+                    blob: vec![],
+                    aad,
+                    chunk_size,
+                },
+                extensions: vec![],
+            },
+            extensions,
+        ),
+        dem_key,
+    ))
 }
 
-impl IBEEncryptions {
-    /// 给定共享和基础密钥，检查共享是否一致
-    /// 例如，检查所有子集的共享是否能重建相同的多项式
-    fn check_share_consistency(
-        &self,
-        shares: &[(u8, [u8; KEY_SIZE])],
-        full_id: &[u8],
-        services: &[(ObjectID, u8)],
-        public_keys: &IBEPublicKeys,
-        base_key: &[u8; KEY_SIZE],
-    ) -> FastCryptoResult<()> {
-        // 从给定的共享计算整个多项式，注意多项式(0) = base_key
-        let polynomial = interpolate(shares)?;
+/// 使用给定的数据加密机制加密数据
+///
+/// @param encryption_input 加密输入数据
+/// @param dem_key 派生对称密钥
+/// @param context 放在额外认证数据之前的头部摘要，不绑定头部时为空，参见`EncryptedObject::context_digest`
+/// @return 密文
+fn encrypt_data(
+    encryption_input: EncryptionInput,
+    dem_key: &[u8; KEY_SIZE],
+    context: &[u8],
+) -> FastCryptoResult<Ciphertext> {
+    Ok(match encryption_input {
+        EncryptionInput::Aes256Gcm { data, aad } => Ciphertext::Aes256Gcm {
+            blob: Aes256Gcm::encrypt(&data, &dem_aad(context, &aad), dem_key),
+            aad,
+        },
+        EncryptionInput::Hmac256Ctr { data, aad } => {
+            let (blob, mac) = Hmac256Ctr::encrypt(&data, &dem_aad(context, &aad), dem_key);
+            Ciphertext::Hmac256Ctr { blob, mac, aad }
+        }
+        EncryptionInput::Plain => Ciphertext::Plain,
+        EncryptionInput::Aes256GcmChunked {
+            data,
+            aad,
+            chunk_size,
+        } => Ciphertext::Aes256GcmChunked {
+            blob: Aes256GcmChunked::encrypt(&data, &dem_aad(context, &aad), dem_key, chunk_size)?,
+            aad,
+            chunk_size,
+        },
+        EncryptionInput::ChaCha20Poly1305 { data, aad } => Ciphertext::ChaCha20Poly1305 {
+            blob: ChaCha20Poly1305::encrypt(&data, &dem_aad(context, &aad), dem_key),
+            aad,
+        },
+        EncryptionInput::Aes256GcmCommitting { data, aad } => {
+            let (blob, commitment) =
+                Aes256GcmCommitting::encrypt(&data, &dem_aad(context, &aad), dem_key);
+            Ciphertext::Aes256GcmCommitting {
+                blob,
+                aad,
+                commitment,
+            }
+        }
+        // 填充和压缩在派生对称密钥之前由`preprocess_input`展开
+        EncryptionInput::Padded { .. } | EncryptionInput::Compressed { .. } => {
+            return Err(InvalidInput)
+        }
+    })
+}
 
-        // 使用派生的密钥解密所有共享
-        let all_shares = self.decrypt_all_shares(full_id, services, public_keys, base_key)?;
+/// 展开`EncryptionInput::Padded`和`EncryptionInput::Compressed`：先压缩再填充内部输入的明文，
+/// 并返回需要加入头部的扩展。其他输入原样返回，且没有扩展
+///
+/// @param encryption_input 加密输入数据
+/// @return 压缩和填充后的加密输入数据，以及需要加入头部的扩展
+fn preprocess_input(
+    encryption_input: EncryptionInput,
+) -> FastCryptoResult<(EncryptionInput, Vec<Extension>)> {
+    match encryption_input {
+        EncryptionInput::Padded { input, padding } => {
+            let (mut input, mut extensions) = match *input {
+                input @ EncryptionInput::Compressed { .. } => preprocess_input(input)?,
+                input => (input, vec![]),
+            };
+            let data = input.data_mut().ok_or(InvalidInput)?;
+            *data = padding.pad(std::mem::take(data))?;
+            header::set_extension(
+                &mut extensions,
+                header::TAG_PADDING,
+                bcs::to_bytes(&padding).expect("Never fails"),
+            );
+            Ok((input, extensions))
+        }
+        EncryptionInput::Compressed { input, compression } => {
+            let mut input = *input;
+            let data = input.data_mut().ok_or(InvalidInput)?;
+            *data = compression.compress(data)?;
+            let mut extensions = vec![];
+            header::set_extension(
+                &mut extensions,
+                header::TAG_COMPRESSION,
+                bcs::to_bytes(&compression).expect("Never fails"),
+            );
+            Ok((input, extensions))
+        }
+        encryption_input => Ok((encryption_input, vec![])),
+    }
+}
+
+/// 将加密时产生的扩展加入加密对象。没有扩展时保持对象不变，否则升级到版本1
+fn add_extensions(
+    encrypted_object: EncryptedObject,
+    extensions: Vec<Extension>,
+) -> EncryptedObject {
+    if extensions.is_empty() {
+        return encrypted_object;
+    }
+    let mut encrypted_object = upgrade_to_v1(&encrypted_object);
+    for Extension { tag, value } in extensions {
+        header::set_extension(&mut encrypted_object.extensions, tag, value);
+    }
+    encrypted_object
+}
+
+/// 数据加密机制实际使用的额外认证数据：头部摘要（如果绑定）加上用户提供的额外认证数据
+fn dem_aad(context: &[u8], aad: &Option<Vec<u8>>) -> Vec<u8> {
+    [context, aad.as_deref().unwrap_or_default()].concat()
+}
+
+/// 使用阈值秘密共享(TSS)将基础密钥分成多个共享，并使用IBE对每个共享进行加密
+///
+/// @param rng 随机数生成器
+/// @param base_key 要分享的基础密钥
+/// @param identity 身份在加密对象中的序号，主身份为0，用于派生加密随机性的密钥
+/// @param full_id 完整ID，用于IBE加密
+/// @param key_servers 用于加密的密钥服务器列表
+/// @param public_keys 密钥服务器的公钥
+/// @param threshold TSS的阈值
+/// @return 密钥服务器及其共享索引列表，以及加密的共享
+fn encrypt_base_key<R: AllowedRng>(
+    rng: &mut R,
+    base_key: &[u8; KEY_SIZE],
+    identity: u8,
+    full_id: &[u8],
+    key_servers: Vec<ObjectID>,
+    public_keys: &IBEPublicKeys,
+    threshold: u8,
+) -> FastCryptoResult<(Vec<(ObjectID, u8)>, IBEEncryptions)> {
+    let IBEPublicKeys::BonehFranklinBLS12381(public_keys) = public_keys;
+    encrypt_base_key_with(
+        rng,
+        base_key,
+        identity,
+        key_servers,
+        threshold,
+        |randomness, shares, services| {
+            encrypt_batched_deterministic(randomness, shares, public_keys, full_id, services)
+        },
+    )
+}
+
+/// 与`encrypt_base_key`相同，但共享由`encrypt_batched`加密，
+/// 例如`seal_encrypt_batch`使用预计算的身份加密共享
+///
+/// @param rng 随机数生成器
+/// @param base_key 要分享的基础密钥
+/// @param identity 身份在加密对象中的序号，主身份为0，用于派生加密随机性的密钥
+/// @param key_servers 用于加密的密钥服务器列表
+/// @param threshold TSS的阈值
+/// @param encrypt_batched 使用给定的随机性加密与密钥服务器顺序相同的共享，返回随机数和加密的共享
+/// @return 密钥服务器及其共享索引列表，以及加密的共享
+fn encrypt_base_key_with<R: AllowedRng>(
+    rng: &mut R,
+    base_key: &[u8; KEY_SIZE],
+    identity: u8,
+    key_servers: Vec<ObjectID>,
+    threshold: u8,
+    encrypt_batched: impl FnOnce(
+        &ibe::Randomness,
+        &[ibe::Plaintext],
+        &[ibe::Info],
+    ) -> FastCryptoResult<(ibe::Nonce, Vec<ibe::Ciphertext>)>,
+) -> FastCryptoResult<(Vec<(ObjectID, u8)>, IBEEncryptions)> {
+    let number_of_shares = key_servers.len() as u8;
+
+    // 使用阈值秘密共享对基础密钥进行分享
+    let SecretSharing {
+        indices, shares, ..
+    } = split(rng, *base_key, threshold, number_of_shares)?;
+
+    // 将密钥服务器ID与共享索引配对
+    let services = key_servers.into_iter().zip(indices).collect::<Vec<_>>();
+
+    let encrypted_shares =
+        encrypt_shares_with(rng, base_key, identity, &services, &shares, encrypt_batched)?;
+    Ok((services, encrypted_shares))
+}
+
+/// 使用IBE对每个共享进行加密，并加密IBE随机性用于验证基础密钥
+///
+/// @param rng 随机数生成器
+/// @param base_key 被分享的基础密钥
+/// @param identity 身份在加密对象中的序号，主身份为0，用于派生加密随机性的密钥
+/// @param full_id 完整ID，用于IBE加密
+/// @param services 密钥服务器及其共享索引列表，索引必须互不相同
+/// @param shares 与`services`顺序相同的共享
+/// @param public_keys 密钥服务器的公钥
+/// @return 加密的共享
+fn encrypt_shares<R: AllowedRng>(
+    rng: &mut R,
+    base_key: &[u8; KEY_SIZE],
+    identity: u8,
+    full_id: &[u8],
+    services: &[(ObjectID, u8)],
+    shares: &[[u8; KEY_SIZE]],
+    public_keys: &IBEPublicKeys,
+) -> FastCryptoResult<IBEEncryptions> {
+    // 根据公钥类型选择IBE加密方法
+    match public_keys {
+        IBEPublicKeys::BonehFranklinBLS12381(public_keys) => {
+            // 验证公钥数量是否正确
+            if public_keys.len() != services.len() {
+                return Err(InvalidInput);
+            }
+            // 使用共享索引作为IBE解密的索引参数，允许为同一身份的多个共享使用相同的公钥加密
+            encrypt_shares_with(
+                rng,
+                base_key,
+                identity,
+                services,
+                shares,
+                |randomness, shares, services| {
+                    encrypt_batched_deterministic(
+                        randomness,
+                        shares,
+                        public_keys,
+                        full_id,
+                        services,
+                    )
+                },
+            )
+        }
+    }
+}
+
+/// 生成IBE随机性，使用`encrypt_batched`加密共享，并加密随机性用于验证基础密钥
+///
+/// @param rng 随机数生成器
+/// @param base_key 被分享的基础密钥
+/// @param identity 身份在加密对象中的序号，主身份为0，用于派生加密随机性的密钥
+/// @param services 密钥服务器及其共享索引列表，索引必须互不相同
+/// @param shares 与`services`顺序相同的共享
+/// @param encrypt_batched 使用给定的随机性加密共享，返回随机数和加密的共享
+/// @return 加密的共享
+fn encrypt_shares_with<R: AllowedRng>(
+    rng: &mut R,
+    base_key: &[u8; KEY_SIZE],
+    identity: u8,
+    services: &[(ObjectID, u8)],
+    shares: &[[u8; KEY_SIZE]],
+    encrypt_batched: impl FnOnce(
+        &ibe::Randomness,
+        &[ibe::Plaintext],
+        &[ibe::Info],
+    ) -> FastCryptoResult<(ibe::Nonce, Vec<ibe::Ciphertext>)>,
+) -> FastCryptoResult<IBEEncryptions> {
+    if shares.len() != services.len() {
+        return Err(InvalidInput);
+    }
+    // 生成随机值用于IBE加密
+    let randomness = ibe::Randomness::rand(rng);
+
+    // 使用IBE加密共享
+    let (nonce, encrypted_shares) = encrypt_batched(&randomness, shares, services)?;
+
+    // 加密随机值，用于验证
+    let encrypted_randomness =
+        ibe::encrypt_randomness(&randomness, randomness_key(base_key, identity).expose());
+    Ok(IBEEncryptions::BonehFranklinBLS12381 {
+        nonce,
+        encrypted_shares,
+        encrypted_randomness,
+    })
+}
+
+/// 解密给定的密文。解密过程如下：
+/// 1. 使用用户私钥为给定的随机数解封IBE密钥
+/// 2. 使用IBE密钥解密共享
+/// 3. 使用足够数量的共享重建AES密钥
+/// 4. 使用AES密钥解密密文
+///
+/// @param encrypted_object 加密对象，由`seal_encrypt`生成
+/// @param user_secret_keys 用户私钥。如果没有提供公钥，假设这些密钥已经过验证（例如使用`verify_user_secret_keys`），否则解密将失败，或者在使用`Plain`模式的情况下，派生的密钥将不正确
+/// @param public_keys 密钥服务器的公钥。如果提供，使用的用户私钥会先被批量验证，所有共享将被解密并检查一致性
+/// @return 解密后的明文数据，或者如果使用了`Plain`模式，则返回派生的密钥
+pub fn seal_decrypt(
+    encrypted_object: &EncryptedObject,
+    user_secret_keys: &IBEUserSecretKeys,
+    public_keys: Option<&IBEPublicKeys>,
+) -> FastCryptoResult<Vec<u8>> {
+    seal_decrypt_with_max_size(
+        encrypted_object,
+        user_secret_keys,
+        public_keys,
+        DEFAULT_MAX_DECOMPRESSED_SIZE,
+    )
+}
+
+/// 与`seal_decrypt`相同，但可以指定解压缩后明文的最大长度。
+/// `seal_decrypt`使用`compression::DEFAULT_MAX_DECOMPRESSED_SIZE`，对于没有压缩的对象这个参数没有作用。
+///
+/// @param encrypted_object 加密对象，由`seal_encrypt`生成
+/// @param user_secret_keys 用户私钥，参见`seal_decrypt`
+/// @param public_keys 密钥服务器的公钥，参见`seal_decrypt`
+/// @param max_decompressed_size 解压缩后明文的最大长度，超过时返回错误，用于防止解压缩炸弹
+/// @return 解密后的明文数据，或者如果使用了`Plain`模式，则返回派生的密钥
+pub fn seal_decrypt_with_max_size(
+    encrypted_object: &EncryptedObject,
+    user_secret_keys: &IBEUserSecretKeys,
+    public_keys: Option<&IBEPublicKeys>,
+    max_decompressed_size: usize,
+) -> FastCryptoResult<Vec<u8>> {
+    let base_key = decrypt_base_key(encrypted_object, user_secret_keys, public_keys)?;
+    decrypt_data(encrypted_object, base_key.expose(), max_decompressed_size)
+}
+
+/// 鲁棒解密：与`seal_decrypt`相同，但当某些密钥服务器返回了无效的用户私钥时，
+/// 找出这些密钥服务器，并在剩余的有效私钥足够时仍然解密。
+///
+/// 首先使用所有持有的用户私钥解密共享，不逐个验证私钥。然后使用Berlekamp-Welch算法纠正其中的错误，
+/// 错误的共享不超过⌊(n - t) / 2⌋个时只需要多项式时间。纠错失败时依次尝试阈值大小的子集，
+/// 直到重建的基础密钥通过加密的随机性的验证。尝试`MAX_ROBUST_DECRYPTION_ATTEMPTS`次仍然失败时，
+/// 逐个验证用户私钥，使用有效私钥解密的共享重建基础密钥。
+///
+/// 通过验证的基础密钥是唯一的，使用它可以得到加密方生成的所有共享。有效的用户私钥总是解密出相同的共享，
+/// 因此解密结果不同的密钥服务器返回了无效的用户私钥。如果加密方生成的共享不在同一个多项式上，
+/// 加密对象本身无效，返回与`seal_decrypt`相同的错误，而不会归咎于密钥服务器。
+///
+/// 只支持使用单层阈值共享的对象，多身份对象只尝试主身份。
+///
+/// @param encrypted_object 加密对象，由`seal_encrypt`生成
+/// @param user_secret_keys 用户私钥，不需要事先验证
+/// @param public_keys 每个共享对应的密钥服务器公钥，与`services`顺序相同
+/// @return 解密后的明文数据（或者`Plain`模式下的派生密钥），以及行为不当的密钥服务器列表
+pub fn seal_decrypt_robust(
+    encrypted_object: &EncryptedObject,
+    user_secret_keys: &IBEUserSecretKeys,
+    public_keys: &IBEPublicKeys,
+) -> Result<(Vec<u8>, Vec<ObjectID>), RobustDecryptionError> {
+    seal_decrypt_robust_with_max_size(
+        encrypted_object,
+        user_secret_keys,
+        public_keys,
+        DEFAULT_MAX_DECOMPRESSED_SIZE,
+    )
+}
+
+/// 与`seal_decrypt_robust`相同，但可以指定解压缩后明文的最大长度，参见`seal_decrypt_with_max_size`。
+///
+/// @param encrypted_object 加密对象，由`seal_encrypt`生成
+/// @param user_secret_keys 用户私钥，不需要事先验证
+/// @param public_keys 每个共享对应的密钥服务器公钥，与`services`顺序相同
+/// @param max_decompressed_size 解压缩后明文的最大长度，超过时返回错误，用于防止解压缩炸弹
+/// @return 解密后的明文数据（或者`Plain`模式下的派生密钥），以及行为不当的密钥服务器列表
+pub fn seal_decrypt_robust_with_max_size(
+    encrypted_object: &EncryptedObject,
+    user_secret_keys: &IBEUserSecretKeys,
+    public_keys: &IBEPublicKeys,
+    max_decompressed_size: usize,
+) -> Result<(Vec<u8>, Vec<ObjectID>), RobustDecryptionError> {
+    if encrypted_object.version > MAX_SUPPORTED_VERSION {
+        return Err(InvalidInput.into());
+    }
+    encrypted_object.validate_header()?;
+    if encrypted_object.access_tree()?.is_some() {
+        return Err(InvalidInput.into());
+    }
+
+    let EncryptedObject {
+        package_id,
+        id,
+        services,
+        threshold,
+        encrypted_shares,
+        ..
+    } = encrypted_object;
+    let full_id = create_full_id(package_id, id);
+    let (
+        IBEEncryptions::BonehFranklinBLS12381 {
+            nonce,
+            encrypted_shares: shares,
+            ..
+        },
+        IBEUserSecretKeys::BonehFranklinBLS12381(user_secret_keys),
+        IBEPublicKeys::BonehFranklinBLS12381(share_public_keys),
+    ) = (encrypted_shares, user_secret_keys, public_keys);
+    if shares.len() != services.len() {
+        return Err(InvalidInput.into());
+    }
+    check_public_keys(share_public_keys, services)?;
+
+    // 使用持有的用户私钥解密共享，此时还没有验证私钥
+    let threshold = *threshold as usize;
+    let available = services
+        .iter()
+        .zip(shares)
+        .filter_map(|(service, share)| {
+            user_secret_keys.get(&service.0).map(|usk| {
+                (
+                    service.0,
+                    (
+                        service.1,
+                        ibe::decrypt(nonce, share, usk, &full_id, service),
+                    ),
+                )
+            })
+        })
+        .collect_vec();
+    if available.len() < threshold {
+        return Err(InvalidInput.into());
+    }
+    let available_shares = available.iter().map(|(_, share)| *share).collect_vec();
+
+    // 首先尝试纠错解码得到的一致共享，然后尝试阈值大小的子集，直到重建的基础密钥通过验证
+    let decoded = decode(&available_shares, threshold as u8)
+        .ok()
+        .map(|(_, invalid)| {
+            available_shares
+                .iter()
+                .filter(|(index, _)| !invalid.contains(index))
+                .take(threshold)
+                .copied()
+                .collect_vec()
+        })
+        .filter(|consistent| consistent.len() == threshold);
+    let candidates = decoded
+        .into_iter()
+        .chain(available_shares.iter().copied().combinations(threshold))
+        .take(MAX_ROBUST_DECRYPTION_ATTEMPTS);
+    let mut base_key = None;
+    for subset in candidates {
+        let candidate = Secret::new(combine(&subset)?);
+        if encrypted_shares
+            .verify_base_key(candidate.expose(), 0)
+            .is_ok()
+        {
+            base_key = Some(candidate);
+            break;
+        }
+    }
+    let base_key = match base_key {
+        Some(base_key) => base_key,
+        None => {
+            // 逐个验证用户私钥，只使用有效私钥解密的共享
+            let invalid =
+                find_invalid_key_servers(&full_id, services, user_secret_keys, share_public_keys);
+            let valid = available
+                .iter()
+                .filter(|(id, _)| !invalid.contains(id))
+                .map(|(_, share)| *share)
+                .take(threshold)
+                .collect_vec();
+            if valid.len() < threshold {
+                return Err(RobustDecryptionError::MisbehavingKeyServers(invalid));
+            }
+            let base_key = Secret::new(combine(&valid)?);
+            if encrypted_shares
+                .verify_base_key(base_key.expose(), 0)
+                .is_err()
+            {
+                return Err(GeneralError("共享不一致".to_string()).into());
+            }
+            base_key
+        }
+    };
+
+    // 检查加密方生成的共享在同一个多项式上，并且该多项式在0处的值是基础密钥
+    let all_shares = encrypted_shares.decrypt_all_shares(
+        &full_id,
+        services,
+        public_keys,
+        base_key.expose(),
+        0,
+    )?;
+    let polynomial = interpolate(&all_shares[..threshold])?;
+    if combine(&all_shares[..threshold])? != *base_key.expose()
+        || all_shares
+            .iter()
+            .any(|(index, share)| polynomial(*index) != *share)
+    {
+        return Err(GeneralError("共享不一致".to_string()).into());
+    }
+
+    // 有效的用户私钥总是解密出加密方生成的共享
+    let misbehaving = available
+        .iter()
+        .filter(|(_, share)| !all_shares.contains(share))
+        .map(|(id, _)| *id)
+        .unique()
+        .collect();
+    Ok((
+        decrypt_data(encrypted_object, base_key.expose(), max_decompressed_size)?,
+        misbehaving,
+    ))
+}
+
+/// 验证签名的加密对象确实由预期的发送者创建，然后使用`seal_decrypt`解密。
+/// 签名无效或者发送者不是预期的发送者时，不会尝试解密。
+///
+/// @param signed_object 签名的加密对象，由`seal_encrypt_and_sign`生成
+/// @param expected_sender 预期的发送者
+/// @param user_secret_keys 用户私钥，参见`seal_decrypt`
+/// @param public_keys 密钥服务器的公钥，参见`seal_decrypt`
+/// @return 解密后的明文数据，或者如果使用了`Plain`模式，则返回派生的密钥
+pub fn verify_and_decrypt(
+    signed_object: &SignedEncryptedObject,
+    expected_sender: &Sender,
+    user_secret_keys: &IBEUserSecretKeys,
+    public_keys: Option<&IBEPublicKeys>,
+) -> FastCryptoResult<Vec<u8>> {
+    verify_and_decrypt_with_max_size(
+        signed_object,
+        expected_sender,
+        user_secret_keys,
+        public_keys,
+        DEFAULT_MAX_DECOMPRESSED_SIZE,
+    )
+}
+
+/// 与`verify_and_decrypt`相同，但可以指定解压缩后明文的最大长度，参见`seal_decrypt_with_max_size`。
+///
+/// @param signed_object 签名的加密对象，由`seal_encrypt_and_sign`生成
+/// @param expected_sender 预期的发送者
+/// @param user_secret_keys 用户私钥，参见`seal_decrypt`
+/// @param public_keys 密钥服务器的公钥，参见`seal_decrypt`
+/// @param max_decompressed_size 解压缩后明文的最大长度，超过时返回错误，用于防止解压缩炸弹
+/// @return 解密后的明文数据，或者如果使用了`Plain`模式，则返回派生的密钥
+pub fn verify_and_decrypt_with_max_size(
+    signed_object: &SignedEncryptedObject,
+    expected_sender: &Sender,
+    user_secret_keys: &IBEUserSecretKeys,
+    public_keys: Option<&IBEPublicKeys>,
+    max_decompressed_size: usize,
+) -> FastCryptoResult<Vec<u8>> {
+    if signed_object.verify()? != *expected_sender {
+        return Err(GeneralError("Unexpected sender".to_string()));
+    }
+    seal_decrypt_with_max_size(
+        &signed_object.encrypted_object,
+        user_secret_keys,
+        public_keys,
+        max_decompressed_size,
+    )
+}
+
+/// 使用基础密钥派生对称密钥并解密密文
+///
+/// @param encrypted_object 加密对象，如果绑定了头部，头部摘要会被加入额外认证数据
+/// @param base_key 基础密钥
+/// @param max_decompressed_size 解压缩后明文的最大长度
+/// @return 解密、移除填充并解压缩后的明文数据，或者如果使用了`Plain`模式，则返回派生的密钥
+fn decrypt_data(
+    encrypted_object: &EncryptedObject,
+    base_key: &[u8; KEY_SIZE],
+    max_decompressed_size: usize,
+) -> FastCryptoResult<Vec<u8>> {
+    // 派生对称密钥并解密密文
+    let dem_key = derive_dem_key(base_key, &encrypted_object.extensions);
+    let dem_key = dem_key.expose();
+    let aad = encrypted_object.dem_aad();
+    let plaintext = match &encrypted_object.ciphertext {
+        Ciphertext::Aes256Gcm { blob, .. } => Aes256Gcm::decrypt(blob, &aad, dem_key),
+        Ciphertext::Hmac256Ctr { blob, mac, .. } => Hmac256Ctr::decrypt(blob, mac, &aad, dem_key),
+        Ciphertext::Plain => return Ok(dem_key.to_vec()),
+        Ciphertext::Aes256GcmChunked {
+            blob, chunk_size, ..
+        } => Aes256GcmChunked::decrypt(blob, &aad, dem_key, *chunk_size),
+        Ciphertext::ChaCha20Poly1305 { blob, .. } => ChaCha20Poly1305::decrypt(blob, &aad, dem_key),
+        Ciphertext::Aes256GcmCommitting {
+            blob, commitment, ..
+        } => Aes256GcmCommitting::decrypt(blob, commitment, &aad, dem_key),
+    }?;
+    encrypted_object.decode_plaintext(plaintext, max_decompressed_size)
+}
+
+/// 流式解密由`seal_encrypt_stream`生成的加密对象。
+/// 密钥重建与`seal_decrypt`相同，密文从`reader`中读取，解密后的明文写入`writer`。
+/// 如果对象带有填充，写入`writer`之前移除填充。不支持压缩的对象。
+/// 如果返回错误，已经写入`writer`的数据必须被丢弃。
+///
+/// @param encrypted_object 加密对象，由`seal_encrypt_stream`生成
+/// @param user_secret_keys 用户私钥
+/// @param public_keys 密钥服务器的公钥。如果提供，所有共享将被解密并检查一致性
+/// @param reader 密文来源
+/// @param writer 明文输出
+/// @return 写入`writer`的明文字节数
+pub fn seal_decrypt_stream<R: Read, W: Write>(
+    encrypted_object: &EncryptedObject,
+    user_secret_keys: &IBEUserSecretKeys,
+    public_keys: Option<&IBEPublicKeys>,
+    reader: R,
+    writer: W,
+) -> FastCryptoResult<u64> {
+    // 只支持密文单独存储的分块对象
+    let chunk_size = match &encrypted_object.ciphertext {
+        Ciphertext::Aes256GcmChunked {
+            blob, chunk_size, ..
+        } if blob.is_empty() => *chunk_size,
+        _ => return Err(InvalidInput),
+    };
+    if encrypted_object.compression()?.is_some() {
+        return Err(InvalidInput);
+    }
+    let padding = encrypted_object.padding()?;
+    let base_key = decrypt_base_key(encrypted_object, user_secret_keys, public_keys)?;
+    let dem_key = derive_dem_key(base_key.expose(), &encrypted_object.extensions);
+    let aad = encrypted_object.dem_aad();
+    match padding {
+        Some(_) => {
+            let mut writer = UnpaddedWriter::new(writer);
+            Aes256GcmChunked::decrypt_stream(
+                reader,
+                &mut writer,
+                &aad,
+                dem_key.expose(),
+                chunk_size,
+            )?;
+            writer.finish()
+        }
+        None => {
+            Aes256GcmChunked::decrypt_stream(reader, writer, &aad, dem_key.expose(), chunk_size)
+        }
+    }
+}
+
+/// 使用用户私钥重建加密对象的对称密钥（DEM密钥），而不解密密文。
+/// 当需要多次调用`decrypt_range`时，可以只重建一次密钥。
+///
+/// @param encrypted_object 加密对象
+/// @param user_secret_keys 用户私钥
+/// @param public_keys 密钥服务器的公钥。如果提供，所有共享将被解密并检查一致性
+/// @return 用于加密密文的派生对称密钥，与`seal_encrypt`返回的密钥相同
+pub fn seal_decrypt_key(
+    encrypted_object: &EncryptedObject,
+    user_secret_keys: &IBEUserSecretKeys,
+    public_keys: Option<&IBEPublicKeys>,
+) -> FastCryptoResult<Secret<[u8; KEY_SIZE]>> {
+    let base_key = decrypt_base_key(encrypted_object, user_secret_keys, public_keys)?;
+    Ok(derive_dem_key(
+        base_key.expose(),
+        &encrypted_object.extensions,
+    ))
+}
+
+/// 只解密加密对象中明文`range`范围内的字节。
+/// 只有与`range`相交的块会被认证和解密，因此只支持`Ciphertext::Aes256GcmChunked`且密文内联在对象中的情况。
+/// 如果明文被填充或压缩（参见`EncryptionInput::Padded`和`EncryptionInput::Compressed`），
+/// 这里的范围对应填充或压缩后的数据，调用者需要自行处理。
+///
+/// @param encrypted_object 加密对象，由`seal_encrypt`使用`EncryptionInput::Aes256GcmChunked`生成
+/// @param dem_key 派生对称密钥，由`seal_encrypt`或`seal_decrypt_key`返回
+/// @param range 明文中的字节范围
+/// @return `range`范围内的明文
+pub fn decrypt_range(
+    encrypted_object: &EncryptedObject,
+    dem_key: &[u8; KEY_SIZE],
+    range: Range<u64>,
+) -> FastCryptoResult<Vec<u8>> {
+    match &encrypted_object.ciphertext {
+        Ciphertext::Aes256GcmChunked {
+            blob, chunk_size, ..
+        } => Aes256GcmChunked::decrypt_range(
+            blob,
+            &encrypted_object.dem_aad(),
+            dem_key,
+            *chunk_size,
+            range,
+        ),
+        _ => Err(InvalidInput),
+    }
+}
+
+/// 与`decrypt_range`相同，但密文单独存储并从可定位的`reader`中按需读取，
+/// 用于`seal_encrypt_stream`生成的加密对象。
+///
+/// @param encrypted_object 加密对象，由`seal_encrypt_stream`生成
+/// @param dem_key 派生对称密钥，由`seal_encrypt_stream`或`seal_decrypt_key`返回
+/// @param reader 包含完整密文的可定位数据源
+/// @param range 明文中的字节范围
+/// @return `range`范围内的明文
+pub fn decrypt_range_seekable<R: Read + Seek>(
+    encrypted_object: &EncryptedObject,
+    dem_key: &[u8; KEY_SIZE],
+    reader: R,
+    range: Range<u64>,
+) -> FastCryptoResult<Vec<u8>> {
+    match &encrypted_object.ciphertext {
+        Ciphertext::Aes256GcmChunked {
+            blob, chunk_size, ..
+        } if blob.is_empty() => Aes256GcmChunked::decrypt_range_seekable(
+            reader,
+            &encrypted_object.dem_aad(),
+            dem_key,
+            *chunk_size,
+            range,
+        ),
+        _ => Err(InvalidInput),
+    }
+}
+
+/// 使用用户私钥重建加密对象的基础密钥。基础密钥可以派生出所有其他密钥，
+/// 通常只用于`reshare`，调用者应尽快丢弃它。
+///
+/// @param encrypted_object 加密对象
+/// @param user_secret_keys 用户私钥
+/// @param public_keys 密钥服务器的公钥。如果提供，所有共享将被解密并检查一致性
+/// @return 重建的基础密钥
+pub fn seal_decrypt_base_key(
+    encrypted_object: &EncryptedObject,
+    user_secret_keys: &IBEUserSecretKeys,
+    public_keys: Option<&IBEPublicKeys>,
+) -> FastCryptoResult<Secret<[u8; KEY_SIZE]>> {
+    decrypt_base_key(encrypted_object, user_secret_keys, public_keys)
+}
+
+/// 将加密对象的基础密钥重新分享给新的密钥服务器集合，而不重新加密数据。
+/// 基础密钥使用新的随机性重新分割并用新的公钥加密，`ciphertext`、版本和头部扩展保持不变，
+/// 因此新对象的派生对称密钥与原对象相同。
+///
+/// 注意：这不会撤销旧的密钥服务器的访问权限。任何保存了原对象的人仍然可以通过旧的密钥服务器解密。
+/// 对于多身份对象，只有主身份被重新分享，其他身份保持不变。
+/// 新对象总是使用单层阈值共享，原对象的访问树扩展会被移除。
+/// 由`seal_encrypt_context_bound`生成的对象不能重新分享。
+///
+/// @param encrypted_object 要重新分享的加密对象
+/// @param base_key 加密对象的基础密钥，参见`seal_decrypt_base_key`
+/// @param key_servers 新的密钥服务器列表
+/// @param public_keys 新的密钥服务器的公钥
+/// @param threshold 新的阈值
+/// @return 使用新的密钥服务器和阈值的加密对象
+pub fn reshare(
+    encrypted_object: &EncryptedObject,
+    base_key: &[u8; KEY_SIZE],
+    key_servers: Vec<ObjectID>,
+    public_keys: &IBEPublicKeys,
+    threshold: u8,
+) -> FastCryptoResult<EncryptedObject> {
+    reshare_with_rng(
+        &mut thread_rng(),
+        encrypted_object,
+        base_key,
+        key_servers,
+        public_keys,
+        threshold,
+    )
+}
+
+/// 与`reshare`相同，但使用调用者提供的随机数生成器。
+///
+/// @param rng 随机数生成器，用于生成新的秘密共享和IBE随机性
+/// @param encrypted_object 要重新分享的加密对象
+/// @param base_key 加密对象的基础密钥，参见`seal_decrypt_base_key`
+/// @param key_servers 新的密钥服务器列表
+/// @param public_keys 新的密钥服务器的公钥
+/// @param threshold 新的阈值
+/// @return 使用新的密钥服务器和阈值的加密对象
+pub fn reshare_with_rng<R: AllowedRng>(
+    rng: &mut R,
+    encrypted_object: &EncryptedObject,
+    base_key: &[u8; KEY_SIZE],
+    key_servers: Vec<ObjectID>,
+    public_keys: &IBEPublicKeys,
+    threshold: u8,
+) -> FastCryptoResult<EncryptedObject> {
+    let number_of_shares = key_servers.len() as u8;
+    if threshold > number_of_shares || threshold == 0 {
+        return Err(InvalidInput);
+    }
+
+    // 绑定的头部摘要包含密钥服务器，重新分享后密文将无法解密
+    if encrypted_object.context_digest().is_some() {
+        return Err(GeneralError(
+            "Cannot reshare an object bound to its header".to_string(),
+        ));
+    }
+
+    // 确保基础密钥属于该对象，否则新对象将无法解密
+    encrypted_object
+        .encrypted_shares
+        .verify_base_key(base_key, 0)?;
+
+    let (services, encrypted_shares) = encrypt_base_key(
+        rng,
+        base_key,
+        0,
+        &create_full_id(&encrypted_object.package_id, &encrypted_object.id),
+        key_servers,
+        public_keys,
+        threshold,
+    )?;
+
+    Ok(EncryptedObject {
+        services,
+        threshold,
+        encrypted_shares,
+        extensions: encrypted_object
+            .extensions
+            .iter()
+            .filter(|ext| ext.tag != header::TAG_ACCESS_TREE)
+            .cloned()
+            .collect(),
+        ..encrypted_object.clone()
+    })
+}
+
+/// 使用足够数量的旧密钥服务器的用户私钥重建基础密钥，然后将其重新分享给新的密钥服务器集合。
+/// 参见`reshare`。
+///
+/// @param encrypted_object 要重新分享的加密对象
+/// @param user_secret_keys 旧的密钥服务器的用户私钥
+/// @param old_public_keys 旧的密钥服务器的公钥。如果提供，所有共享将被解密并检查一致性
+/// @param key_servers 新的密钥服务器列表
+/// @param public_keys 新的密钥服务器的公钥
+/// @param threshold 新的阈值
+/// @return 使用新的密钥服务器和阈值的加密对象
+pub fn reshare_with_user_secret_keys(
+    encrypted_object: &EncryptedObject,
+    user_secret_keys: &IBEUserSecretKeys,
+    old_public_keys: Option<&IBEPublicKeys>,
+    key_servers: Vec<ObjectID>,
+    public_keys: &IBEPublicKeys,
+    threshold: u8,
+) -> FastCryptoResult<EncryptedObject> {
+    let base_key = decrypt_base_key(encrypted_object, user_secret_keys, old_public_keys)?;
+    reshare(
+        encrypted_object,
+        base_key.expose(),
+        key_servers,
+        public_keys,
+        threshold,
+    )
+}
+
+/// 使用用户私钥解密足够数量的共享并重建基础密钥
+///
+/// 对于多身份对象，按顺序尝试每个有足够用户私钥的身份，直到重建的基础密钥通过验证。
+/// 由于用户私钥只按密钥服务器区分，为其他身份提取的私钥会重建出错误的基础密钥，因此验证是必需的。
+///
+/// @param encrypted_object 加密对象
+/// @param user_secret_keys 用户私钥
+/// @param public_keys 密钥服务器的公钥。如果提供，所有共享将被解密并检查一致性
+/// @return 重建的基础密钥
+fn decrypt_base_key(
+    encrypted_object: &EncryptedObject,
+    user_secret_keys: &IBEUserSecretKeys,
+    public_keys: Option<&IBEPublicKeys>,
+) -> FastCryptoResult<Secret<[u8; KEY_SIZE]>> {
+    // 检查版本兼容性和头部扩展
+    if encrypted_object.version > MAX_SUPPORTED_VERSION {
+        return Err(InvalidInput);
+    }
+    encrypted_object.validate_header()?;
+
+    // 访问树只适用于主身份
+    let identities = encrypted_object.identities()?;
+    let access_tree = encrypted_object.access_tree()?;
+    if identities.len() == 1 {
+        return decrypt_identity_base_key(
+            &identities[0],
+            0,
+            access_tree.as_ref(),
+            user_secret_keys,
+            public_keys,
+        );
+    }
+    let mut result = Err(InvalidInput);
+    for (i, identity) in identities.iter().enumerate() {
+        let access_tree = access_tree.as_ref().filter(|_| i == 0);
+        result = decrypt_identity_base_key(
+            identity,
+            i as u8,
+            access_tree,
+            user_secret_keys,
+            public_keys,
+        )
+        .and_then(|base_key| {
+                identity
+                    .encrypted_shares
+                    .verify_base_key(base_key.expose(), i as u8)
+                    .map(|()| base_key)
+            });
+        if result.is_ok() {
+            break;
+        }
+    }
+    result
+}
+
+/// 使用用户私钥解密某个身份的足够数量的共享并重建基础密钥
+///
+/// @param identity 加密对象中的身份
+/// @param index 身份在加密对象中的序号，主身份为0
+/// @param access_tree 身份的访问树。如果为None，则使用单层阈值共享
+/// @param user_secret_keys 用户私钥
+/// @param public_keys 密钥服务器的公钥。如果提供，所有共享将被解密并检查一致性
+/// @return 重建的基础密钥
+fn decrypt_identity_base_key(
+    identity: &IdentityEncryption,
+    index: u8,
+    access_tree: Option<&AccessTree>,
+    user_secret_keys: &IBEUserSecretKeys,
+    public_keys: Option<&IBEPublicKeys>,
+) -> FastCryptoResult<Secret<[u8; KEY_SIZE]>> {
+    let IdentityEncryption {
+        package_id,
+        id,
+        services,
+        threshold,
+        encrypted_shares,
+    } = identity;
+
+    // 创建完整ID，用于IBE解密
+    let full_id = create_full_id(package_id, id);
+
+    // 根据IBE类型解密共享
+    let shares = match (&encrypted_shares, user_secret_keys) {
+        (
+            IBEEncryptions::BonehFranklinBLS12381 {
+                nonce,
+                encrypted_shares,
+                ..
+            },
+            IBEUserSecretKeys::BonehFranklinBLS12381(user_secret_keys),
+        ) => {
+            // 检查加密对象是否有效
+            // 例如，每个服务是否都有一个对应的加密共享
+            if encrypted_shares.len() != services.len() {
+                return Err(InvalidInput);
+            }
+
+            // 找出我们拥有私钥的服务索引
+            let service_indices: Vec<usize> = services
+                .iter()
+                .enumerate()
+                .filter(|(_, (id, _))| user_secret_keys.contains_key(id))
+                .map(|(i, _)| i)
+                .collect();
+            // 检查我们是否有足够的私钥来达到阈值
+            if service_indices.len() < *threshold as usize {
+                return Err(InvalidInput);
+            }
+
+            // 如果提供了公钥，先批量验证用户私钥，以便指出无效的私钥而不是只报告共享不一致
+            if let Some(IBEPublicKeys::BonehFranklinBLS12381(public_keys)) = public_keys {
+                verify_identity_user_secret_keys(
+                    &full_id,
+                    services,
+                    user_secret_keys,
+                    public_keys,
+                )?;
+            }
+
+            // 使用私钥解密每个共享，共享在使用后清零
+            Zeroizing::new(
+                service_indices
+                    .into_iter()
+                    .map(|i| {
+                        let index = services[i].1;
+                        (
+                            index,
+                            ibe::decrypt(
+                                nonce,
+                                &encrypted_shares[i],
+                                user_secret_keys
+                                    .get(&services[i].0)
+                                    .expect("这不应该发生：上面已经检查过这个私钥是否可用"),
+                                &full_id,
+                                &services[i],
+                            ),
+                        )
+                    })
+                    .collect_vec(),
+            )
+        }
+    };
+
+    // 使用共享重建基础密钥，访问树的叶子位置为共享索引减1
+    let base_key = Secret::new(match access_tree {
+        Some(tree) => combine_tree(
+            tree,
+            &Zeroizing::new(
+                shares
+                    .iter()
+                    .map(|(i, share)| (i - 1, *share))
+                    .collect_vec(),
+            ),
+        )?,
+        None => combine(&shares)?,
+    });
+
+    // 如果提供了公钥，可以解密所有共享并检查一致性
+    if let Some(public_keys) = public_keys {
+        match access_tree {
+            Some(tree) => encrypted_shares.check_tree_consistency(
+                tree,
+                &full_id,
+                services,
+                public_keys,
+                base_key.expose(),
+                index,
+            )?,
+            None => encrypted_shares.check_share_consistency(
+                &shares,
+                &full_id,
+                services,
+                public_keys,
+                base_key.expose(),
+                index,
+            )?,
+        }
+    }
+    Ok(base_key)
+}
+
+/// 批量验证加密对象主身份的用户私钥。只验证`user_secret_keys`中属于对象密钥服务器的私钥，
+/// 其余私钥被忽略。可以在`seal_decrypt`之前使用，例如验证从多个密钥服务器获取的私钥；
+/// 向`seal_decrypt`提供公钥时也会自动进行相同的验证。
+///
+/// @param encrypted_object 加密对象
+/// @param user_secret_keys 用户私钥
+/// @param public_keys 每个共享对应的密钥服务器公钥，与`services`顺序相同
+/// @return 如果所有私钥都有效则返回Ok(())，否则返回列出无效私钥对应密钥服务器ID的错误
+pub fn verify_user_secret_keys(
+    encrypted_object: &EncryptedObject,
+    user_secret_keys: &IBEUserSecretKeys,
+    public_keys: &IBEPublicKeys,
+) -> FastCryptoResult<()> {
+    match (user_secret_keys, public_keys) {
+        (
+            IBEUserSecretKeys::BonehFranklinBLS12381(user_secret_keys),
+            IBEPublicKeys::BonehFranklinBLS12381(public_keys),
+        ) => verify_identity_user_secret_keys(
+            &create_full_id(&encrypted_object.package_id, &encrypted_object.id),
+            &encrypted_object.services,
+            user_secret_keys,
+            public_keys,
+        ),
+    }
+}
+
+/// 批量验证持有的用户私钥是否对应给定的完整ID和密钥服务器的公钥。
+/// 同一个密钥服务器出现多次时只验证一次，错误中列出无效私钥对应的密钥服务器ID。
+fn verify_identity_user_secret_keys(
+    full_id: &[u8],
+    services: &[(ObjectID, u8)],
+    user_secret_keys: &HashMap<ObjectID, ibe::UserSecretKey>,
+    public_keys: &[ibe::PublicKey],
+) -> FastCryptoResult<()> {
+    check_public_keys(public_keys, services)?;
+    let invalid = find_invalid_key_servers(full_id, services, user_secret_keys, public_keys);
+    if invalid.is_empty() {
+        return Ok(());
+    }
+    Err(GeneralError(format!(
+        "Invalid user secret keys from key servers {:?}",
+        invalid
+    )))
+}
+
+/// 批量验证持有的用户私钥，返回私钥无效的密钥服务器ID，按其在`services`中首次出现的顺序。
+/// 同一个密钥服务器出现多次时只验证一次，公钥必须已经通过`check_public_keys`检查。
+fn find_invalid_key_servers(
+    full_id: &[u8],
+    services: &[(ObjectID, u8)],
+    user_secret_keys: &HashMap<ObjectID, ibe::UserSecretKey>,
+    public_keys: &[ibe::PublicKey],
+) -> Vec<ObjectID> {
+    let (key_servers, claims): (Vec<_>, Vec<_>) = services
+        .iter()
+        .zip(public_keys)
+        .unique_by(|((id, _), _)| id)
+        .filter_map(|((id, _), pk)| {
+            user_secret_keys
+                .get(id)
+                .map(|usk| (*id, (usk, full_id, pk)))
+        })
+        .unzip();
+    match ibe::verify_user_secret_keys_batch(&mut thread_rng(), &claims) {
+        Ok(()) => vec![],
+        Err(_) => ibe::find_invalid_user_secret_keys(&claims)
+            .into_iter()
+            .map(|i| key_servers[i])
+            .collect(),
+    }
+}
+
+/// 从DST、包ID和内部ID创建完整ID。结果的格式为：
+/// [len(DST)][DST][package_id][id]
+pub fn create_full_id(package_id: &[u8; 32], id: &[u8]) -> Vec<u8> {
+    assert!(DST.len() < 256);
+    let mut full_id = vec![DST.len() as u8];
+    full_id.extend_from_slice(DST);
+    full_id.extend_from_slice(package_id);
+    full_id.extend_from_slice(id);
+    full_id
+}
+
+/// 表示派生密钥的不同用途
+pub enum KeyPurpose {
+    /// 用于加密随机性的密钥
+    EncryptedRandomness,
+    /// 用于数据加密机制(DEM)的密钥
+    DEM,
+    /// 从明文模式的密钥派生的应用程序子密钥，参见`derive_subkey`
+    Subkey(SubkeyLabel),
+}
+
+/// 子密钥的标签，不同的标签得到相互独立的子密钥。
+/// 标签的字节编码作为HKDF的info，编码以"seal-subkey/"和标签种类开头，
+/// 因此自定义标签不会与内置标签冲突。其他实现（例如TypeScript SDK）使用相同的编码即可得到相同的子密钥
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubkeyLabel {
+    /// 对称加密密钥，编码为"seal-subkey/encryption"
+    Encryption,
+    /// 消息认证码密钥，编码为"seal-subkey/mac"
+    Mac,
+    /// 第N个文件的密钥，编码为"seal-subkey/file/N"（N为十进制）
+    File(u64),
+    /// 应用程序自定义的标签，不能为空，编码为"seal-subkey/custom/"加上标签的字节
+    Custom(Vec<u8>),
+}
+
+impl SubkeyLabel {
+    /// 标签的字节编码，用作HKDF的info
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            SubkeyLabel::Encryption => b"seal-subkey/encryption".to_vec(),
+            SubkeyLabel::Mac => b"seal-subkey/mac".to_vec(),
+            SubkeyLabel::File(index) => format!("seal-subkey/file/{}", index).into_bytes(),
+            SubkeyLabel::Custom(label) => [b"seal-subkey/custom/".as_slice(), label].concat(),
+        }
+    }
+}
+
+/// 从基础密钥为特定用途派生一个密钥
+fn derive_key(purpose: KeyPurpose, derived_key: &[u8; KEY_SIZE]) -> Secret<[u8; KEY_SIZE]> {
+    match purpose {
+        KeyPurpose::EncryptedRandomness => hmac_secret(derived_key, &[0]),
+        KeyPurpose::DEM => hmac_secret(derived_key, &[1]),
+        KeyPurpose::Subkey(label) => {
+            let ikm = HkdfIkm::from_bytes(derived_key).expect("固定长度");
+            let output = Zeroizing::new(
+                hkdf_sha3_256(&ikm, DST_SUBKEY, &label.to_bytes(), KEY_SIZE).expect("固定长度"),
+            );
+            let mut key = Secret::zeroed();
+            key.expose_mut().copy_from_slice(&output);
+            key
+        }
+    }
+}
+
+/// 计算HMAC-SHA3-256并写入堆内存中的秘密值，栈上的摘要在复制后立即清零
+fn hmac_secret(key: &[u8; KEY_SIZE], message: &[u8]) -> Secret<[u8; KEY_SIZE]> {
+    let hmac_key = HmacKey::from_bytes(key).expect("固定长度");
+    let mut digest = hmac_sha3_256(&hmac_key, message).digest;
+    let mut secret = Secret::zeroed();
+    secret.expose_mut().copy_from_slice(&digest);
+    digest.zeroize();
+    secret
+}
+
+/// 从明文模式（`EncryptionInput::Plain`）的密钥派生带标签的子密钥，
+/// 使应用程序不需要自行设计密钥派生，例如分别派生加密密钥和MAC密钥，或者为每个文件派生一个密钥。
+///
+/// 子密钥为 HKDF-SHA3-256(ikm = key, salt = `DST_SUBKEY`, info = `label.to_bytes()`) 的前32字节，
+/// 其他实现按照相同的定义即可得到相同的子密钥。
+///
+/// @param key 明文模式的密钥，即`seal_encrypt`返回的密钥或`seal_decrypt`对明文模式对象返回的密钥
+/// @param label 子密钥的标签
+/// @return 子密钥。如果自定义标签为空则返回错误
+pub fn derive_subkey(
+    key: &[u8; KEY_SIZE],
+    label: SubkeyLabel,
+) -> FastCryptoResult<Secret<[u8; KEY_SIZE]>> {
+    if label == SubkeyLabel::Custom(vec![]) {
+        return Err(InvalidInput);
+    }
+    Ok(derive_key(KeyPurpose::Subkey(label), key))
+}
+
+/// 参与派生对称密钥的扩展，它们改变数据加密机制的语义
+const DEM_KEY_TAGS: &[u16] = &[
+    header::TAG_CONTEXT_BINDING,
+    header::TAG_PADDING,
+    header::TAG_COMPRESSION,
+];
+
+/// 派生加密对象的对称密钥（DEM密钥）。没有`DEM_KEY_TAGS`中的扩展时与`KeyPurpose::DEM`相同，
+/// 否则这些扩展也参与派生，因此移除或添加它们会得到不同的密钥，从而导致解密失败，
+/// 而不能通过修改密文中的额外认证数据来绕过
+fn derive_dem_key(base_key: &[u8; KEY_SIZE], extensions: &[Extension]) -> Secret<[u8; KEY_SIZE]> {
+    let dem_extensions = extensions
+        .iter()
+        .filter(|ext| DEM_KEY_TAGS.contains(&ext.tag))
+        .collect_vec();
+    if dem_extensions.is_empty() {
+        return derive_key(KeyPurpose::DEM, base_key);
+    }
+    let info = [
+        &[1][..],
+        &bcs::to_bytes(&dem_extensions).expect("Never fails"),
+    ]
+    .concat();
+    hmac_secret(base_key, &info)
+}
+
+/// 派生用于加密第`identity`个身份的IBE随机性的密钥。
+/// 主身份使用与单身份对象相同的密钥，其他身份使用各自独立的密钥，避免多个随机性使用同一个密钥加密。
+fn randomness_key(base_key: &[u8; KEY_SIZE], identity: u8) -> Secret<[u8; KEY_SIZE]> {
+    match identity {
+        0 => derive_key(KeyPurpose::EncryptedRandomness, base_key),
+        _ => hmac_secret(base_key, &[0, identity]),
+    }
+}
+
+impl IBEEncryptions {
+    /// 检查给定的基础密钥是否为加密这些共享时使用的基础密钥
+    fn verify_base_key(&self, base_key: &[u8; KEY_SIZE], identity: u8) -> FastCryptoResult<()> {
+        match self {
+            IBEEncryptions::BonehFranklinBLS12381 {
+                nonce,
+                encrypted_randomness,
+                ..
+            } => ibe::decrypt_and_verify_nonce(
+                encrypted_randomness,
+                randomness_key(base_key, identity).expose(),
+                nonce,
+            )
+            .map(|_| ()),
+        }
+    }
+
+    /// 给定共享和基础密钥，检查共享是否一致
+    /// 例如，检查所有子集的共享是否能重建相同的多项式
+    fn check_share_consistency(
+        &self,
+        shares: &[(u8, [u8; KEY_SIZE])],
+        full_id: &[u8],
+        services: &[(ObjectID, u8)],
+        public_keys: &IBEPublicKeys,
+        base_key: &[u8; KEY_SIZE],
+        identity: u8,
+    ) -> FastCryptoResult<()> {
+        // 从给定的共享计算整个多项式，注意多项式(0) = base_key
+        let polynomial = interpolate(shares)?;
+
+        // 使用派生的密钥解密所有共享
+        let all_shares =
+            self.decrypt_all_shares(full_id, services, public_keys, base_key, identity)?;
+
+        // 检查所有共享是否都在重建的多项式上
+        if all_shares
+            .into_iter()
+            .any(|(i, share)| polynomial(i) != share)
+        {
+            return Err(GeneralError("共享不一致".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 与`check_share_consistency`相同，但按照访问树检查每个阈值节点的所有子节点是否一致，
+    /// 并且由所有共享重建的秘密等于基础密钥
+    fn check_tree_consistency(
+        &self,
+        access_tree: &AccessTree,
+        full_id: &[u8],
+        services: &[(ObjectID, u8)],
+        public_keys: &IBEPublicKeys,
+        base_key: &[u8; KEY_SIZE],
+        identity: u8,
+    ) -> FastCryptoResult<()> {
+        let all_shares = self
+            .decrypt_all_shares(full_id, services, public_keys, base_key, identity)?
+            .into_iter()
+            .map(|(_, share)| share)
+            .collect_vec();
+        if combine_tree_consistent(access_tree, &all_shares)? != *base_key {
+            return Err(GeneralError("共享不一致".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 给定派生的密钥，解密所有共享
+    fn decrypt_all_shares(
+        &self,
+        full_id: &[u8],
+        services: &[(ObjectID, u8)],
+        public_keys: &IBEPublicKeys,
+        base_key: &[u8; KEY_SIZE],
+        identity: u8,
+    ) -> FastCryptoResult<Vec<(u8, [u8; KEY_SIZE])>> {
+        match self {
+            IBEEncryptions::BonehFranklinBLS12381 {
+                encrypted_randomness,
+                encrypted_shares,
+                nonce,
+            } => {
+                // 解密加密的随机数
+                let nonce = ibe::decrypt_and_verify_nonce(
+                    encrypted_randomness,
+                    randomness_key(base_key, identity).expose(),
+                    nonce,
+                )?;
+
+                // 解密所有共享
+                match public_keys {
+                    IBEPublicKeys::BonehFranklinBLS12381(public_keys) => {
+                        if encrypted_shares.len() != services.len() {
+                            return Err(InvalidInput);
+                        }
+                        check_public_keys(public_keys, services)?;
+                        let shares = decrypt_batched_deterministic(
+                            &nonce,
+                            encrypted_shares,
+                            public_keys,
+                            full_id,
+                            services,
+                        )?;
+                        Ok(services
+                            .iter()
+                            .map(|(_, index)| *index)
+                            .zip(shares)
+                            .collect())
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 检查公钥是否按共享给出：长度与`services`相同，并且同一个密钥服务器的所有共享使用相同的公钥。
+/// 加权加密中同一个密钥服务器持有多个共享时，它的公钥需要在每个共享的位置重复给出，
+/// 只给出每个密钥服务器一个公钥的列表会被拒绝。
+fn check_public_keys(
+    public_keys: &[ibe::PublicKey],
+    services: &[(ObjectID, u8)],
+) -> FastCryptoResult<()> {
+    if public_keys.len() != services.len() {
+        return Err(InvalidInput);
+    }
+    let mut server_public_keys = HashMap::new();
+    for ((id, _), pk) in services.iter().zip(public_keys) {
+        if server_public_keys
+            .insert(id, pk)
+            .is_some_and(|other| other != pk)
+        {
+            return Err(InvalidInput);
+        }
+    }
+    Ok(())
+}
+
+/// 将加密对象升级到版本1，而不需要明文或任何密钥。
+/// 升级后的对象包含数据加密机制的算法标识符扩展，其余字段（包括密文）保持不变，
+/// 因此可以用相同的用户私钥解密。版本1及以上的对象原样返回。
+///
+/// @param encrypted_object 要升级的加密对象
+/// @return 版本1的加密对象
+pub fn upgrade_to_v1(encrypted_object: &EncryptedObject) -> EncryptedObject {
+    let mut upgraded = encrypted_object.clone();
+    if upgraded.version == 0 {
+        upgraded.version = 1;
+        header::set_extension(
+            &mut upgraded.extensions,
+            header::TAG_DEM_ALGORITHM,
+            vec![upgraded.ciphertext.algorithm_id()],
+        );
+    }
+    upgraded
+}
+
+impl EncryptedObject {
+    /// 解析BCS编码的加密对象，根据版本号选择对应的格式
+    ///
+    /// @param bytes BCS编码的加密对象
+    /// @return 加密对象。如果版本不受支持、包含不认识的关键扩展或有多余的字节，则返回错误
+    pub fn from_bytes(bytes: &[u8]) -> FastCryptoResult<Self> {
+        match bytes.first() {
+            Some(version) if *version <= MAX_SUPPORTED_VERSION => {
+                bcs::from_bytes(bytes).map_err(|e| GeneralError(e.to_string()))
+            }
+            _ => Err(InvalidInput),
+        }
+    }
+
+    /// 将加密对象编码为BCS字节
+    pub fn to_bytes(&self) -> FastCryptoResult<Vec<u8>> {
+        bcs::to_bytes(self).map_err(|e| GeneralError(e.to_string()))
+    }
+
+    /// 获取给定标签的扩展值
+    pub fn extension(&self, tag: u16) -> Option<&[u8]> {
+        header::find_extension(&self.extensions, tag)
+    }
+
+    /// 设置给定标签的扩展值。只有版本1及以上的对象可以包含扩展，参见[upgrade_to_v1]
+    pub fn set_extension(&mut self, tag: u16, value: Vec<u8>) -> FastCryptoResult<()> {
+        if self.version == 0 {
+            return Err(InvalidInput);
+        }
+        // 如果新的扩展无效，保持对象不变
+        let previous = self.extensions.clone();
+        header::set_extension(&mut self.extensions, tag, value);
+        self.validate_header()
+            .inspect_err(|_| self.extensions = previous)
+    }
+
+    /// 对象的创建时间（自Unix纪元起的毫秒数）
+    pub fn created_at(&self) -> Option<u64> {
+        self.extension(header::TAG_CREATED_AT)
+            .and_then(|value| bcs::from_bytes(value).ok())
+    }
+
+    /// 设置对象的创建时间（自Unix纪元起的毫秒数）
+    pub fn set_created_at(&mut self, timestamp_ms: u64) -> FastCryptoResult<()> {
+        self.set_extension(
+            header::TAG_CREATED_AT,
+            bcs::to_bytes(&timestamp_ms).expect("Never fails"),
+        )
+    }
+
+    /// 明文的内容类型提示
+    pub fn content_type(&self) -> Option<&str> {
+        self.extension(header::TAG_CONTENT_TYPE)
+            .and_then(|value| std::str::from_utf8(value).ok())
+    }
+
+    /// 设置明文的内容类型提示，例如"video/mp4"
+    pub fn set_content_type(&mut self, content_type: &str) -> FastCryptoResult<()> {
+        self.set_extension(header::TAG_CONTENT_TYPE, content_type.as_bytes().to_vec())
+    }
+
+    /// 可以解密该对象的所有身份，第一个是主身份，其余来自`header::TAG_ALTERNATIVE_IDENTITIES`扩展
+    pub fn identities(&self) -> FastCryptoResult<Vec<IdentityEncryption>> {
+        let primary = IdentityEncryption {
+            package_id: self.package_id,
+            id: self.id.clone(),
+            services: self.services.clone(),
+            threshold: self.threshold,
+            encrypted_shares: self.encrypted_shares.clone(),
+        };
+        let alternatives: Vec<IdentityEncryption> =
+            match self.extension(header::TAG_ALTERNATIVE_IDENTITIES) {
+                Some(value) => bcs::from_bytes(value).map_err(|_| InvalidInput)?,
+                None => vec![],
+            };
+        if alternatives.len() >= u8::MAX as usize {
+            return Err(InvalidInput);
+        }
+        Ok(std::iter::once(primary).chain(alternatives).collect())
+    }
+
+    /// 主身份的访问树，参见`seal_encrypt_with_access_tree`
+    pub fn access_tree(&self) -> FastCryptoResult<Option<AccessTree>> {
+        self.extension(header::TAG_ACCESS_TREE)
+            .map(|value| bcs::from_bytes(value).map_err(|_| InvalidInput))
+            .transpose()
+    }
+
+    /// 明文的填充策略，参见`EncryptionInput::Padded`
+    pub fn padding(&self) -> FastCryptoResult<Option<Padding>> {
+        self.extension(header::TAG_PADDING)
+            .map(|value| bcs::from_bytes(value).map_err(|_| InvalidInput))
+            .transpose()
+    }
+
+    /// 明文的压缩算法，参见`EncryptionInput::Compressed`
+    pub fn compression(&self) -> FastCryptoResult<Option<Compression>> {
+        self.extension(header::TAG_COMPRESSION)
+            .map(|value| bcs::from_bytes(value).map_err(|_| InvalidInput))
+            .transpose()
+    }
+
+    /// 移除解密后明文的填充，然后解压缩。如果对象没有填充或压缩，明文原样返回。
+    /// 使用派生对称密钥直接解密密文时，需要调用这个函数得到原始明文
+    pub fn decode_plaintext(
+        &self,
+        plaintext: Vec<u8>,
+        max_decompressed_size: usize,
+    ) -> FastCryptoResult<Vec<u8>> {
+        let plaintext = match self.padding()? {
+            Some(_) => Padding::unpad(plaintext)?,
+            None => plaintext,
+        };
+        match self.compression()? {
+            Some(compression) => compression.decompress(&plaintext, max_decompressed_size),
+            None => Ok(plaintext),
+        }
+    }
+
+    /// 头部摘要，仅当对象包含`header::TAG_CONTEXT_BINDING`扩展时存在。
+    /// 摘要覆盖版本、包ID、ID、密钥服务器、阈值、加密的共享以及所有关键扩展，
+    /// 但不覆盖可以在加密后修改的非关键扩展，例如创建时间和内容类型
+    pub fn context_digest(&self) -> Option<[u8; 32]> {
+        self.extension(header::TAG_CONTEXT_BINDING)?;
+        let critical_extensions = self
+            .extensions
+            .iter()
+            .filter(|ext| ext.is_critical())
+            .collect_vec();
+        let mut hash = Sha3_256::new();
+        hash.update(DST_CONTEXT);
+        hash.update(
+            bcs::to_bytes(&(
+                self.version,
+                &self.package_id,
+                &self.id,
+                &self.services,
+                self.threshold,
+                &self.encrypted_shares,
+                critical_extensions,
+            ))
+            .expect("Never fails"),
+        );
+        Some(hash.finalize().digest)
+    }
+
+    /// 数据加密机制实际使用的额外认证数据：头部摘要（如果绑定）加上密文中的额外认证数据。
+    /// 使用派生对称密钥直接解密密文时，必须使用这个额外认证数据
+    pub fn dem_aad(&self) -> Vec<u8> {
+        let aad = match &self.ciphertext {
+            Ciphertext::Aes256Gcm { aad, .. }
+            | Ciphertext::Hmac256Ctr { aad, .. }
+            | Ciphertext::Aes256GcmChunked { aad, .. }
+            | Ciphertext::ChaCha20Poly1305 { aad, .. }
+            | Ciphertext::Aes256GcmCommitting { aad, .. } => aad,
+            Ciphertext::Plain => &None,
+        };
+        dem_aad(
+            self.context_digest().as_ref().map_or(&[], |digest| digest),
+            aad,
+        )
+    }
+
+    /// 检查版本和头部扩展是否一致
+    fn validate_header(&self) -> FastCryptoResult<()> {
+        if self.version > MAX_SUPPORTED_VERSION
+            || (self.version == 0 && !self.extensions.is_empty())
+        {
+            return Err(InvalidInput);
+        }
+        header::validate_extensions(&self.extensions)?;
+        if self.extension(header::TAG_ALTERNATIVE_IDENTITIES).is_some() {
+            self.identities()?;
+        }
+        // 访问树的叶子与共享一一对应，共享索引为位置加1
+        if let Some(tree) = self.access_tree()? {
+            tree.validate(self.services.len())?;
+            if tree.min_leaves() != self.threshold as usize
+                || self
+                    .services
+                    .iter()
+                    .enumerate()
+                    .any(|(i, (_, index))| *index as usize != i + 1)
+            {
+                return Err(InvalidInput);
+            }
+        }
+        match self.extension(header::TAG_DEM_ALGORITHM) {
+            Some(id) if id != [self.ciphertext.algorithm_id()] => Err(InvalidInput),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// `Ciphertext::Aes256Gcm`的算法标识符
+pub const ALGORITHM_AES256_GCM: u8 = 0;
+/// `Ciphertext::Hmac256Ctr`的算法标识符
+pub const ALGORITHM_HMAC256_CTR: u8 = 1;
+/// `Ciphertext::Plain`的算法标识符
+pub const ALGORITHM_PLAIN: u8 = 2;
+/// `Ciphertext::Aes256GcmChunked`的算法标识符
+pub const ALGORITHM_AES256_GCM_CHUNKED: u8 = 3;
+/// `Ciphertext::ChaCha20Poly1305`的算法标识符
+pub const ALGORITHM_CHACHA20_POLY1305: u8 = 4;
+/// `Ciphertext::Aes256GcmCommitting`的算法标识符
+pub const ALGORITHM_AES256_GCM_COMMITTING: u8 = 5;
+
+impl Ciphertext {
+    /// 数据加密机制的算法标识符，在所有版本中保持不变。
+    /// 标识符是固定的常量，不依赖枚举变体的顺序，新的变体必须使用新的标识符
+    pub fn algorithm_id(&self) -> u8 {
+        match self {
+            Ciphertext::Aes256Gcm { .. } => ALGORITHM_AES256_GCM,
+            Ciphertext::Hmac256Ctr { .. } => ALGORITHM_HMAC256_CTR,
+            Ciphertext::Plain => ALGORITHM_PLAIN,
+            Ciphertext::Aes256GcmChunked { .. } => ALGORITHM_AES256_GCM_CHUNKED,
+            Ciphertext::ChaCha20Poly1305 { .. } => ALGORITHM_CHACHA20_POLY1305,
+            Ciphertext::Aes256GcmCommitting { .. } => ALGORITHM_AES256_GCM_COMMITTING,
+        }
+    }
+}
+
+/// `EncryptedObject`的字段名
+const ENCRYPTED_OBJECT_FIELDS: &[&str] = &[
+    "version",
+    "package_id",
+    "id",
+    "services",
+    "threshold",
+    "encrypted_shares",
+    "ciphertext",
+    "extensions",
+];
+
+impl Serialize for EncryptedObject {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.validate_header().map_err(S::Error::custom)?;
+        let with_extensions = self.version >= 1;
+        let mut state = serializer.serialize_struct(
+            "EncryptedObject",
+            ENCRYPTED_OBJECT_FIELDS.len() - !with_extensions as usize,
+        )?;
+        state.serialize_field("version", &self.version)?;
+        state.serialize_field("package_id", &self.package_id)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("services", &self.services)?;
+        state.serialize_field("threshold", &self.threshold)?;
+        state.serialize_field("encrypted_shares", &self.encrypted_shares)?;
+        state.serialize_field("ciphertext", &self.ciphertext)?;
+        if with_extensions {
+            state.serialize_field("extensions", &self.extensions)?;
+        } else {
+            state.skip_field("extensions")?;
+        }
+        state.end()
+    }
+}
+
+/// 人类可读格式（例如JSON）中的加密对象，版本0的对象没有`extensions`字段
+#[derive(Deserialize)]
+struct HumanReadableEncryptedObject {
+    version: u8,
+    package_id: ObjectID,
+    id: Vec<u8>,
+    services: Vec<(ObjectID, u8)>,
+    threshold: u8,
+    encrypted_shares: IBEEncryptions,
+    ciphertext: Ciphertext,
+    #[serde(default)]
+    extensions: Vec<Extension>,
+}
+
+/// 按顺序读取字段的访问器，根据版本号决定是否读取`extensions`
+struct EncryptedObjectVisitor;
+
+impl<'de> Visitor<'de> for EncryptedObjectVisitor {
+    type Value = EncryptedObject;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("an EncryptedObject")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let missing = |i| A::Error::invalid_length(i, &self);
+        let version: u8 = seq.next_element()?.ok_or_else(|| missing(0))?;
+        let package_id = seq.next_element()?.ok_or_else(|| missing(1))?;
+        let id = seq.next_element()?.ok_or_else(|| missing(2))?;
+        let services = seq.next_element()?.ok_or_else(|| missing(3))?;
+        let threshold = seq.next_element()?.ok_or_else(|| missing(4))?;
+        let encrypted_shares = seq.next_element()?.ok_or_else(|| missing(5))?;
+        let ciphertext = seq.next_element()?.ok_or_else(|| missing(6))?;
+        let extensions = match version {
+            0 => vec![],
+            _ => seq.next_element()?.ok_or_else(|| missing(7))?,
+        };
+        Ok(EncryptedObject {
+            version,
+            package_id,
+            id,
+            services,
+            threshold,
+            encrypted_shares,
+            ciphertext,
+            extensions,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptedObject {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encrypted_object = if deserializer.is_human_readable() {
+            let fields = HumanReadableEncryptedObject::deserialize(deserializer)?;
+            EncryptedObject {
+                version: fields.version,
+                package_id: fields.package_id,
+                id: fields.id,
+                services: fields.services,
+                threshold: fields.threshold,
+                encrypted_shares: fields.encrypted_shares,
+                ciphertext: fields.ciphertext,
+                extensions: fields.extensions,
+            }
+        } else {
+            deserializer.deserialize_struct(
+                "EncryptedObject",
+                ENCRYPTED_OBJECT_FIELDS,
+                EncryptedObjectVisitor,
+            )?
+        };
+        encrypted_object
+            .validate_header()
+            .map_err(D::Error::custom)?;
+        Ok(encrypted_object)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastcrypto::{
+        encoding::{Base64, Encoding},
+        groups::{
+            bls12381::{G1Element, Scalar},
+            HashToGroupElement,
+        },
+        serde_helpers::ToFromByteArray,
+    };
+    use std::str::FromStr;
+
+    /// 测试哈希函数的回归测试
+    /// 确保哈希结果与预期值一致，避免意外的行为变化
+    #[test]
+    fn test_hash_with_prefix_regression() {
+        let hash = G1Element::hash_to_group_element(&create_full_id(
+            &ObjectID::from_bytes([0u8; 32]).unwrap(),
+            &[1, 2, 3, 4],
+        ));
+        assert_eq!(hex::encode(hash.to_byte_array()), "b32685b6ffd1f373faf3abb10c05772e033f75da8af729c3611d81aea845670db48ceadd0132d3a667dbbaa36acefac7");
+    }
+
+    /// 测试使用AES-256-GCM模式的加密和解密完整流程
+    /// 1. 创建密钥对和测试数据
+    /// 2. 加密数据
+    /// 3. 解密数据并验证结果
+    /// 4. 验证AAD更改时解密失败
+    #[test]
+    fn test_encryption_round_trip_aes() {
+        let data = b"Hello, World!";
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3, 4];
+
+        let full_id = create_full_id(&package_id, &id);
+
+        // 生成3对密钥，阈值设为2
+        let mut rng = rand::thread_rng();
+        let keypairs = (0..3)
+            .map(|_| ibe::generate_key_pair(&mut rng))
+            .collect_vec();
+
+        let services = keypairs.iter().map(|_| ObjectID::random()).collect_vec();
+
+        let threshold = 2;
+        let public_keys =
+            IBEPublicKeys::BonehFranklinBLS12381(keypairs.iter().map(|(_, pk)| *pk).collect_vec());
+
+        // 加密数据
+        let encrypted = seal_encrypt(
+            package_id,
+            id,
+            services.clone(),
+            &public_keys,
+            threshold,
+            EncryptionInput::Aes256Gcm {
+                data: data.to_vec(),
+                aad: Some(b"something".to_vec()),
+            },
+        )
+        .unwrap()
+        .0;
+
+        // 准备用户私钥并解密
+        let user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(
+            services
+                .into_iter()
+                .zip(keypairs)
+                .map(|(s, kp)| (s, ibe::extract(&kp.0, &full_id)))
+                .collect(),
+        );
+        let decrypted = seal_decrypt(&encrypted, &user_secret_keys, Some(&public_keys)).unwrap();
+
+        // 验证解密结果
+        assert_eq!(data, decrypted.as_slice());
+
+        // 验证AAD更改时解密失败
+        let mut modified_encrypted = encrypted.clone();
+        match modified_encrypted.ciphertext {
+            Ciphertext::Aes256Gcm { ref mut aad, .. } => {
+                match aad {
+                    None => panic!(),
+                    Some(ref mut aad) => aad.push(0),
+                }
+                assert!(
+                    seal_decrypt(&modified_encrypted, &user_secret_keys, Some(&public_keys))
+                        .is_err()
+                );
+            }
+            _ => panic!(),
+        }
+    }
+
+    /// 测试使用HMAC-256-CTR模式的加密和解密完整流程
+    /// 1. 创建密钥对和测试数据
+    /// 2. 加密数据
+    /// 3. 解密数据并验证结果
+    /// 4. 验证AAD更改时解密失败
+    #[test]
+    fn test_encryption_round_trip_hmac() {
+        let data = b"Hello, World!";
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3, 4];
+
+        let full_id = create_full_id(&package_id, &id);
+
+        // 生成3对密钥，阈值设为2
+        let mut rng = rand::thread_rng();
+        let keypairs = (0..3)
+            .map(|_| ibe::generate_key_pair(&mut rng))
+            .collect_vec();
+
+        let services = keypairs.iter().map(|_| ObjectID::random()).collect_vec();
+
+        let threshold = 2;
+        let public_keys =
+            IBEPublicKeys::BonehFranklinBLS12381(keypairs.iter().map(|(_, pk)| *pk).collect_vec());
+
+        // 加密数据
+        let encrypted = seal_encrypt(
+            package_id,
+            id,
+            services.clone(),
+            &public_keys,
+            threshold,
+            EncryptionInput::Hmac256Ctr {
+                data: data.to_vec(),
+                aad: Some(b"something".to_vec()),
+            },
+        )
+        .unwrap()
+        .0;
+
+        // 准备用户私钥并解密
+        let user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(
+            services
+                .into_iter()
+                .zip(keypairs)
+                .map(|(s, kp)| (s, ibe::extract(&kp.0, &full_id)))
+                .collect(),
+        );
+        let decrypted = seal_decrypt(&encrypted, &user_secret_keys, Some(&public_keys)).unwrap();
+
+        // 验证解密结果
+        assert_eq!(data, decrypted.as_slice());
+
+        // 验证AAD更改时解密失败
+        let mut modified_encrypted = encrypted.clone();
+        match modified_encrypted.ciphertext {
+            Ciphertext::Hmac256Ctr { ref mut aad, .. } => {
+                match aad {
+                    None => panic!(),
+                    Some(ref mut aad) => aad.push(0),
+                }
+                assert!(
+                    seal_decrypt(&modified_encrypted, &user_secret_keys, Some(&public_keys))
+                        .is_err()
+                );
+            }
+            _ => panic!(),
+        }
+    }
+
+    /// 测试使用ChaCha20-Poly1305模式的加密和解密完整流程
+    /// 1. 创建密钥对和测试数据
+    /// 2. 加密数据
+    /// 3. 解密数据并验证结果
+    /// 4. 验证AAD更改时解密失败
+    #[test]
+    fn test_encryption_round_trip_chacha20_poly1305() {
+        let data = b"Hello, World!";
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3, 4];
+
+        let full_id = create_full_id(&package_id, &id);
+
+        // 生成3对密钥，阈值设为2
+        let mut rng = rand::thread_rng();
+        let keypairs = (0..3)
+            .map(|_| ibe::generate_key_pair(&mut rng))
+            .collect_vec();
+
+        let services = keypairs.iter().map(|_| ObjectID::random()).collect_vec();
+
+        let threshold = 2;
+        let public_keys =
+            IBEPublicKeys::BonehFranklinBLS12381(keypairs.iter().map(|(_, pk)| *pk).collect_vec());
+
+        // 加密数据
+        let encrypted = seal_encrypt(
+            package_id,
+            id,
+            services.clone(),
+            &public_keys,
+            threshold,
+            EncryptionInput::ChaCha20Poly1305 {
+                data: data.to_vec(),
+                aad: Some(b"something".to_vec()),
+            },
+        )
+        .unwrap()
+        .0;
+
+        // 准备用户私钥并解密
+        let user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(
+            services
+                .into_iter()
+                .zip(keypairs)
+                .map(|(s, kp)| (s, ibe::extract(&kp.0, &full_id)))
+                .collect(),
+        );
+        let decrypted = seal_decrypt(&encrypted, &user_secret_keys, Some(&public_keys)).unwrap();
+
+        // 验证解密结果
+        assert_eq!(data, decrypted.as_slice());
+
+        // 验证AAD更改时解密失败
+        let mut modified_encrypted = encrypted.clone();
+        match modified_encrypted.ciphertext {
+            Ciphertext::ChaCha20Poly1305 { ref mut aad, .. } => {
+                match aad {
+                    None => panic!(),
+                    Some(ref mut aad) => aad.push(0),
+                }
+                assert!(
+                    seal_decrypt(&modified_encrypted, &user_secret_keys, Some(&public_keys))
+                        .is_err()
+                );
+            }
+            _ => panic!(),
+        }
+    }
+
+    /// 测试使用分块AES-256-GCM模式的加密和解密完整流程
+    /// 1. 将密文内联在加密对象中，使用seal_encrypt和seal_decrypt
+    /// 2. 将密文单独存储，使用seal_encrypt_stream和seal_decrypt_stream
+    /// 3. 流式加密时填充明文，流式解密时移除填充
+    #[test]
+    fn test_encryption_round_trip_chunked() {
+        let data = b"The difference between a Miracle and a Fact is exactly the difference between a mermaid and a seal.";
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3, 4];
+
+        let full_id = create_full_id(&package_id, &id);
+
+        // 生成3对密钥，阈值设为2
+        let mut rng = rand::thread_rng();
+        let keypairs = (0..3)
+            .map(|_| ibe::generate_key_pair(&mut rng))
+            .collect_vec();
+
+        let services = keypairs.iter().map(|_| ObjectID::random()).collect_vec();
+
+        let threshold = 2;
+        let public_keys =
+            IBEPublicKeys::BonehFranklinBLS12381(keypairs.iter().map(|(_, pk)| *pk).collect_vec());
+
+        let user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(
+            services
+                .iter()
+                .zip(&keypairs)
+                .map(|(s, kp)| (*s, ibe::extract(&kp.0, &full_id)))
+                .collect(),
+        );
+
+        // 密文内联在加密对象中
+        let encrypted = seal_encrypt(
+            package_id,
+            id.clone(),
+            services.clone(),
+            &public_keys,
+            threshold,
+            EncryptionInput::Aes256GcmChunked {
+                data: data.to_vec(),
+                aad: Some(b"something".to_vec()),
+                chunk_size: 16,
+            },
+        )
+        .unwrap()
+        .0;
+        let decrypted = seal_decrypt(&encrypted, &user_secret_keys, Some(&public_keys)).unwrap();
+        assert_eq!(data, decrypted.as_slice());
+
+        // 按范围解密内联的密文
+        let dem_key = seal_decrypt_key(&encrypted, &user_secret_keys, Some(&public_keys)).unwrap();
+        assert_eq!(
+            &data[5..40],
+            decrypt_range(&encrypted, dem_key.expose(), 5..40)
+                .unwrap()
+                .as_slice()
+        );
+
+        // 内联对象不能通过流式接口解密
+        assert!(seal_decrypt_stream(
+            &encrypted,
+            &user_secret_keys,
+            Some(&public_keys),
+            &[][..],
+            &mut Vec::new()
+        )
+        .is_err());
+
+        // 密文单独存储
+        let mut ciphertext = Vec::new();
+        let (encrypted, key) = seal_encrypt_stream(
+            package_id,
+            id.clone(),
+            services.clone(),
+            &public_keys,
+            threshold,
+            Some(b"something".to_vec()),
+            16,
+            None,
+            &data[..],
+            &mut ciphertext,
+        )
+        .unwrap();
+        match &encrypted.ciphertext {
+            Ciphertext::Aes256GcmChunked {
+                blob, chunk_size, ..
+            } => {
+                assert!(blob.is_empty());
+                assert_eq!(*chunk_size, 16);
+            }
+            _ => panic!(),
+        }
+        assert_eq!(
+            data,
+            Aes256GcmChunked::decrypt(&ciphertext, b"something", key.expose(), 16)
+                .unwrap()
+                .as_slice()
+        );
+
+        let mut decrypted = Vec::new();
+        let written = seal_decrypt_stream(
+            &encrypted,
+            &user_secret_keys,
+            Some(&public_keys),
+            ciphertext.as_slice(),
+            &mut decrypted,
+        )
+        .unwrap();
+        assert_eq!(written, data.len() as u64);
+        assert_eq!(data, decrypted.as_slice());
+
+        // 按范围解密单独存储的密文
+        let dem_key = seal_decrypt_key(&encrypted, &user_secret_keys, Some(&public_keys)).unwrap();
+        assert_eq!(dem_key.expose(), key.expose());
+        assert_eq!(
+            &data[20..50],
+            decrypt_range_seekable(
+                &encrypted,
+                dem_key.expose(),
+                std::io::Cursor::new(&ciphertext),
+                20..50
+            )
+            .unwrap()
+            .as_slice()
+        );
+        assert!(decrypt_range(&encrypted, dem_key.expose(), 20..50).is_err());
+
+        // 头部中的块大小超过上限时直接失败，而不是按块大小分配内存
+        let mut oversized = encrypted.clone();
+        match &mut oversized.ciphertext {
+            Ciphertext::Aes256GcmChunked { chunk_size, .. } => *chunk_size = u32::MAX,
+            _ => panic!(),
+        }
+        assert!(seal_decrypt_stream(
+            &oversized,
+            &user_secret_keys,
+            Some(&public_keys),
+            ciphertext.as_slice(),
+            &mut Vec::new()
+        )
+        .is_err());
+
+        // 修改密文时解密失败
+        ciphertext[20] ^= 1;
+        assert!(seal_decrypt_stream(
+            &encrypted,
+            &user_secret_keys,
+            Some(&public_keys),
+            ciphertext.as_slice(),
+            &mut Vec::new()
+        )
+        .is_err());
+
+        // 流式加密时填充明文，密文长度只取决于填充后的长度
+        let mut ciphertext = Vec::new();
+        let (encrypted, _) = seal_encrypt_stream(
+            package_id,
+            id.clone(),
+            services.clone(),
+            &public_keys,
+            threshold,
+            None,
+            16,
+            Some(Padding::Bucket(64)),
+            &data[..],
+            &mut ciphertext,
+        )
+        .unwrap();
+        assert_eq!(encrypted.version, 1);
+        assert_eq!(encrypted.padding().unwrap(), Some(Padding::Bucket(64)));
+        assert_eq!(
+            ciphertext.len(),
+            Padding::Bucket(64).padded_length(data.len()).unwrap() / 16 * 32
+        );
+        let mut decrypted = Vec::new();
+        let written = seal_decrypt_stream(
+            &encrypted,
+            &user_secret_keys,
+            Some(&public_keys),
+            ciphertext.as_slice(),
+            &mut decrypted,
+        )
+        .unwrap();
+        assert_eq!(written, data.len() as u64);
+        assert_eq!(data, decrypted.as_slice());
+
+        // 移除填充扩展后派生的密钥不同，无法解密
+        let mut stripped = encrypted.clone();
+        stripped
+            .extensions
+            .retain(|ext| ext.tag != header::TAG_PADDING);
+        assert!(seal_decrypt_stream(
+            &stripped,
+            &user_secret_keys,
+            Some(&public_keys),
+            ciphertext.as_slice(),
+            &mut Vec::new()
+        )
+        .is_err());
+
+        assert!(seal_encrypt_stream(
+            package_id,
+            id,
+            services,
+            &public_keys,
+            threshold,
+            None,
+            16,
+            Some(Padding::Bucket(0)),
+            &data[..],
+            &mut Vec::new(),
+        )
+        .is_err());
+    }
+
+    /// 测试多身份加密
+    /// 1. 持有任意一个身份的足够用户私钥都可以解密，即使两个身份使用相同的密钥服务器
+    /// 2. 对象可以被序列化和解析，并且扩展是关键扩展
+    /// 3. 没有足够用户私钥的身份无法解密
+    #[test]
+    fn test_multi_identity() {
+        let data = b"Subscribers or the owner";
+        let mut rng = rand::thread_rng();
+        let keypairs = (0..3)
+            .map(|_| ibe::generate_key_pair(&mut rng))
+            .collect_vec();
+        let services = keypairs.iter().map(|_| ObjectID::random()).collect_vec();
+        let public_keys =
+            IBEPublicKeys::BonehFranklinBLS12381(keypairs.iter().map(|(_, pk)| *pk).collect());
+        let user_secret_keys = |package_id: &ObjectID, id: &[u8], skip: usize| {
+            let full_id = create_full_id(package_id, id);
+            IBEUserSecretKeys::BonehFranklinBLS12381(
+                services
+                    .iter()
+                    .zip(&keypairs)
+                    .skip(skip)
+                    .map(|(s, kp)| (*s, ibe::extract(&kp.0, &full_id)))
+                    .collect(),
+            )
+        };
+
+        let subscribers = IdentityPolicy {
+            package_id: ObjectID::random(),
+            id: vec![1],
+            key_servers: services.clone(),
+            public_keys: public_keys.clone(),
+            threshold: 2,
+        };
+        let owner = IdentityPolicy {
+            package_id: ObjectID::random(),
+            id: vec![2],
+            key_servers: services[1..].to_vec(),
+            public_keys: IBEPublicKeys::BonehFranklinBLS12381(
+                keypairs[1..].iter().map(|(_, pk)| *pk).collect(),
+            ),
+            threshold: 2,
+        };
+        let (encrypted, dem_key) = seal_encrypt_multi_identity(
+            vec![subscribers.clone(), owner.clone()],
+            EncryptionInput::Aes256Gcm {
+                data: data.to_vec(),
+                aad: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(encrypted.version, 1);
+        assert_eq!(encrypted.identities().unwrap().len(), 2);
+        let encrypted = EncryptedObject::from_bytes(&encrypted.to_bytes().unwrap()).unwrap();
+
+        let subscriber_keys = user_secret_keys(&subscribers.package_id, &subscribers.id, 1);
+        assert_eq!(
+            seal_decrypt(&encrypted, &subscriber_keys, Some(&public_keys)).unwrap(),
+            data
+        );
+        let owner_keys = user_secret_keys(&owner.package_id, &owner.id, 1);
+        assert_eq!(seal_decrypt(&encrypted, &owner_keys, None).unwrap(), data);
+        assert_eq!(
+            seal_decrypt(&encrypted, &owner_keys, Some(&owner.public_keys)).unwrap(),
+            data
+        );
+        assert_eq!(
+            seal_decrypt_key(&encrypted, &owner_keys, None)
+                .unwrap()
+                .expose(),
+            dem_key.expose()
+        );
+
+        // 其他身份的私钥，或者数量不足的私钥
+        assert!(seal_decrypt(
+            &encrypted,
+            &user_secret_keys(&owner.package_id, &[3], 0),
+            None
+        )
+        .is_err());
+        assert!(seal_decrypt(
+            &encrypted,
+            &user_secret_keys(&owner.package_id, &owner.id, 2),
+            None
+        )
+        .is_err());
+
+        // 身份扩展是关键扩展
+        assert!(
+            header::find_extension(&encrypted.extensions, header::TAG_ALTERNATIVE_IDENTITIES)
+                .is_some()
+        );
+        assert!(seal_encrypt_multi_identity(vec![], EncryptionInput::Plain).is_err());
+    }
+
+    /// 测试使用访问树 "{A,B,C}中的2个 且 {D,E}中的1个" 加密
+    /// 1. 满足访问树的密钥服务器组合可以解密，并且共享一致
+    /// 2. 不满足访问树的组合无法解密，即使数量达到`threshold`
+    /// 3. 重新分享后对象使用单层阈值共享
+    #[test]
+    fn test_access_tree() {
+        let data = b"Compliance approved";
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3];
+        let full_id = create_full_id(&package_id, &id);
+        let mut rng = rand::thread_rng();
+        let keypairs = (0..5)
+            .map(|_| ibe::generate_key_pair(&mut rng))
+            .collect_vec();
+        let services = keypairs.iter().map(|_| ObjectID::random()).collect_vec();
+        let public_keys =
+            IBEPublicKeys::BonehFranklinBLS12381(keypairs.iter().map(|(_, pk)| *pk).collect());
+        let user_secret_keys = |leaves: &[usize]| {
+            IBEUserSecretKeys::BonehFranklinBLS12381(
+                leaves
+                    .iter()
+                    .map(|&i| (services[i], ibe::extract(&keypairs[i].0, &full_id)))
+                    .collect(),
+            )
+        };
+        let access_tree = AccessTree::Threshold {
+            threshold: 2,
+            children: vec![
+                AccessTree::Threshold {
+                    threshold: 2,
+                    children: vec![
+                        AccessTree::Leaf(0),
+                        AccessTree::Leaf(1),
+                        AccessTree::Leaf(2),
+                    ],
+                },
+                AccessTree::Threshold {
+                    threshold: 1,
+                    children: vec![AccessTree::Leaf(3), AccessTree::Leaf(4)],
+                },
+            ],
+        };
+
+        let (encrypted, _) = seal_encrypt_with_access_tree(
+            package_id,
+            id,
+            services.clone(),
+            &public_keys,
+            &access_tree,
+            EncryptionInput::Aes256Gcm {
+                data: data.to_vec(),
+                aad: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(encrypted.threshold, 3);
+        let encrypted = EncryptedObject::from_bytes(&encrypted.to_bytes().unwrap()).unwrap();
+        assert_eq!(encrypted.access_tree().unwrap(), Some(access_tree.clone()));
+
+        for leaves in [&[0, 2, 4][..], &[1, 2, 3]] {
+            assert_eq!(
+                seal_decrypt(&encrypted, &user_secret_keys(leaves), Some(&public_keys)).unwrap(),
+                data
+            );
+        }
+        for leaves in [&[0, 1, 2][..], &[0, 3, 4]] {
+            assert!(seal_decrypt(&encrypted, &user_secret_keys(leaves), None).is_err());
+        }
+
+        // 访问树的叶子必须与密钥服务器一一对应
+        assert!(seal_encrypt_with_access_tree(
+            package_id,
+            vec![],
+            services[..4].to_vec(),
+            &public_keys,
+            &access_tree,
+            EncryptionInput::Plain,
+        )
+        .is_err());
+
+        let reshared = reshare_with_user_secret_keys(
+            &encrypted,
+            &user_secret_keys(&[0, 1, 4]),
+            Some(&public_keys),
+            services.clone(),
+            &public_keys,
+            3,
+        )
+        .unwrap();
+        assert_eq!(reshared.access_tree().unwrap(), None);
+        assert_eq!(
+            seal_decrypt(&reshared, &user_secret_keys(&[0, 1, 2]), None).unwrap(),
+            data
+        );
+    }
+
+    /// 测试加权加密：权重为2的密钥服务器A与任意另一个密钥服务器即可达到阈值3
+    /// 1. 解密时公钥按共享提供，只给出每个密钥服务器一个公钥或者同一个密钥服务器的公钥不一致时返回错误
+    /// 2. 只有权重为1的密钥服务器时无法解密
+    #[test]
+    fn test_weighted_encryption() {
+        let data = b"Weighted message";
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3];
+        let full_id = create_full_id(&package_id, &id);
+        let mut rng = rand::thread_rng();
+        let keypairs = (0..3)
+            .map(|_| ibe::generate_key_pair(&mut rng))
+            .collect_vec();
+        let services = keypairs.iter().map(|_| ObjectID::random()).collect_vec();
+        let public_keys =
+            IBEPublicKeys::BonehFranklinBLS12381(keypairs.iter().map(|(_, pk)| *pk).collect());
+        let user_secret_keys = |servers: &[usize]| {
+            IBEUserSecretKeys::BonehFranklinBLS12381(
+                servers
+                    .iter()
+                    .map(|&i| (services[i], ibe::extract(&keypairs[i].0, &full_id)))
+                    .collect(),
+            )
+        };
+
+        let (encrypted, _) = seal_encrypt_weighted(
+            package_id,
+            id,
+            services.iter().copied().zip([2, 1, 1]).collect(),
+            &public_keys,
+            3,
+            EncryptionInput::Aes256Gcm {
+                data: data.to_vec(),
+                aad: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            encrypted.services,
+            vec![
+                (services[0], 1),
+                (services[0], 2),
+                (services[1], 3),
+                (services[2], 4)
+            ]
+        );
 
-        // 检查所有共享是否都在重建的多项式上
-        if all_shares
-            .into_iter()
-            .any(|(i, share)| polynomial(i) != share)
-        {
-            return Err(GeneralError("共享不一致".to_string()));
+        let share_public_keys = IBEPublicKeys::BonehFranklinBLS12381(
+            [0, 0, 1, 2].iter().map(|&i| keypairs[i].1).collect(),
+        );
+        let mismatched_public_keys = IBEPublicKeys::BonehFranklinBLS12381(
+            [0, 1, 1, 2].iter().map(|&i| keypairs[i].1).collect(),
+        );
+        for servers in [&[0, 1][..], &[0, 2]] {
+            assert_eq!(
+                seal_decrypt(
+                    &encrypted,
+                    &user_secret_keys(servers),
+                    Some(&share_public_keys)
+                )
+                .unwrap(),
+                data
+            );
+            assert!(
+                seal_decrypt(&encrypted, &user_secret_keys(servers), Some(&public_keys)).is_err()
+            );
+            assert!(seal_decrypt(
+                &encrypted,
+                &user_secret_keys(servers),
+                Some(&mismatched_public_keys)
+            )
+            .is_err());
         }
-        Ok(())
+        assert!(seal_decrypt(&encrypted, &user_secret_keys(&[1, 2]), None).is_err());
+
+        // 权重为0或公钥数量不匹配时返回错误
+        assert!(seal_encrypt_weighted(
+            package_id,
+            vec![],
+            services.iter().copied().zip([2, 0, 1]).collect(),
+            &public_keys,
+            2,
+            EncryptionInput::Plain,
+        )
+        .is_err());
+        assert!(seal_encrypt_weighted(
+            package_id,
+            vec![],
+            services[..2].iter().copied().zip([2, 1]).collect(),
+            &public_keys,
+            2,
+            EncryptionInput::Plain,
+        )
+        .is_err());
     }
 
-    /// 给定派生的密钥，解密所有共享
-    fn decrypt_all_shares(
-        &self,
-        full_id: &[u8],
-        services: &[(ObjectID, u8)],
-        public_keys: &IBEPublicKeys,
-        base_key: &[u8; KEY_SIZE],
-    ) -> FastCryptoResult<Vec<(u8, [u8; KEY_SIZE])>> {
-        match self {
-            IBEEncryptions::BonehFranklinBLS12381 {
-                encrypted_randomness,
-                encrypted_shares,
-                nonce,
-            } => {
-                // 解密加密的随机数
-                let nonce = ibe::decrypt_and_verify_nonce(
-                    encrypted_randomness,
-                    &derive_key(KeyPurpose::EncryptedRandomness, base_key),
-                    nonce,
-                )?;
+    /// 测试批量加密：每个对象使用独立的基础密钥，都可以被解密并通过一致性检查，
+    /// 并且即使并行加密，相同的种子也得到相同的结果
+    #[test]
+    fn test_encrypt_batch() {
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3];
+        let full_id = create_full_id(&package_id, &id);
+        let mut rng = rand::thread_rng();
+        let keypairs = (0..3)
+            .map(|_| ibe::generate_key_pair(&mut rng))
+            .collect_vec();
+        let services = keypairs.iter().map(|_| ObjectID::random()).collect_vec();
+        let public_keys =
+            IBEPublicKeys::BonehFranklinBLS12381(keypairs.iter().map(|(_, pk)| *pk).collect());
+        let user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(
+            services
+                .iter()
+                .zip(&keypairs)
+                .take(2)
+                .map(|(s, (sk, _))| (*s, ibe::extract(sk, &full_id)))
+                .collect(),
+        );
+        let messages = [&b"first"[..], b"second", b"third"];
+        let inputs = messages
+            .iter()
+            .map(|m| EncryptionInput::Aes256Gcm {
+                data: m.to_vec(),
+                aad: None,
+            })
+            .collect_vec();
 
-                // 解密所有共享
-                match public_keys {
-                    IBEPublicKeys::BonehFranklinBLS12381(public_keys) => {
-                        if public_keys.len() != encrypted_shares.len() {
-                            return Err(InvalidInput);
-                        }
-                        public_keys
-                            .iter()
-                            .zip(encrypted_shares)
-                            .zip(services)
-                            .map(|((pk, s), service)| {
-                                decrypt_deterministic(&nonce, s, pk, full_id, service)
-                                    .map(|s| (service.1, s))
-                            })
-                            .collect::<FastCryptoResult<_>>()
-                    }
-                }
-            }
+        let encrypted = seal_encrypt_batch(
+            package_id,
+            id.clone(),
+            services.clone(),
+            &public_keys,
+            2,
+            inputs.clone(),
+        )
+        .unwrap();
+        assert_eq!(encrypted.len(), messages.len());
+        assert_ne!(encrypted[0].1.expose(), encrypted[1].1.expose());
+        for ((object, _), message) in encrypted.iter().zip(messages) {
+            assert_eq!(
+                seal_decrypt(object, &user_secret_keys, Some(&public_keys)).unwrap(),
+                message
+            );
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use fastcrypto::{
-        encoding::{Base64, Encoding},
-        groups::{
-            bls12381::{G1Element, Scalar},
-            HashToGroupElement,
-        },
-        serde_helpers::ToFromByteArray,
-    };
-    use std::str::FromStr;
+        let with_seed = |seed| {
+            seal_encrypt_batch_with_rng(
+                &mut StdRng::seed_from_u64(seed),
+                package_id,
+                id.clone(),
+                services.clone(),
+                &public_keys,
+                2,
+                inputs.clone(),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|(object, _)| object.to_bytes().unwrap())
+            .collect_vec()
+        };
+        assert_eq!(with_seed(1), with_seed(1));
 
-    /// 测试哈希函数的回归测试
-    /// 确保哈希结果与预期值一致，避免意外的行为变化
+        assert!(seal_encrypt_batch(package_id, id, services, &public_keys, 4, inputs).is_err());
+    }
+
+    /// 测试批量验证用户私钥：提供公钥时`seal_decrypt`会指出无效私钥对应的密钥服务器
     #[test]
-    fn test_hash_with_prefix_regression() {
-        let hash = G1Element::hash_to_group_element(&create_full_id(
-            &ObjectID::from_bytes([0u8; 32]).unwrap(),
-            &[1, 2, 3, 4],
+    fn test_verify_user_secret_keys() {
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3];
+        let full_id = create_full_id(&package_id, &id);
+        let mut rng = rand::thread_rng();
+        let keypairs = (0..3)
+            .map(|_| ibe::generate_key_pair(&mut rng))
+            .collect_vec();
+        let services = keypairs.iter().map(|_| ObjectID::random()).collect_vec();
+        let public_keys =
+            IBEPublicKeys::BonehFranklinBLS12381(keypairs.iter().map(|(_, pk)| *pk).collect());
+        let (encrypted, _) = seal_encrypt(
+            package_id,
+            id,
+            services.clone(),
+            &public_keys,
+            2,
+            EncryptionInput::Plain,
+        )
+        .unwrap();
+
+        let mut user_secret_keys = services
+            .iter()
+            .zip(&keypairs)
+            .map(|(s, (sk, _))| (*s, ibe::extract(sk, &full_id)))
+            .collect::<HashMap<_, _>>();
+        let valid = IBEUserSecretKeys::BonehFranklinBLS12381(user_secret_keys.clone());
+        assert!(verify_user_secret_keys(&encrypted, &valid, &public_keys).is_ok());
+        assert!(seal_decrypt(&encrypted, &valid, Some(&public_keys)).is_ok());
+
+        // 没有第一个密钥服务器的私钥，第三个密钥服务器的私钥来自错误的身份。
+        // 错误中给出密钥服务器ID，而不是私钥在持有的私钥中的位置
+        user_secret_keys.remove(&services[0]);
+        user_secret_keys.insert(services[2], ibe::extract(&keypairs[2].0, b"wrong id"));
+        let invalid = IBEUserSecretKeys::BonehFranklinBLS12381(user_secret_keys);
+        let expected = format!(
+            "Invalid user secret keys from key servers [{:?}]",
+            services[2]
+        );
+        assert!(matches!(
+            verify_user_secret_keys(&encrypted, &invalid, &public_keys),
+            Err(GeneralError(message)) if message == expected
         ));
-        assert_eq!(hex::encode(hash.to_byte_array()), "b32685b6ffd1f373faf3abb10c05772e033f75da8af729c3611d81aea845670db48ceadd0132d3a667dbbaa36acefac7");
+        match seal_decrypt(&encrypted, &invalid, Some(&public_keys)) {
+            Err(GeneralError(message)) => {
+                assert_eq!(message, expected)
+            }
+            _ => panic!("Expected the invalid user secret key to be reported"),
+        }
     }
 
-    /// 测试使用AES-256-GCM模式的加密和解密完整流程
-    /// 1. 创建密钥对和测试数据
-    /// 2. 加密数据
-    /// 3. 解密数据并验证结果
-    /// 4. 验证AAD更改时解密失败
+    /// 测试鲁棒解密：两个密钥服务器返回了无效的用户私钥
+    /// 1. 剩余的有效私钥足够时仍然解密，并指出行为不当的密钥服务器
+    /// 2. 有效私钥不足时返回指出行为不当的密钥服务器的错误
+    /// 3. 加密方生成的共享不一致时返回加密对象无效的错误，不归咎于密钥服务器
     #[test]
-    fn test_encryption_round_trip_aes() {
+    fn test_robust_decryption() {
         let data = b"Hello, World!";
         let package_id = ObjectID::random();
         let id = vec![1, 2, 3, 4];
-
         let full_id = create_full_id(&package_id, &id);
-
-        // 生成3对密钥，阈值设为2
         let mut rng = rand::thread_rng();
-        let keypairs = (0..3)
+        let keypairs = (0..4)
             .map(|_| ibe::generate_key_pair(&mut rng))
             .collect_vec();
-
         let services = keypairs.iter().map(|_| ObjectID::random()).collect_vec();
-
-        let threshold = 2;
         let public_keys =
             IBEPublicKeys::BonehFranklinBLS12381(keypairs.iter().map(|(_, pk)| *pk).collect_vec());
 
-        // 加密数据
-        let encrypted = seal_encrypt(
+        let (mut encrypted, _) = seal_encrypt(
             package_id,
             id,
             services.clone(),
             &public_keys,
-            threshold,
+            2,
             EncryptionInput::Aes256Gcm {
                 data: data.to_vec(),
-                aad: Some(b"something".to_vec()),
+                aad: None,
             },
         )
-        .unwrap()
-        .0;
+        .unwrap();
 
-        // 准备用户私钥并解密
-        let user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(
+        let mut usks = services
+            .iter()
+            .zip(&keypairs)
+            .map(|(s, (sk, _))| (*s, ibe::extract(sk, &full_id)))
+            .collect::<HashMap<_, _>>();
+        usks.insert(services[2], ibe::extract(&keypairs[0].0, &full_id));
+        usks.insert(services[3], ibe::extract(&keypairs[3].0, b"wrong id"));
+        let user_secret_keys = |servers: &[usize]| {
+            IBEUserSecretKeys::BonehFranklinBLS12381(
+                servers
+                    .iter()
+                    .map(|i| (services[*i], usks[&services[*i]]))
+                    .collect(),
+            )
+        };
+
+        assert!(seal_decrypt(&encrypted, &user_secret_keys(&[0, 2]), Some(&public_keys)).is_err());
+        assert_eq!(
+            seal_decrypt_robust(&encrypted, &user_secret_keys(&[0, 1, 2, 3]), &public_keys),
+            Ok((data.to_vec(), vec![services[2], services[3]]))
+        );
+        assert_eq!(
+            seal_decrypt_robust(&encrypted, &user_secret_keys(&[0, 1, 2]), &public_keys),
+            Ok((data.to_vec(), vec![services[2]]))
+        );
+        assert_eq!(
+            seal_decrypt_robust(&encrypted, &user_secret_keys(&[1, 2, 3]), &public_keys),
+            Err(RobustDecryptionError::MisbehavingKeyServers(vec![
+                services[2],
+                services[3]
+            ]))
+        );
+
+        // 加密方篡改了一个共享，即使所有用户私钥都有效也无法解密
+        let IBEEncryptions::BonehFranklinBLS12381 {
+            encrypted_shares, ..
+        } = &mut encrypted.encrypted_shares;
+        encrypted_shares[1][0] ^= 1;
+        let valid_keys = IBEUserSecretKeys::BonehFranklinBLS12381(
             services
-                .into_iter()
-                .zip(keypairs)
-                .map(|(s, kp)| (s, ibe::extract(&kp.0, &full_id)))
+                .iter()
+                .zip(&keypairs)
+                .map(|(s, (sk, _))| (*s, ibe::extract(sk, &full_id)))
                 .collect(),
         );
-        let decrypted = seal_decrypt(&encrypted, &user_secret_keys, Some(&public_keys)).unwrap();
-
-        // 验证解密结果
-        assert_eq!(data, decrypted.as_slice());
-
-        // 验证AAD更改时解密失败
-        let mut modified_encrypted = encrypted.clone();
-        match modified_encrypted.ciphertext {
-            Ciphertext::Aes256Gcm { ref mut aad, .. } => {
-                match aad {
-                    None => panic!(),
-                    Some(ref mut aad) => aad.push(0),
-                }
-                assert!(
-                    seal_decrypt(&modified_encrypted, &user_secret_keys, Some(&public_keys))
-                        .is_err()
-                );
-            }
-            _ => panic!(),
+        for user_secret_keys in [&valid_keys, &user_secret_keys(&[0, 1, 3])] {
+            assert_eq!(
+                seal_decrypt_robust(&encrypted, user_secret_keys, &public_keys),
+                Err(RobustDecryptionError::Crypto(GeneralError(
+                    "共享不一致".to_string()
+                )))
+            );
         }
     }
 
-    /// 测试使用HMAC-256-CTR模式的加密和解密完整流程
-    /// 1. 创建密钥对和测试数据
-    /// 2. 加密数据
-    /// 3. 解密数据并验证结果
-    /// 4. 验证AAD更改时解密失败
+    /// 测试鲁棒解密的计算量限制：16个共享、阈值为8
+    /// 1. 3个用户私钥无效时通过纠错解码找出它们，不需要尝试子集
+    /// 2. 6个用户私钥无效时超过纠错能力，尝试的子集数量达到上限后逐个验证用户私钥，仍然可以解密
+    /// 3. 9个用户私钥无效时有效私钥不足，返回指出所有行为不当的密钥服务器的错误
     #[test]
-    fn test_encryption_round_trip_hmac() {
+    fn test_robust_decryption_attempts() {
         let data = b"Hello, World!";
         let package_id = ObjectID::random();
         let id = vec![1, 2, 3, 4];
-
         let full_id = create_full_id(&package_id, &id);
-
-        // 生成3对密钥，阈值设为2
         let mut rng = rand::thread_rng();
-        let keypairs = (0..3)
+        let keypairs = (0..16)
             .map(|_| ibe::generate_key_pair(&mut rng))
             .collect_vec();
-
         let services = keypairs.iter().map(|_| ObjectID::random()).collect_vec();
-
-        let threshold = 2;
         let public_keys =
             IBEPublicKeys::BonehFranklinBLS12381(keypairs.iter().map(|(_, pk)| *pk).collect_vec());
-
-        // 加密数据
-        let encrypted = seal_encrypt(
+        let user_secret_keys = |invalid: &[usize]| {
+            IBEUserSecretKeys::BonehFranklinBLS12381(
+                services
+                    .iter()
+                    .zip(&keypairs)
+                    .enumerate()
+                    .map(|(i, (s, (sk, _)))| {
+                        let id: &[u8] = if invalid.contains(&i) {
+                            b"wrong id"
+                        } else {
+                            &full_id
+                        };
+                        (*s, ibe::extract(sk, id))
+                    })
+                    .collect(),
+            )
+        };
+        let (encrypted, _) = seal_encrypt(
             package_id,
             id,
             services.clone(),
             &public_keys,
-            threshold,
-            EncryptionInput::Hmac256Ctr {
+            8,
+            EncryptionInput::Aes256Gcm {
                 data: data.to_vec(),
-                aad: Some(b"something".to_vec()),
+                aad: None,
             },
         )
-        .unwrap()
-        .0;
+        .unwrap();
+        let servers = |indices: &[usize]| indices.iter().map(|i| services[*i]).collect_vec();
 
-        // 准备用户私钥并解密
-        let user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(
-            services
-                .into_iter()
-                .zip(keypairs)
-                .map(|(s, kp)| (s, ibe::extract(&kp.0, &full_id)))
+        assert_eq!(
+            seal_decrypt_robust(&encrypted, &user_secret_keys(&[1, 5, 9]), &public_keys),
+            Ok((data.to_vec(), servers(&[1, 5, 9])))
+        );
+        let invalid = [1, 5, 9, 10, 11, 12];
+        assert_eq!(
+            seal_decrypt_robust(&encrypted, &user_secret_keys(&invalid), &public_keys),
+            Ok((data.to_vec(), servers(&invalid)))
+        );
+        let invalid = [0, 1, 5, 9, 10, 11, 12, 13, 14];
+        assert_eq!(
+            seal_decrypt_robust(&encrypted, &user_secret_keys(&invalid), &public_keys),
+            Err(RobustDecryptionError::MisbehavingKeyServers(servers(
+                &invalid
+            )))
+        );
+    }
+
+    /// 测试将基础密钥重新分享给新的密钥服务器集合
+    /// 1. 重新分享后密文保持不变，新的密钥服务器可以解密
+    /// 2. 旧的密钥服务器的私钥不能解密新对象
+    /// 3. 错误的基础密钥和无效的阈值会被拒绝
+    #[test]
+    fn test_reshare() {
+        let data = b"Hello, World!";
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3, 4];
+        let full_id = create_full_id(&package_id, &id);
+        let mut rng = rand::thread_rng();
+
+        // 旧的密钥服务器：3个，阈值为2
+        let old_keypairs = (0..3)
+            .map(|_| ibe::generate_key_pair(&mut rng))
+            .collect_vec();
+        let old_services = old_keypairs
+            .iter()
+            .map(|_| ObjectID::random())
+            .collect_vec();
+        let old_public_keys = IBEPublicKeys::BonehFranklinBLS12381(
+            old_keypairs.iter().map(|(_, pk)| *pk).collect_vec(),
+        );
+        let old_user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(
+            old_services
+                .iter()
+                .zip(&old_keypairs)
+                .map(|(s, kp)| (*s, ibe::extract(&kp.0, &full_id)))
+                .collect(),
+        );
+
+        // 新的密钥服务器：4个，阈值为3
+        let new_keypairs = (0..4)
+            .map(|_| ibe::generate_key_pair(&mut rng))
+            .collect_vec();
+        let new_services = new_keypairs
+            .iter()
+            .map(|_| ObjectID::random())
+            .collect_vec();
+        let new_public_keys = IBEPublicKeys::BonehFranklinBLS12381(
+            new_keypairs.iter().map(|(_, pk)| *pk).collect_vec(),
+        );
+        let new_user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(
+            new_services
+                .iter()
+                .zip(&new_keypairs)
+                .skip(1)
+                .map(|(s, kp)| (*s, ibe::extract(&kp.0, &full_id)))
                 .collect(),
         );
-        let decrypted = seal_decrypt(&encrypted, &user_secret_keys, Some(&public_keys)).unwrap();
 
-        // 验证解密结果
-        assert_eq!(data, decrypted.as_slice());
+        let (encrypted, dem_key) = seal_encrypt(
+            package_id,
+            id,
+            old_services,
+            &old_public_keys,
+            2,
+            EncryptionInput::Aes256Gcm {
+                data: data.to_vec(),
+                aad: None,
+            },
+        )
+        .unwrap();
+
+        let reshared = reshare_with_user_secret_keys(
+            &encrypted,
+            &old_user_secret_keys,
+            Some(&old_public_keys),
+            new_services.clone(),
+            &new_public_keys,
+            3,
+        )
+        .unwrap();
+        assert_eq!(reshared.threshold, 3);
+        assert_eq!(
+            bcs::to_bytes(&reshared.ciphertext).unwrap(),
+            bcs::to_bytes(&encrypted.ciphertext).unwrap()
+        );
+        assert_eq!(
+            seal_decrypt(&reshared, &new_user_secret_keys, Some(&new_public_keys)).unwrap(),
+            data
+        );
+        assert_eq!(
+            seal_decrypt_key(&reshared, &new_user_secret_keys, None)
+                .unwrap()
+                .expose(),
+            dem_key.expose()
+        );
+        assert!(seal_decrypt(&reshared, &old_user_secret_keys, None).is_err());
+
+        // 使用已知的基础密钥重新分享
+        let base_key = seal_decrypt_base_key(&encrypted, &old_user_secret_keys, None).unwrap();
+        let reshared = reshare(
+            &encrypted,
+            base_key.expose(),
+            new_services.clone(),
+            &new_public_keys,
+            3,
+        )
+        .unwrap();
+        assert_eq!(
+            seal_decrypt(&reshared, &new_user_secret_keys, Some(&new_public_keys)).unwrap(),
+            data
+        );
 
-        // 验证AAD更改时解密失败
-        let mut modified_encrypted = encrypted.clone();
-        match modified_encrypted.ciphertext {
-            Ciphertext::Hmac256Ctr { ref mut aad, .. } => {
-                match aad {
-                    None => panic!(),
-                    Some(ref mut aad) => aad.push(0),
-                }
-                assert!(
-                    seal_decrypt(&modified_encrypted, &user_secret_keys, Some(&public_keys))
-                        .is_err()
-                );
-            }
-            _ => panic!(),
-        }
+        // 错误的基础密钥和无效的阈值
+        assert!(reshare(
+            &encrypted,
+            &[0; KEY_SIZE],
+            new_services.clone(),
+            &new_public_keys,
+            3
+        )
+        .is_err());
+        assert!(reshare(
+            &encrypted,
+            base_key.expose(),
+            new_services.clone(),
+            &new_public_keys,
+            5
+        )
+        .is_err());
+        assert!(reshare(
+            &encrypted,
+            base_key.expose(),
+            new_services,
+            &old_public_keys,
+            3
+        )
+        .is_err());
     }
 
     /// 测试明文模式（Plain）的加密和解密流程
@@ -666,7 +3994,7 @@ mod tests {
 
         // 验证解密结果就是原始密钥
         assert_eq!(
-            key.to_vec(),
+            key.expose().to_vec(),
             seal_decrypt(
                 &encrypted,
                 &IBEUserSecretKeys::BonehFranklinBLS12381(user_secret_keys),
@@ -676,6 +4004,54 @@ mod tests {
         );
     }
 
+    /// 测试子密钥派生：不同的标签得到不同的子密钥，结果与HKDF-SHA3-256的定义一致，
+    /// 其他实现可以使用这里的固定值检查兼容性
+    #[test]
+    fn test_derive_subkey() {
+        let key: [u8; KEY_SIZE] = core::array::from_fn(|i| i as u8);
+        let cases = [
+            (
+                SubkeyLabel::Encryption,
+                "573dc3c45f65e24438c819b39fd4763f63ba83f6622ac8ffb206d6309b298f3a",
+            ),
+            (
+                SubkeyLabel::Mac,
+                "3f7eb45d74095fea5801a3cf21ac9b90efcb8cb9e8dd91eac7b9f7dac98be4a6",
+            ),
+            (
+                SubkeyLabel::File(0),
+                "34737ee5b4055b91b05c64d11ade6f8c1a37d0dfe2c2991b3431b32fa9e10398",
+            ),
+            (
+                SubkeyLabel::File(7),
+                "95c420298c95ca094ae864b09b44907453fb72c8004b2cf518d17ec52ffc2005",
+            ),
+            (
+                SubkeyLabel::File(u64::MAX),
+                "e69bf6ef536ca6f1e31906ed85050e122a8be0e22f0b875d31af6de3318a8dd3",
+            ),
+            (
+                SubkeyLabel::Custom(b"file-7".to_vec()),
+                "dd31b4eff5f211fd8ef3648428399e7257573db7c7f1ca064ec3e772402e9638",
+            ),
+        ];
+        for (label, expected) in cases {
+            assert_eq!(
+                hex::encode(derive_subkey(&key, label).unwrap().expose()),
+                expected
+            );
+        }
+
+        // 自定义标签与内置标签的编码不同，即使字符串相同也不会得到内置标签的子密钥
+        assert_ne!(
+            derive_subkey(&key, SubkeyLabel::Custom(b"file-7".to_vec()))
+                .unwrap()
+                .expose(),
+            derive_subkey(&key, SubkeyLabel::File(7)).unwrap().expose()
+        );
+        assert!(derive_subkey(&key, SubkeyLabel::Custom(vec![])).is_err());
+    }
+
     /// 测试与TypeScript实现的兼容性
     /// 使用预定义的测试向量确保跨语言实现的一致性
     #[test]
@@ -692,7 +4068,9 @@ mod tests {
         ]
         .iter()
         .map(|key| {
-            Scalar::from_byte_array(&Base64::decode(key).unwrap().try_into().unwrap()).unwrap()
+            ibe::MasterKey::new(
+                Scalar::from_byte_array(&Base64::decode(key).unwrap().try_into().unwrap()).unwrap(),
+            )
         })
         .collect::<Vec<_>>();
         
@@ -720,8 +4098,8 @@ mod tests {
         let full_id = create_full_id(&package_id, &inner_id);
         let user_secret_keys = object_ids
             .into_iter()
-            .zip(master_keys)
-            .map(|(s, k)| (s, ibe::extract(&k, &full_id)))
+            .zip(&master_keys)
+            .map(|(s, k)| (s, ibe::extract(k, &full_id)))
             .collect();
 
         // 解密并验证结果
@@ -735,6 +4113,165 @@ mod tests {
         assert_eq!(decrypted, b"My super secret message");
     }
 
+    /// 测试加密对象的版本1格式和升级
+    /// 1. 版本0对象的编码保持不变
+    /// 2. 升级后的对象可以序列化、解析并使用相同的私钥解密
+    /// 3. 不支持的版本和不认识的关键扩展会被拒绝
+    #[test]
+    fn test_encrypted_object_v1() {
+        // 版本0对象的编码与TypeScript测试向量相同
+        let bytes = Base64::decode("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABAECAwQDAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAE4AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAALKAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAM3AgCEgtXcUe2iGMS8zEMEB9YVJo4WbdUuW7uqNBLEJc+xA0pnC6TNep2SGpudVO3gXtAG7W4lSNmc/xMhFv9WDfaTZfppIk7H6IXEmM8aUfjk6TyXtMO2D5T0PzB3HhTNIo4De81Z5tb7mnshJWTjJtHBoeWWUpoSunAGQQAWsGFQ5NK9AnAugziSj/SnS5I042nRGswaeMmTBG5+FyLP1FJPSadWZGTQSZzQGcRVVefDJw5gUxUVMhT+CfesAVHHZKkanKv0UhCEy3EnKc6Bkrl09fSLqo7hTKwqNxCJf9oaHhkAJ81y6phEffQ8F4xsbi87mpR05qGNtzvbyh/Y4PLhhL8yQyy4gxhPHwEEAQIDBA==").unwrap();
+        let v0 = EncryptedObject::from_bytes(&bytes).unwrap();
+        assert_eq!(v0.version, 0);
+        assert!(v0.extensions.is_empty());
+        assert_eq!(v0.to_bytes().unwrap(), bytes);
+
+        // 多余的字节和不支持的版本
+        assert!(EncryptedObject::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+        let mut v2 = bytes.clone();
+        v2[0] = 2;
+        assert!(EncryptedObject::from_bytes(&v2).is_err());
+        assert!(bcs::from_bytes::<EncryptedObject>(&v2).is_err());
+
+        // 版本0对象不能包含扩展
+        let mut invalid = v0.clone();
+        assert!(invalid.set_created_at(1).is_err());
+        invalid.extensions.push(Extension {
+            tag: header::TAG_CREATED_AT,
+            value: vec![],
+        });
+        assert!(invalid.to_bytes().is_err());
+
+        // 升级并设置可选的头部字段
+        let data = b"Hello, World!";
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3, 4];
+        let full_id = create_full_id(&package_id, &id);
+        let mut rng = rand::thread_rng();
+        let keypairs = (0..3)
+            .map(|_| ibe::generate_key_pair(&mut rng))
+            .collect_vec();
+        let services = keypairs.iter().map(|_| ObjectID::random()).collect_vec();
+        let public_keys =
+            IBEPublicKeys::BonehFranklinBLS12381(keypairs.iter().map(|(_, pk)| *pk).collect_vec());
+        let user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(
+            services
+                .iter()
+                .zip(&keypairs)
+                .map(|(s, kp)| (*s, ibe::extract(&kp.0, &full_id)))
+                .collect(),
+        );
+        let encrypted = seal_encrypt(
+            package_id,
+            id,
+            services,
+            &public_keys,
+            2,
+            EncryptionInput::ChaCha20Poly1305 {
+                data: data.to_vec(),
+                aad: None,
+            },
+        )
+        .unwrap()
+        .0;
+
+        let mut upgraded = upgrade_to_v1(&encrypted);
+        assert_eq!(upgraded.version, 1);
+        assert_eq!(
+            upgraded.extension(header::TAG_DEM_ALGORITHM),
+            Some(&[4u8][..])
+        );
+        upgraded.set_created_at(1_700_000_000_000).unwrap();
+        upgraded.set_content_type("text/plain").unwrap();
+
+        let parsed = EncryptedObject::from_bytes(&upgraded.to_bytes().unwrap()).unwrap();
+        assert_eq!(parsed.version, 1);
+        assert_eq!(parsed.extensions, upgraded.extensions);
+        assert_eq!(parsed.created_at(), Some(1_700_000_000_000));
+        assert_eq!(parsed.content_type(), Some("text/plain"));
+        assert_eq!(
+            seal_decrypt(&parsed, &user_secret_keys, Some(&public_keys)).unwrap(),
+            data
+        );
+
+        // 人类可读格式
+        let json = serde_json::to_string(&parsed).unwrap();
+        let from_json: EncryptedObject = serde_json::from_str(&json).unwrap();
+        assert_eq!(from_json.extensions, parsed.extensions);
+        let json = serde_json::to_string(&encrypted).unwrap();
+        assert!(!json.contains("extensions"));
+        assert!(serde_json::from_str::<EncryptedObject>(&json).is_ok());
+
+        // 不认识的关键扩展
+        let mut critical = parsed.clone();
+        assert!(critical
+            .set_extension(header::CRITICAL_BIT | 0x7fff, vec![])
+            .is_err());
+        assert_eq!(critical.extensions, parsed.extensions);
+        header::set_extension(
+            &mut critical.extensions,
+            header::CRITICAL_BIT | 0x7fff,
+            vec![],
+        );
+        assert!(critical.to_bytes().is_err());
+        assert!(seal_decrypt(&critical, &user_secret_keys, Some(&public_keys)).is_err());
+
+        // 算法标识符与密文类型不一致
+        let mut mismatch = parsed.clone();
+        header::set_extension(&mut mismatch.extensions, header::TAG_DEM_ALGORITHM, vec![0]);
+        assert!(mismatch.to_bytes().is_err());
+        assert!(seal_decrypt(&mismatch, &user_secret_keys, Some(&public_keys)).is_err());
+    }
+
+    /// 固定每种密文类型的算法标识符，它们已写入现有对象的头部，不能随枚举顺序改变
+    #[test]
+    fn test_algorithm_id() {
+        let ciphertexts = [
+            (
+                Ciphertext::Aes256Gcm {
+                    blob: vec![],
+                    aad: None,
+                },
+                0,
+            ),
+            (
+                Ciphertext::Hmac256Ctr {
+                    blob: vec![],
+                    aad: None,
+                    mac: [0; KEY_SIZE],
+                },
+                1,
+            ),
+            (Ciphertext::Plain, 2),
+            (
+                Ciphertext::Aes256GcmChunked {
+                    blob: vec![],
+                    aad: None,
+                    chunk_size: 1,
+                },
+                3,
+            ),
+            (
+                Ciphertext::ChaCha20Poly1305 {
+                    blob: vec![],
+                    aad: None,
+                },
+                4,
+            ),
+            (
+                Ciphertext::Aes256GcmCommitting {
+                    blob: vec![],
+                    aad: None,
+                    commitment: [0; KEY_SIZE],
+                },
+                5,
+            ),
+        ];
+        for (ciphertext, id) in ciphertexts {
+            assert_eq!(ciphertext.algorithm_id(), id);
+        }
+    }
+
     /// 测试共享一致性检查
     /// 验证共享修改时的系统行为：
     /// 1. 如果不验证一致性，使用t个有效共享可以成功解密
@@ -818,4 +4355,449 @@ mod tests {
         assert!(seal_decrypt(&encrypted, &usks, Some(&public_keys),)
             .is_err_and(|e| e == GeneralError("共享不一致".to_string())));
     }
+
+    /// 测试签名的加密对象：预期的发送者可以解密，其他发送者被拒绝，解压缩的长度受到限制
+    #[test]
+    fn test_verify_and_decrypt() {
+        use fastcrypto::ed25519::Ed25519KeyPair;
+        use fastcrypto::traits::KeyPair;
+
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3];
+        let full_id = create_full_id(&package_id, &id);
+        let mut rng = rand::thread_rng();
+        let (sk, pk) = ibe::generate_key_pair(&mut rng);
+        let service = ObjectID::random();
+        let public_keys = IBEPublicKeys::BonehFranklinBLS12381(vec![pk]);
+        let data = b"Hello, World!";
+
+        let sender = Ed25519KeyPair::generate(&mut rng);
+        let other = Ed25519KeyPair::generate(&mut rng);
+        let (signed, _) = seal_encrypt_and_sign(
+            package_id,
+            id,
+            vec![service],
+            &public_keys,
+            1,
+            EncryptionInput::Aes256Gcm {
+                data: data.to_vec(),
+                aad: None,
+            }
+            .compressed(Compression::Zstd),
+            &SigningKey::Ed25519(&sender),
+        )
+        .unwrap();
+
+        let usks = IBEUserSecretKeys::BonehFranklinBLS12381(HashMap::from([(
+            service,
+            ibe::extract(&sk, &full_id),
+        )]));
+        assert_eq!(
+            verify_and_decrypt(
+                &signed,
+                &Sender::Ed25519(sender.public().clone()),
+                &usks,
+                Some(&public_keys),
+            )
+            .unwrap(),
+            data
+        );
+        assert!(verify_and_decrypt(
+            &signed,
+            &Sender::Ed25519(other.public().clone()),
+            &usks,
+            Some(&public_keys),
+        )
+        .is_err_and(|e| e == GeneralError("Unexpected sender".to_string())));
+        assert!(verify_and_decrypt_with_max_size(
+            &signed,
+            &Sender::Ed25519(sender.public().clone()),
+            &usks,
+            Some(&public_keys),
+            data.len() - 1,
+        )
+        .is_err());
+    }
+
+    /// 测试头部绑定：
+    /// 1. 绑定的对象可以正常解密，修改非关键扩展不影响解密
+    /// 2. 持有基础密钥的攻击者把密文移到新的密钥服务器和阈值下，解密失败
+    /// 3. 移除绑定扩展后解密失败
+    #[test]
+    fn test_context_binding() {
+        let data = b"Hello, World!";
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3, 4];
+        let full_id = create_full_id(&package_id, &id);
+        let mut rng = rand::thread_rng();
+        let keypairs = (0..3)
+            .map(|_| ibe::generate_key_pair(&mut rng))
+            .collect_vec();
+        let services = keypairs.iter().map(|_| ObjectID::random()).collect_vec();
+        let public_keys =
+            IBEPublicKeys::BonehFranklinBLS12381(keypairs.iter().map(|(_, pk)| *pk).collect());
+        let user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(
+            services
+                .iter()
+                .zip(&keypairs)
+                .map(|(s, (sk, _))| (*s, ibe::extract(sk, &full_id)))
+                .collect(),
+        );
+
+        let (mut encrypted, dem_key) = seal_encrypt_context_bound(
+            package_id,
+            id.clone(),
+            services.clone(),
+            &public_keys,
+            2,
+            EncryptionInput::Aes256Gcm {
+                data: data.to_vec(),
+                aad: Some(b"aad".to_vec()),
+            },
+        )
+        .unwrap();
+        assert_eq!(encrypted.version, 1);
+        encrypted.set_created_at(1234).unwrap();
+        let encrypted = EncryptedObject::from_bytes(&encrypted.to_bytes().unwrap()).unwrap();
+        assert_eq!(
+            seal_decrypt(&encrypted, &user_secret_keys, Some(&public_keys)).unwrap(),
+            data
+        );
+        let Ciphertext::Aes256Gcm { blob, .. } = &encrypted.ciphertext else {
+            panic!("Expected an AES-256-GCM ciphertext");
+        };
+        assert_eq!(
+            Aes256Gcm::decrypt(blob, &encrypted.dem_aad(), dem_key.expose()).unwrap(),
+            data
+        );
+
+        // 绑定的对象不能重新分享
+        let base_key =
+            seal_decrypt_base_key(&encrypted, &user_secret_keys, Some(&public_keys)).unwrap();
+        assert!(reshare(
+            &encrypted,
+            base_key.expose(),
+            services.clone(),
+            &public_keys,
+            3
+        )
+        .is_err());
+
+        // 绕过检查，把密文移到阈值为3的新头部下
+        let mut unbound = encrypted.clone();
+        unbound
+            .extensions
+            .retain(|ext| ext.tag != header::TAG_CONTEXT_BINDING);
+        let mut spliced = reshare(&unbound, base_key.expose(), services, &public_keys, 3).unwrap();
+        assert!(seal_decrypt(&spliced, &user_secret_keys, Some(&public_keys)).is_err());
+        header::set_extension(&mut spliced.extensions, header::TAG_CONTEXT_BINDING, vec![]);
+        assert!(seal_decrypt(&spliced, &user_secret_keys, Some(&public_keys)).is_err());
+
+        // 移除绑定扩展，即使把头部摘要移到密文的额外认证数据中也无法解密
+        assert!(seal_decrypt(&unbound, &user_secret_keys, Some(&public_keys)).is_err());
+        if let Ciphertext::Aes256Gcm { aad, .. } = &mut unbound.ciphertext {
+            *aad = Some(encrypted.dem_aad());
+        }
+        assert_eq!(unbound.dem_aad(), encrypted.dem_aad());
+        assert!(seal_decrypt(&unbound, &user_secret_keys, Some(&public_keys)).is_err());
+
+        // 不支持Plain模式
+        assert!(seal_encrypt_context_bound(
+            package_id,
+            id,
+            vec![ObjectID::random()],
+            &IBEPublicKeys::BonehFranklinBLS12381(vec![keypairs[0].1]),
+            1,
+            EncryptionInput::Plain,
+        )
+        .is_err());
+    }
+
+    /// 测试填充：
+    /// 1. 填充的对象可以正常解密，得到原始明文
+    /// 2. 同一个桶中不同长度的明文得到相同长度的密文
+    /// 3. 移除或修改填充扩展后无法解密
+    /// 4. 不能填充明文模式，也不能嵌套填充
+    #[test]
+    fn test_padding() {
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3, 4];
+        let full_id = create_full_id(&package_id, &id);
+        let mut rng = rand::thread_rng();
+        let (sk, pk) = ibe::generate_key_pair(&mut rng);
+        let services = vec![ObjectID::random()];
+        let public_keys = IBEPublicKeys::BonehFranklinBLS12381(vec![pk]);
+        let user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(HashMap::from([(
+            services[0],
+            ibe::extract(&sk, &full_id),
+        )]));
+        let encrypt = |data: &[u8], padding: Padding| {
+            seal_encrypt(
+                package_id,
+                id.clone(),
+                services.clone(),
+                &public_keys,
+                1,
+                EncryptionInput::ChaCha20Poly1305 {
+                    data: data.to_vec(),
+                    aad: None,
+                }
+                .padded(padding),
+            )
+            .unwrap()
+            .0
+        };
+
+        let short = encrypt(b"Ace", Padding::Bucket(64));
+        let long = encrypt(b"Queen of hearts", Padding::Bucket(64));
+        assert_eq!(short.version, 1);
+        assert_eq!(short.padding().unwrap(), Some(Padding::Bucket(64)));
+        assert_eq!(
+            short.to_bytes().unwrap().len(),
+            long.to_bytes().unwrap().len()
+        );
+        for (encrypted, data) in [(&short, &b"Ace"[..]), (&long, &b"Queen of hearts"[..])] {
+            let encrypted = EncryptedObject::from_bytes(&encrypted.to_bytes().unwrap()).unwrap();
+            assert_eq!(
+                seal_decrypt(&encrypted, &user_secret_keys, Some(&public_keys)).unwrap(),
+                data
+            );
+        }
+        let padme = encrypt(&[0; 1000], Padding::Padme);
+        assert_eq!(
+            seal_decrypt(&padme, &user_secret_keys, Some(&public_keys)).unwrap(),
+            vec![0; 1000]
+        );
+
+        // 移除填充扩展或者修改填充策略都会改变对称密钥
+        let mut stripped = short.clone();
+        stripped
+            .extensions
+            .retain(|ext| ext.tag != header::TAG_PADDING);
+        assert!(seal_decrypt(&stripped, &user_secret_keys, Some(&public_keys)).is_err());
+        let mut modified = short.clone();
+        modified
+            .set_extension(
+                header::TAG_PADDING,
+                bcs::to_bytes(&Padding::PowerOfTwo).unwrap(),
+            )
+            .unwrap();
+        assert!(seal_decrypt(&modified, &user_secret_keys, Some(&public_keys)).is_err());
+
+        // 填充也可以与头部绑定一起使用
+        let (bound, _) = seal_encrypt_context_bound(
+            package_id,
+            id.clone(),
+            services.clone(),
+            &public_keys,
+            1,
+            EncryptionInput::Aes256Gcm {
+                data: b"Ace".to_vec(),
+                aad: None,
+            }
+            .padded(Padding::PowerOfTwo),
+        )
+        .unwrap();
+        assert_eq!(
+            seal_decrypt(&bound, &user_secret_keys, Some(&public_keys)).unwrap(),
+            b"Ace"
+        );
+
+        for input in [
+            EncryptionInput::Plain.padded(Padding::Padme),
+            EncryptionInput::Aes256Gcm {
+                data: vec![],
+                aad: None,
+            }
+            .padded(Padding::Padme)
+            .padded(Padding::Padme),
+            EncryptionInput::Aes256Gcm {
+                data: vec![],
+                aad: None,
+            }
+            .padded(Padding::Bucket(0)),
+        ] {
+            assert!(seal_encrypt(
+                package_id,
+                id.clone(),
+                services.clone(),
+                &public_keys,
+                1,
+                input
+            )
+            .is_err());
+        }
+    }
+
+    /// 测试压缩：
+    /// 1. 压缩的对象可以正常解密，得到原始明文，并且比没有压缩的对象小
+    /// 2. 可以同时压缩和填充，但不能先填充再压缩
+    /// 3. 超过最大长度时无法解密
+    /// 4. 移除压缩扩展后无法解密
+    #[test]
+    fn test_compression() {
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3, 4];
+        let full_id = create_full_id(&package_id, &id);
+        let mut rng = rand::thread_rng();
+        let (sk, pk) = ibe::generate_key_pair(&mut rng);
+        let services = vec![ObjectID::random()];
+        let public_keys = IBEPublicKeys::BonehFranklinBLS12381(vec![pk]);
+        let user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(HashMap::from([(
+            services[0],
+            ibe::extract(&sk, &full_id),
+        )]));
+        let encrypt = |input: EncryptionInput| {
+            seal_encrypt(
+                package_id,
+                id.clone(),
+                services.clone(),
+                &public_keys,
+                1,
+                input,
+            )
+        };
+        let data = br#"{"card": "Queen of hearts", "player": 1}"#.repeat(50);
+        let input = EncryptionInput::Aes256Gcm {
+            data: data.clone(),
+            aad: Some(b"aad".to_vec()),
+        };
+
+        let (uncompressed, _) = encrypt(input.clone()).unwrap();
+        for compression in [Compression::Deflate, Compression::Zstd] {
+            let (encrypted, dem_key) = encrypt(input.clone().compressed(compression)).unwrap();
+            assert_eq!(encrypted.version, 1);
+            assert_eq!(encrypted.compression().unwrap(), Some(compression));
+            assert!(encrypted.to_bytes().unwrap().len() < uncompressed.to_bytes().unwrap().len());
+            let encrypted = EncryptedObject::from_bytes(&encrypted.to_bytes().unwrap()).unwrap();
+            assert_eq!(
+                seal_decrypt(&encrypted, &user_secret_keys, Some(&public_keys)).unwrap(),
+                data
+            );
+
+            // 使用派生对称密钥直接解密
+            let Ciphertext::Aes256Gcm { blob, .. } = &encrypted.ciphertext else {
+                panic!("Expected an AES-256-GCM ciphertext");
+            };
+            let plaintext = Aes256Gcm::decrypt(blob, &encrypted.dem_aad(), dem_key.expose());
+            assert_eq!(
+                encrypted
+                    .decode_plaintext(plaintext.unwrap(), data.len())
+                    .unwrap(),
+                data
+            );
+
+            // 超过最大长度
+            assert!(seal_decrypt_with_max_size(
+                &encrypted,
+                &user_secret_keys,
+                Some(&public_keys),
+                data.len() - 1
+            )
+            .is_err());
+            assert_eq!(
+                seal_decrypt_robust_with_max_size(
+                    &encrypted,
+                    &user_secret_keys,
+                    &public_keys,
+                    data.len()
+                ),
+                Ok((data.clone(), vec![]))
+            );
+            assert!(seal_decrypt_robust_with_max_size(
+                &encrypted,
+                &user_secret_keys,
+                &public_keys,
+                data.len() - 1
+            )
+            .is_err());
+
+            // 移除压缩扩展会改变对称密钥
+            let mut stripped = encrypted.clone();
+            stripped
+                .extensions
+                .retain(|ext| ext.tag != header::TAG_COMPRESSION);
+            assert!(seal_decrypt(&stripped, &user_secret_keys, Some(&public_keys)).is_err());
+        }
+
+        // 先压缩再填充
+        let (encrypted, _) = encrypt(
+            input
+                .clone()
+                .compressed(Compression::Zstd)
+                .padded(Padding::Padme),
+        )
+        .unwrap();
+        assert_eq!(encrypted.padding().unwrap(), Some(Padding::Padme));
+        assert_eq!(
+            seal_decrypt(&encrypted, &user_secret_keys, Some(&public_keys)).unwrap(),
+            data
+        );
+
+        for input in [
+            input
+                .clone()
+                .padded(Padding::Padme)
+                .compressed(Compression::Zstd),
+            input
+                .compressed(Compression::Zstd)
+                .compressed(Compression::Deflate),
+            EncryptionInput::Plain.compressed(Compression::Zstd),
+        ] {
+            assert!(encrypt(input).is_err());
+        }
+    }
+
+    /// 测试带有密钥承诺的AES-256-GCM模式：
+    /// 1. 加密对象可以正常解密
+    /// 2. 把密文放到另一个对象的加密共享下（重建出不同的基础密钥）时，因承诺不符而被拒绝
+    #[test]
+    fn test_encryption_round_trip_aes256gcm_committing() {
+        let data = b"Hello, World!";
+        let package_id = ObjectID::random();
+        let id = vec![1, 2, 3, 4];
+        let full_id = create_full_id(&package_id, &id);
+        let mut rng = rand::thread_rng();
+        let keypairs = (0..3)
+            .map(|_| ibe::generate_key_pair(&mut rng))
+            .collect_vec();
+        let services = keypairs.iter().map(|_| ObjectID::random()).collect_vec();
+        let public_keys =
+            IBEPublicKeys::BonehFranklinBLS12381(keypairs.iter().map(|(_, pk)| *pk).collect());
+        let user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(
+            services
+                .iter()
+                .zip(&keypairs)
+                .map(|(s, (sk, _))| (*s, ibe::extract(sk, &full_id)))
+                .collect(),
+        );
+        let encrypt = |data: &[u8]| {
+            seal_encrypt(
+                package_id,
+                id.clone(),
+                services.clone(),
+                &public_keys,
+                2,
+                EncryptionInput::Aes256GcmCommitting {
+                    data: data.to_vec(),
+                    aad: Some(b"something".to_vec()),
+                },
+            )
+            .unwrap()
+            .0
+        };
+
+        let encrypted = encrypt(data);
+        assert_eq!(
+            seal_decrypt(&encrypted, &user_secret_keys, Some(&public_keys)).unwrap(),
+            data
+        );
+
+        let mut spliced = encrypt(b"Something else");
+        spliced.ciphertext = encrypted.ciphertext.clone();
+        assert!(
+            seal_decrypt(&spliced, &user_secret_keys, Some(&public_keys))
+                .is_err_and(|e| e == GeneralError("Invalid key commitment".to_string()))
+        );
+    }
 }
@@ -16,10 +16,12 @@
  * 主要功能:
  * - 多项式求值
  * - 多项式加法、乘法和标量除法
+ * - 多项式带余除法
  * - Lagrange多项式插值
  */
 
 use crate::gf256::GF256;
+use fastcrypto::error::FastCryptoError::InvalidInput;
 use fastcrypto::error::FastCryptoResult;
 use itertools::Itertools;
 use std::iter::{Product, Sum};
@@ -139,6 +141,40 @@ impl Polynomial {
             })
             .sum()
     }
+
+    /**
+     * 多项式带余除法
+     * 
+     * 计算商q和余数r，使得self = q * divisor + r，并且r的次数小于除数的次数。
+     * 
+     * 参数:
+     * @param divisor - 除数，不能是零多项式
+     * 
+     * 返回:
+     * (商, 余数)，如果除数是零多项式则返回错误
+     */
+    pub fn div_rem(&self, divisor: &Polynomial) -> FastCryptoResult<(Polynomial, Polynomial)> {
+        let divisor = divisor.clone().strip_trailing_zeros();
+        let leading = divisor.0.last().ok_or(InvalidInput)?;
+        let mut remainder = self.clone().strip_trailing_zeros().0;
+        if remainder.len() < divisor.0.len() {
+            return Ok((Self::zero(), Self(remainder)));
+        }
+
+        // 从最高次项开始，每一步消去余数的最高次项
+        let mut quotient = vec![GF256::zero(); remainder.len() + 1 - divisor.0.len()];
+        for i in (0..quotient.len()).rev() {
+            let coefficient = (&remainder[i + divisor.0.len() - 1] / leading)?;
+            for (j, d) in divisor.0.iter().enumerate() {
+                remainder[i + j] = &remainder[i + j] - &(&coefficient * d);
+            }
+            quotient[i] = coefficient;
+        }
+        Ok((
+            Self(quotient).strip_trailing_zeros(),
+            Self(remainder).strip_trailing_zeros(),
+        ))
+    }
 }
 
 /**
@@ -281,6 +317,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_div_rem() {
+        let p1 = Polynomial(vec![GF256::from(1), GF256::from(2), GF256::from(3)]);
+        let p2 = Polynomial(vec![GF256::from(4), GF256::from(5)]);
+        let p3 = Polynomial(vec![GF256::from(2)]);
+
+        // (p1 * p2 + p3) / p2 = (p1, p3)
+        let dividend = &(&p1 * &p2) + &p3;
+        assert_eq!(dividend.div_rem(&p2).unwrap(), (p1.clone(), p3.clone()));
+        assert_eq!(p2.div_rem(&p1).unwrap(), (Polynomial::zero(), p2.clone()));
+        assert!(p1.div_rem(&Polynomial::zero()).is_err());
+    }
+
     #[test]
     fn test_interpolation() {
         let x = [GF256::from(1), GF256::from(2), GF256::from(3)];
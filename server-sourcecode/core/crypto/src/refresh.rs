@@ -0,0 +1,836 @@
+// Copyright (c), Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/**
+ * 主密钥份额的主动刷新 (Proactive Refresh) 模块
+ *
+ * 通过DKG生成的主密钥份额需要定期重新随机化，使攻击者在不同时期窃取的份额无法组合使用。
+ * 刷新不会改变主公钥，因此链上注册的`KeyServer.pk`和所有已有的`EncryptedObject`都保持可用。
+ *
+ * 本模块基于零共享 (zero-sharing)：每个分发者选择一个在指定根点取值为零的随机多项式
+ * δ(X) = (X - root) · q(X)，广播q的系数承诺，并使用`dkg`中的份额加密方式把δ(j)发给接收方。
+ * 由于δ(root) = 0，任何接收方都可以通过承诺验证收到的值，而不需要知道多项式本身。
+ *
+ * 支持两种操作:
+ * 1. 刷新（`deal_refresh`, `refresh`）：根点为0，所有参与方把收到的零共享加到自己的份额上。
+ *    新份额仍然是同一主密钥的份额，但与旧份额无关，旧份额应在刷新后删除。
+ * 2. 恢复（`deal_recovery`, `recovery_share`, `recover`）：根点为要恢复的索引r，
+ *    至少阈值数量的帮助者在彼此之间交换零共享，然后各自把掩码后的份额 x_i + Δ(i) 发给接管索引r的新机器。
+ *    新机器在r处插值得到 x_r，并且任何单个帮助者的份额都不会泄露给它。
+ *
+ * 与DKG相同，零共享消息使用分发者的参与方私钥进行BLS签名，`refresh`、`recovery_share`和`recover`
+ * 拒绝签名无效的消息。否则攻击者可以把所有分发者的零共享替换为自己的零共享，从而知道所有零共享之和，
+ * 并把窃取的旧份额转换为有效的新份额。签名以参与方的公钥为信任根，公钥必须通过认证信道分发。
+ *
+ * 参与方的公钥是长期使用的，因此每条消息还带有轮次编号`epoch`并被签名覆盖，处理消息时必须传入期望的轮次。
+ * 否则某一轮中签名的零共享可以在之后的轮次中被重放，同一个Δ被加两次，
+ * 任何同时持有某一轮前后份额的人都能算出该Δ，主动安全性随之失效。
+ * 委员会必须为每一轮刷新或恢复使用不同的轮次编号，例如单调递增的计数器。
+ *
+ * 与DKG相同，本实现没有投诉阶段：任何无效消息都会导致返回指明出错参与方的错误。
+ */
+use crate::dkg::{
+    decrypt_share, encrypt_share, evaluate, evaluate_commitments, generate_party_key,
+    lagrange_coefficients_at, validate_parties, EncryptedShare, KeyShare, Party, PartySecretKey,
+};
+use crate::ibe;
+use crate::secret::Secret;
+use fastcrypto::error::FastCryptoError::{GeneralError, InvalidInput};
+use fastcrypto::error::FastCryptoResult;
+use fastcrypto::groups::bls12381::{G1Element, G2Element, Scalar};
+use fastcrypto::groups::{GroupElement, HashToGroupElement, Pairing, Scalar as GenericScalar};
+use fastcrypto::traits::AllowedRng;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::iter::repeat_with;
+
+/// 零共享消息签名的域分隔标签
+const DST_REFRESH_MESSAGE: &[u8] = b"SUI-SEAL-REFRESH-MESSAGE-00";
+
+/// 分发者广播的零共享消息
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZeroSharingMessage {
+    /// 本轮刷新或恢复的轮次编号，使消息不能在其他轮次中重放
+    pub epoch: u64,
+    /// 分发者的索引
+    pub dealer: u8,
+    /// 多项式取值为零的点，刷新时为0，恢复时为要恢复的索引
+    pub root: u8,
+    /// 多项式 q(X) 系数的承诺 g2^{q_k}，共`threshold - 1`个
+    pub commitments: Vec<G2Element>,
+    /// 发给每个接收方的加密值 δ(j)
+    pub encrypted_shares: Vec<EncryptedShare>,
+    /// 分发者使用其参与方私钥对消息的BLS签名，参见`signing_message`
+    pub signature: G2Element,
+}
+
+/// 帮助者发给新机器的掩码份额
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveryShare {
+    /// 帮助者的索引
+    pub helper: u8,
+    /// 加密的掩码份额 x_i + Δ(i)
+    pub encrypted_share: EncryptedShare,
+}
+
+/**
+ * 生成刷新用的零共享消息
+ *
+ * 参数:
+ * @param rng - 随机数生成器
+ * @param key_share - 自己当前的主密钥份额
+ * @param secret_key - 自己的参与方私钥，用于签名消息
+ * @param parties - 所有参与方的公开信息
+ * @param epoch - 本轮刷新的轮次编号
+ *
+ * 返回:
+ * 要广播给所有参与方的消息
+ */
+pub fn deal_refresh<R: AllowedRng>(
+    rng: &mut R,
+    key_share: &KeyShare,
+    secret_key: &PartySecretKey,
+    parties: &[Party],
+    epoch: u64,
+) -> FastCryptoResult<ZeroSharingMessage> {
+    validate_committee(key_share, parties)?;
+    deal_zero_sharing(
+        rng,
+        secret_key,
+        epoch,
+        key_share.index,
+        key_share.threshold,
+        0,
+        parties,
+    )
+}
+
+/**
+ * 处理所有参与方的刷新消息并计算新的主密钥份额
+ *
+ * 新份额为旧份额与所有零共享之和，验证密钥也相应更新，主公钥保持不变。
+ *
+ * 参数:
+ * @param key_share - 自己当前的主密钥份额
+ * @param secret_key - 自己用于接收份额的私钥
+ * @param parties - 所有参与方的公开信息
+ * @param messages - 所有参与方的刷新消息，每个参与方恰好一条
+ * @param epoch - 本轮刷新的轮次编号，轮次不同的消息被拒绝
+ *
+ * 返回:
+ * 刷新后的主密钥份额，如果某个分发者的消息无效则返回指明该分发者的错误
+ */
+pub fn refresh(
+    key_share: &KeyShare,
+    secret_key: &PartySecretKey,
+    parties: &[Party],
+    messages: &[ZeroSharingMessage],
+    epoch: u64,
+) -> FastCryptoResult<KeyShare> {
+    validate_committee(key_share, parties)?;
+    validate_messages(parties.iter().map(|p| p.index), 0, epoch, messages)?;
+    let delta = receive(
+        key_share.index,
+        secret_key,
+        key_share.threshold,
+        parties,
+        messages,
+    )?;
+
+    let public_shares = key_share
+        .public_shares
+        .iter()
+        .map(|(j, pk)| (*j, pk + evaluate_zero_sharings(messages, *j)))
+        .collect();
+    Ok(KeyShare {
+        index: key_share.index,
+        threshold: key_share.threshold,
+        share: Secret::new(*key_share.share.expose() + delta.expose()),
+        public_key: key_share.public_key,
+        public_shares,
+    })
+}
+
+/**
+ * 生成恢复用的零共享消息
+ *
+ * 每个帮助者都需要调用一次，消息只发给其他帮助者。
+ *
+ * 参数:
+ * @param rng - 随机数生成器
+ * @param key_share - 自己的主密钥份额
+ * @param secret_key - 自己的参与方私钥，用于签名消息
+ * @param helpers - 所有帮助者（包括自己）的公开信息，数量不少于阈值
+ * @param target - 要恢复的份额索引
+ * @param epoch - 本轮恢复的轮次编号
+ *
+ * 返回:
+ * 要广播给所有帮助者的消息
+ */
+pub fn deal_recovery<R: AllowedRng>(
+    rng: &mut R,
+    key_share: &KeyShare,
+    secret_key: &PartySecretKey,
+    helpers: &[Party],
+    target: u8,
+    epoch: u64,
+) -> FastCryptoResult<ZeroSharingMessage> {
+    validate_helpers(key_share, helpers, target)?;
+    deal_zero_sharing(
+        rng,
+        secret_key,
+        epoch,
+        key_share.index,
+        key_share.threshold,
+        target,
+        helpers,
+    )
+}
+
+/**
+ * 处理恢复用的零共享消息，生成发给新机器的掩码份额
+ *
+ * 参数:
+ * @param rng - 随机数生成器
+ * @param key_share - 自己的主密钥份额
+ * @param secret_key - 自己用于接收份额的私钥
+ * @param helpers - 所有帮助者的公开信息
+ * @param messages - 所有帮助者的恢复消息，每个帮助者恰好一条
+ * @param target - 接管要恢复的索引的新机器的公开信息
+ * @param epoch - 本轮恢复的轮次编号，轮次不同的消息被拒绝
+ *
+ * 返回:
+ * 加密给新机器的掩码份额
+ */
+pub fn recovery_share<R: AllowedRng>(
+    rng: &mut R,
+    key_share: &KeyShare,
+    secret_key: &PartySecretKey,
+    helpers: &[Party],
+    messages: &[ZeroSharingMessage],
+    target: &Party,
+    epoch: u64,
+) -> FastCryptoResult<RecoveryShare> {
+    validate_helpers(key_share, helpers, target.index)?;
+    validate_messages(
+        helpers.iter().map(|p| p.index),
+        target.index,
+        epoch,
+        messages,
+    )?;
+    let delta = receive(
+        key_share.index,
+        secret_key,
+        key_share.threshold,
+        helpers,
+        messages,
+    )?;
+    let masked = Secret::new(*key_share.share.expose() + delta.expose());
+    Ok(RecoveryShare {
+        helper: key_share.index,
+        encrypted_share: encrypt_share(rng, key_share.index, target, &masked),
+    })
+}
+
+/**
+ * 新机器从帮助者的掩码份额恢复主密钥份额
+ *
+ * 每条恢复消息都根据帮助者的公钥验证签名，每个掩码份额都根据帮助者的验证密钥和零共享承诺进行验证，
+ * 插值结果还要与该索引已知的验证密钥一致。
+ *
+ * 参数:
+ * @param secret_key - 新机器用于接收份额的私钥
+ * @param threshold - 重构主密钥所需的最小份额数量
+ * @param public_key - 主公钥
+ * @param public_shares - 所有参与方份额的验证密钥
+ * @param helpers - 所有帮助者的公开信息
+ * @param messages - 所有帮助者的恢复消息
+ * @param recovery_shares - 所有帮助者发来的掩码份额
+ * @param epoch - 本轮恢复的轮次编号，轮次不同的消息被拒绝
+ *
+ * 返回:
+ * 恢复的主密钥份额，索引为恢复消息中的根点
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn recover(
+    secret_key: &PartySecretKey,
+    threshold: u8,
+    public_key: &ibe::PublicKey,
+    public_shares: &[(u8, ibe::PublicKey)],
+    helpers: &[Party],
+    messages: &[ZeroSharingMessage],
+    recovery_shares: &[RecoveryShare],
+    epoch: u64,
+) -> FastCryptoResult<KeyShare> {
+    let target = messages.first().ok_or(InvalidInput)?.root;
+    let indices = recovery_shares.iter().map(|s| s.helper).collect_vec();
+    validate_parties(threshold, helpers)?;
+    if !public_shares.iter().any(|(j, _)| *j == target)
+        || !indices
+            .iter()
+            .copied()
+            .sorted()
+            .eq(helpers.iter().map(|p| p.index).sorted())
+        || recovery_shares
+            .iter()
+            .any(|s| s.encrypted_share.recipient != target)
+    {
+        return Err(InvalidInput);
+    }
+    validate_messages(helpers.iter().map(|p| p.index), target, epoch, messages)?;
+    verify_signatures(helpers, messages)?;
+
+    let masked_shares = recovery_shares
+        .iter()
+        .map(|s| {
+            let invalid_helper =
+                || GeneralError(format!("Invalid recovery share from helper {}", s.helper));
+            let masked = decrypt_share(s.helper, secret_key, &s.encrypted_share)
+                .map_err(|_| invalid_helper())?;
+            let expected =
+                public_share(public_shares, s.helper)? + evaluate_zero_sharings(messages, s.helper);
+            if G2Element::generator() * masked.expose() != expected {
+                return Err(invalid_helper());
+            }
+            Ok(masked)
+        })
+        .collect::<FastCryptoResult<Vec<_>>>()?;
+
+    let share = Secret::new(
+        lagrange_coefficients_at(target, &indices)?
+            .iter()
+            .zip(&masked_shares)
+            .fold(Scalar::zero(), |acc, (l, m)| acc + *l * m.expose()),
+    );
+    if G2Element::generator() * share.expose() != public_share(public_shares, target)? {
+        return Err(GeneralError(
+            "Recovered share does not match its verification key".to_string(),
+        ));
+    }
+    Ok(KeyShare {
+        index: target,
+        threshold,
+        share,
+        public_key: *public_key,
+        public_shares: public_shares.to_vec(),
+    })
+}
+
+/**
+ * 在本地模拟一轮完整的刷新
+ *
+ * 所有参与方在同一进程中运行，用于测试和演示。
+ *
+ * 参数:
+ * @param rng - 随机数生成器
+ * @param key_shares - 所有参与方当前的主密钥份额
+ * @param epoch - 本轮刷新的轮次编号
+ *
+ * 返回:
+ * 每个参与方刷新后的主密钥份额
+ */
+pub fn simulate<R: AllowedRng>(
+    rng: &mut R,
+    key_shares: &[KeyShare],
+    epoch: u64,
+) -> FastCryptoResult<Vec<KeyShare>> {
+    let (secret_keys, parties) = generate_party_keys(rng, key_shares)?;
+    let messages = key_shares
+        .iter()
+        .zip(&secret_keys)
+        .map(|(s, sk)| deal_refresh(rng, s, sk, &parties, epoch))
+        .collect::<FastCryptoResult<Vec<_>>>()?;
+    key_shares
+        .iter()
+        .zip(&secret_keys)
+        .map(|(s, sk)| refresh(s, sk, &parties, &messages, epoch))
+        .collect()
+}
+
+/// 为每个份额的持有者生成用于接收份额的密钥对
+fn generate_party_keys<R: AllowedRng>(
+    rng: &mut R,
+    key_shares: &[KeyShare],
+) -> FastCryptoResult<(Vec<PartySecretKey>, Vec<Party>)> {
+    Ok(key_shares
+        .iter()
+        .map(|s| generate_party_key(rng, s.index))
+        .collect::<FastCryptoResult<Vec<_>>>()?
+        .into_iter()
+        .unzip())
+}
+
+/// 生成在`root`处取值为零、度为`threshold - 1`的随机多项式，为每个接收方加密其取值，并签名消息
+fn deal_zero_sharing<R: AllowedRng>(
+    rng: &mut R,
+    secret_key: &PartySecretKey,
+    epoch: u64,
+    dealer: u8,
+    threshold: u8,
+    root: u8,
+    recipients: &[Party],
+) -> FastCryptoResult<ZeroSharingMessage> {
+    let public_key = G1Element::generator() * secret_key.expose();
+    if !recipients
+        .iter()
+        .any(|p| p.index == dealer && p.public_key == public_key)
+    {
+        return Err(InvalidInput);
+    }
+
+    let coefficients = repeat_with(|| Secret::new(Scalar::rand(rng)))
+        .take(threshold as usize - 1)
+        .collect_vec();
+    let commitments = coefficients
+        .iter()
+        .map(|q| G2Element::generator() * q.expose())
+        .collect_vec();
+    let encrypted_shares = recipients
+        .iter()
+        .map(|party| {
+            let value =
+                Secret::new(evaluate(&coefficients, party.index) * offset(party.index, root));
+            encrypt_share(rng, dealer, party, &value)
+        })
+        .collect_vec();
+    let signature = G2Element::hash_to_group_element(&signing_message(
+        recipients,
+        epoch,
+        dealer,
+        root,
+        &commitments,
+        &encrypted_shares,
+    )) * secret_key.expose();
+    Ok(ZeroSharingMessage {
+        epoch,
+        dealer,
+        root,
+        commitments,
+        encrypted_shares,
+        signature,
+    })
+}
+
+/// 验证所有零共享消息的签名并解密发给自己的值，根据承诺验证后返回它们的和
+fn receive(
+    index: u8,
+    secret_key: &PartySecretKey,
+    threshold: u8,
+    recipients: &[Party],
+    messages: &[ZeroSharingMessage],
+) -> FastCryptoResult<Secret<Scalar>> {
+    let me = recipients
+        .iter()
+        .find(|p| p.index == index)
+        .ok_or(InvalidInput)?;
+    if me.public_key != G1Element::generator() * secret_key.expose() {
+        return Err(InvalidInput);
+    }
+    verify_signatures(recipients, messages)?;
+
+    let mut sum = Secret::new(Scalar::zero());
+    for message in messages {
+        let invalid_dealer =
+            || GeneralError(format!("Invalid message from dealer {}", message.dealer));
+        if message.commitments.len() != threshold as usize - 1
+            || message.encrypted_shares.len() != recipients.len()
+        {
+            return Err(invalid_dealer());
+        }
+        let encrypted_share = message
+            .encrypted_shares
+            .iter()
+            .find(|s| s.recipient == index)
+            .ok_or_else(invalid_dealer)?;
+        let received = decrypt_share(message.dealer, secret_key, encrypted_share)
+            .map_err(|_| invalid_dealer())?;
+        if G2Element::generator() * received.expose() != evaluate_zero_sharing(message, index) {
+            return Err(invalid_dealer());
+        }
+        sum = Secret::new(*sum.expose() + received.expose());
+    }
+    Ok(sum)
+}
+
+/// 分发者签名的消息：域分隔标签、按索引排序的接收方列表和轮次编号（使签名只在本轮刷新或恢复中有效），
+/// 以及消息中除签名以外的其他字段
+fn signing_message(
+    recipients: &[Party],
+    epoch: u64,
+    dealer: u8,
+    root: u8,
+    commitments: &[G2Element],
+    encrypted_shares: &[EncryptedShare],
+) -> Vec<u8> {
+    let recipients = recipients.iter().sorted_by_key(|p| p.index).collect_vec();
+    let mut message = DST_REFRESH_MESSAGE.to_vec();
+    message.extend(
+        bcs::to_bytes(&(
+            recipients,
+            epoch,
+            dealer,
+            root,
+            commitments,
+            encrypted_shares,
+        ))
+        .expect("Never fails"),
+    );
+    message
+}
+
+/// 验证每条消息的分发者签名，即 e(g1, signature) = e(public_key, H(message))，
+/// 调用者需要先检查每个分发者都是接收方
+fn verify_signatures(
+    recipients: &[Party],
+    messages: &[ZeroSharingMessage],
+) -> FastCryptoResult<()> {
+    for message in messages {
+        let dealer = recipients
+            .iter()
+            .find(|p| p.index == message.dealer)
+            .ok_or(InvalidInput)?;
+        let hash = G2Element::hash_to_group_element(&signing_message(
+            recipients,
+            message.epoch,
+            message.dealer,
+            message.root,
+            &message.commitments,
+            &message.encrypted_shares,
+        ));
+        if G1Element::generator().pairing(&message.signature) != dealer.public_key.pairing(&hash) {
+            return Err(GeneralError(format!(
+                "Invalid message from dealer {}",
+                message.dealer
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// 在群的指数上计算零共享多项式在点x处的值，即 (x - root) · Σ C_k · x^k
+fn evaluate_zero_sharing(message: &ZeroSharingMessage, x: u8) -> G2Element {
+    evaluate_commitments(&message.commitments, x) * offset(x, message.root)
+}
+
+/// 所有零共享在点x处的值之和
+fn evaluate_zero_sharings(messages: &[ZeroSharingMessage], x: u8) -> G2Element {
+    messages.iter().fold(G2Element::zero(), |acc, m| {
+        acc + evaluate_zero_sharing(m, x)
+    })
+}
+
+/// 计算 x - root
+fn offset(x: u8, root: u8) -> Scalar {
+    Scalar::from(x as u128) - Scalar::from(root as u128)
+}
+
+/// 查找指定索引的验证密钥
+fn public_share(
+    public_shares: &[(u8, ibe::PublicKey)],
+    index: u8,
+) -> FastCryptoResult<ibe::PublicKey> {
+    public_shares
+        .iter()
+        .find(|(j, _)| *j == index)
+        .map(|(_, pk)| *pk)
+        .ok_or(InvalidInput)
+}
+
+/// 检查参与方列表与份额的验证密钥覆盖相同的索引
+fn validate_committee(key_share: &KeyShare, parties: &[Party]) -> FastCryptoResult<()> {
+    validate_parties(key_share.threshold, parties)?;
+    if !parties.iter().map(|p| p.index).sorted().eq(key_share
+        .public_shares
+        .iter()
+        .map(|(j, _)| *j)
+        .sorted())
+    {
+        return Err(InvalidInput);
+    }
+    Ok(())
+}
+
+/// 检查帮助者列表包含自己、不包含要恢复的索引，并且要恢复的索引属于委员会
+fn validate_helpers(key_share: &KeyShare, helpers: &[Party], target: u8) -> FastCryptoResult<()> {
+    validate_parties(key_share.threshold, helpers)?;
+    if !helpers.iter().any(|p| p.index == key_share.index)
+        || helpers.iter().any(|p| p.index == target)
+        || target == 0
+        || helpers
+            .iter()
+            .any(|p| public_share(&key_share.public_shares, p.index).is_err())
+    {
+        return Err(InvalidInput);
+    }
+    public_share(&key_share.public_shares, target).map(|_| ())
+}
+
+/// 检查每个分发者恰好有一条消息，并且所有消息使用期望的根点和轮次
+fn validate_messages(
+    dealers: impl Iterator<Item = u8>,
+    root: u8,
+    epoch: u64,
+    messages: &[ZeroSharingMessage],
+) -> FastCryptoResult<()> {
+    if messages.iter().any(|m| m.root != root || m.epoch != epoch)
+        || !messages
+            .iter()
+            .map(|m| m.dealer)
+            .sorted()
+            .eq(dealers.sorted())
+    {
+        return Err(InvalidInput);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dkg::lagrange_coefficients;
+    use rand::thread_rng;
+
+    /// 从足够数量的份额重构主密钥，仅用于测试
+    fn reconstruct(shares: &[&KeyShare]) -> Scalar {
+        let indices = shares.iter().map(|s| s.index).collect_vec();
+        lagrange_coefficients(&indices)
+            .unwrap()
+            .iter()
+            .zip(shares)
+            .fold(Scalar::zero(), |acc, (l, s)| acc + *l * s.share.expose())
+    }
+
+    /// 测试刷新
+    /// 1. 主公钥不变，所有份额都发生变化并与新的验证密钥一致
+    /// 2. 新份额重构出相同的主密钥，新旧份额混合则不能
+    #[test]
+    fn test_refresh() {
+        let mut rng = thread_rng();
+        let shares = crate::dkg::simulate(&mut rng, 3, 5).unwrap();
+        let master_key = reconstruct(&[&shares[0], &shares[1], &shares[2]]);
+
+        let refreshed = simulate(&mut rng, &shares, 1).unwrap();
+        for (old, new) in shares.iter().zip(&refreshed) {
+            assert_eq!(new.index, old.index);
+            assert_eq!(new.public_key, old.public_key);
+            assert_ne!(new.share.expose(), old.share.expose());
+            assert_eq!(new.public_shares, refreshed[0].public_shares);
+            assert_eq!(
+                public_share(&new.public_shares, new.index).unwrap(),
+                ibe::public_key_from_master_key(&new.share)
+            );
+        }
+        assert_eq!(
+            reconstruct(&[&refreshed[4], &refreshed[1], &refreshed[3]]),
+            master_key
+        );
+        assert_ne!(
+            reconstruct(&[&shares[0], &refreshed[1], &refreshed[2]]),
+            master_key
+        );
+    }
+
+    /// 测试恢复
+    /// 1. 三个帮助者为接管索引2的新机器恢复出与原份额相同的份额
+    /// 2. 被篡改的掩码份额被拒绝，并且错误指明了出错的帮助者
+    #[test]
+    fn test_recovery() {
+        let mut rng = thread_rng();
+        let shares = crate::dkg::simulate(&mut rng, 3, 5).unwrap();
+        let helper_shares = [&shares[0], &shares[2], &shares[4]];
+        let (secret_keys, helpers): (Vec<_>, Vec<_>) = helper_shares
+            .iter()
+            .map(|s| generate_party_key(&mut rng, s.index).unwrap())
+            .unzip();
+        let (target_key, target) = generate_party_key(&mut rng, 2).unwrap();
+
+        // 帮助者不能包含要恢复的索引
+        assert!(deal_recovery(&mut rng, &shares[1], &secret_keys[0], &helpers, 2, 1).is_err());
+
+        let mut messages = helper_shares
+            .iter()
+            .zip(&secret_keys)
+            .map(|(s, sk)| deal_recovery(&mut rng, s, sk, &helpers, 2, 1).unwrap())
+            .collect_vec();
+        let mut recovery_shares = helper_shares
+            .iter()
+            .zip(&secret_keys)
+            .map(|(s, sk)| {
+                recovery_share(&mut rng, s, sk, &helpers, &messages, &target, 1).unwrap()
+            })
+            .collect_vec();
+
+        let recovered = recover(
+            &target_key,
+            3,
+            &shares[0].public_key,
+            &shares[0].public_shares,
+            &helpers,
+            &messages,
+            &recovery_shares,
+            1,
+        )
+        .unwrap();
+        assert_eq!(recovered.index, 2);
+        assert_eq!(recovered.share.expose(), shares[1].share.expose());
+
+        recovery_shares[1].encrypted_share.ciphertext[31] ^= 1;
+        match recover(
+            &target_key,
+            3,
+            &shares[0].public_key,
+            &shares[0].public_shares,
+            &helpers,
+            &messages,
+            &recovery_shares,
+            1,
+        ) {
+            Err(GeneralError(e)) => assert_eq!(e, "Invalid recovery share from helper 3"),
+            _ => panic!(),
+        }
+
+        // 冒充帮助者5的恢复消息被帮助者和新机器拒绝
+        let (forger, _) = generate_party_key(&mut rng, 5).unwrap();
+        sign(&mut messages[2], &helpers, &forger);
+        assert_invalid_dealer(
+            recovery_share(
+                &mut rng,
+                helper_shares[0],
+                &secret_keys[0],
+                &helpers,
+                &messages,
+                &target,
+                1,
+            ),
+            5,
+        );
+        assert_invalid_dealer(
+            recover(
+                &target_key,
+                3,
+                &shares[0].public_key,
+                &shares[0].public_shares,
+                &helpers,
+                &messages,
+                &recovery_shares,
+                1,
+            ),
+            5,
+        );
+    }
+
+    /// 使用给定的私钥重新签名消息，用于模拟恶意或冒充的分发者
+    fn sign(message: &mut ZeroSharingMessage, recipients: &[Party], secret_key: &PartySecretKey) {
+        message.signature = G2Element::hash_to_group_element(&signing_message(
+            recipients,
+            message.epoch,
+            message.dealer,
+            message.root,
+            &message.commitments,
+            &message.encrypted_shares,
+        )) * secret_key.expose();
+    }
+
+    fn assert_invalid_dealer<T>(result: FastCryptoResult<T>, dealer: u8) {
+        match result {
+            Err(GeneralError(e)) => {
+                assert_eq!(e, format!("Invalid message from dealer {}", dealer))
+            }
+            _ => panic!(),
+        }
+    }
+
+    /// 测试伪造、篡改和未签名的刷新消息被拒绝，并且错误指明了出错的分发者
+    #[test]
+    fn test_invalid_refresh_messages() {
+        let mut rng = thread_rng();
+        let shares = crate::dkg::simulate(&mut rng, 2, 3).unwrap();
+        let (secret_keys, parties) = generate_party_keys(&mut rng, &shares).unwrap();
+        let messages = shares
+            .iter()
+            .zip(&secret_keys)
+            .map(|(s, sk)| deal_refresh(&mut rng, s, sk, &parties, 1).unwrap())
+            .collect_vec();
+        assert!(refresh(&shares[0], &secret_keys[0], &parties, &messages, 1).is_ok());
+
+        // 分发者只能使用自己的私钥签名
+        assert!(deal_refresh(&mut rng, &shares[0], &secret_keys[1], &parties, 1).is_err());
+
+        // 攻击者把所有零共享替换为自己的零共享，无法为其他分发者签名
+        let (forger_keys, forger_parties) = generate_party_keys(&mut rng, &shares).unwrap();
+        let forged = shares
+            .iter()
+            .zip(&forger_keys)
+            .map(|(s, sk)| {
+                let mut message = deal_refresh(&mut rng, s, sk, &forger_parties, 1).unwrap();
+                message.encrypted_shares = messages[0].encrypted_shares.clone();
+                sign(&mut message, &parties, sk);
+                message
+            })
+            .collect_vec();
+        assert_invalid_dealer(
+            refresh(&shares[0], &secret_keys[0], &parties, &forged, 1),
+            1,
+        );
+
+        // 分发者2的承诺在传输中被篡改
+        let mut tampered = messages.clone();
+        tampered[1].commitments[0] += G2Element::generator();
+        assert_invalid_dealer(
+            refresh(&shares[0], &secret_keys[0], &parties, &tampered, 1),
+            2,
+        );
+
+        // 分发者3的消息没有签名
+        let mut unsigned = messages.clone();
+        unsigned[2].signature = G2Element::zero();
+        assert_invalid_dealer(
+            refresh(&shares[0], &secret_keys[0], &parties, &unsigned, 1),
+            3,
+        );
+
+        // 参与方的顺序不影响签名
+        let reversed = parties.iter().rev().cloned().collect_vec();
+        assert!(refresh(&shares[0], &secret_keys[0], &reversed, &messages, 1).is_ok());
+    }
+
+    /// 测试第1轮的刷新消息不能在第2轮中重放
+    /// 1. 保留原轮次编号的消息被拒绝
+    /// 2. 把轮次编号改为第2轮后签名不再有效，错误指明了出错的分发者
+    #[test]
+    fn test_replayed_refresh_messages() {
+        let mut rng = thread_rng();
+        let shares = crate::dkg::simulate(&mut rng, 2, 3).unwrap();
+        let (secret_keys, parties) = generate_party_keys(&mut rng, &shares).unwrap();
+        let round_1 = shares
+            .iter()
+            .zip(&secret_keys)
+            .map(|(s, sk)| deal_refresh(&mut rng, s, sk, &parties, 1).unwrap())
+            .collect_vec();
+        let refreshed = shares
+            .iter()
+            .zip(&secret_keys)
+            .map(|(s, sk)| refresh(s, sk, &parties, &round_1, 1).unwrap())
+            .collect_vec();
+
+        // 第2轮中分发者1重放了第1轮的消息
+        let mut round_2 = refreshed
+            .iter()
+            .zip(&secret_keys)
+            .map(|(s, sk)| deal_refresh(&mut rng, s, sk, &parties, 2).unwrap())
+            .collect_vec();
+        assert!(refresh(&refreshed[1], &secret_keys[1], &parties, &round_2, 2).is_ok());
+        round_2[0] = round_1[0].clone();
+        assert!(matches!(
+            refresh(&refreshed[1], &secret_keys[1], &parties, &round_2, 2),
+            Err(InvalidInput)
+        ));
+
+        // 只修改轮次编号无法通过签名验证
+        round_2[0].epoch = 2;
+        assert_invalid_dealer(
+            refresh(&refreshed[1], &secret_keys[1], &parties, &round_2, 2),
+            1,
+        );
+    }
+}
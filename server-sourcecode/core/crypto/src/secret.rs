@@ -0,0 +1,134 @@
+// Copyright (c), Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/**
+ * 敏感数据模块
+ *
+ * 提供`Secret`包装类型，用于主密钥、基础密钥和派生对称密钥等秘密数据：
+ * - 值存储在堆上，移动包装类型不会复制秘密数据，并且其内存地址在整个生命周期内保持不变，
+ *   因此可以使用`as_ptr`锁定所在的内存页，防止被交换到磁盘
+ * - 释放时使用`zeroize`将内存清零，防止秘密数据在使用后残留在内存中
+ * - `Debug`输出不包含秘密数据，避免通过日志泄露
+ *
+ * 注意：`expose`返回的引用被解引用复制后，副本不会被自动清零，调用者应尽量只使用引用。
+ * 需要在函数内部生成或派生秘密数据时，应使用`random`或`zeroed`创建包装类型后直接写入堆内存，
+ * 而不是先在栈上构造再移动进来。
+ */
+
+use fastcrypto::traits::AllowedRng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Debug, Formatter};
+use zeroize::{zeroize_flat_type, ZeroizeOnDrop};
+
+/// 在释放时清零并隐藏`Debug`输出的秘密值
+///
+/// `T`必须是不包含堆指针的平坦类型（例如字节数组、标量和群元素），这样才能安全地整体清零。
+pub struct Secret<T: Copy>(Box<T>);
+
+impl<T: Copy> Secret<T> {
+    /// 将值移动到受保护的堆内存中
+    pub fn new(value: T) -> Self {
+        Self(Box::new(value))
+    }
+
+    /// 获取秘密值的引用
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    /// 获取秘密值的可变引用，用于在堆内存中直接生成或派生秘密数据
+    pub(crate) fn expose_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+
+    /// 秘密值所在的内存地址，在整个生命周期内保持不变
+    pub fn as_ptr(&self) -> *const T {
+        self.expose()
+    }
+}
+
+impl<const N: usize> Secret<[u8; N]> {
+    /// 全零的秘密字节数组，之后通过`expose_mut`写入
+    pub(crate) fn zeroed() -> Self {
+        Self::new([0u8; N])
+    }
+
+    /// 直接在堆内存中生成随机字节，栈上不会留下副本
+    pub fn random<R: AllowedRng>(rng: &mut R) -> Self {
+        let mut secret = Self::zeroed();
+        rng.fill_bytes(secret.expose_mut());
+        secret
+    }
+}
+
+impl<T: Copy> Drop for Secret<T> {
+    fn drop(&mut self) {
+        // 安全性：T是Copy类型，因此没有析构函数，清零后的值也不会再被读取
+        unsafe { zeroize_flat_type(self.0.as_mut() as *mut T) }
+    }
+}
+
+impl<T: Copy> ZeroizeOnDrop for Secret<T> {}
+
+impl<T: Copy> Clone for Secret<T> {
+    fn clone(&self) -> Self {
+        Self::new(*self.expose())
+    }
+}
+
+impl<T: Copy> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Copy> Debug for Secret<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Secret(<redacted>)")
+    }
+}
+
+impl<T: Copy + Serialize> Serialize for Secret<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.expose().serialize(serializer)
+    }
+}
+
+impl<'de, T: Copy + Deserialize<'de>> Deserialize<'de> for Secret<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret() {
+        let secret = Secret::new([7u8; 32]);
+        assert_eq!(secret.expose(), &[7u8; 32]);
+        assert_eq!(format!("{:?}", secret), "Secret(<redacted>)");
+        assert_eq!(secret.as_ptr(), secret.expose() as *const _);
+
+        // 序列化与内部值一致
+        let bytes = bcs::to_bytes(&secret).unwrap();
+        assert_eq!(bytes, bcs::to_bytes(&[7u8; 32]).unwrap());
+        let parsed: Secret<[u8; 32]> = bcs::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed.expose(), secret.expose());
+
+        // 克隆使用独立的内存
+        let cloned = secret.clone();
+        assert_ne!(cloned.as_ptr(), secret.as_ptr());
+        assert_eq!(cloned.expose(), secret.expose());
+
+        // 随机生成的值互不相同
+        let mut rng = rand::thread_rng();
+        let random = Secret::<[u8; 32]>::random(&mut rng);
+        assert_ne!(random.expose(), &[0u8; 32]);
+        assert_ne!(
+            random.expose(),
+            Secret::<[u8; 32]>::random(&mut rng).expose()
+        );
+    }
+}
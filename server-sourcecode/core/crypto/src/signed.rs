@@ -0,0 +1,307 @@
+// Copyright (c), Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/**
+ * 发送者认证模块
+ *
+ * 任何知道密钥服务器公钥的人都可以为任意`package_id`和`id`生成加密对象，
+ * 因此接收者无法判断内容是谁创建的。本模块提供一个可选的签名封装：
+ * 发送者对加密对象的BCS编码签名，接收者在解密之前验证签名并得到发送者的身份。
+ *
+ * 支持的签名方案:
+ * 1. Ed25519
+ * 2. secp256k1（对消息的SHA-256哈希签名）
+ * 3. Sui签名：序列化的`GenericSignature`（flag || 签名 || 公钥），签名对象为Sui个人消息，
+ *    因此可以直接由Sui钱包签名，发送者的身份为Sui地址。目前支持Ed25519、secp256k1和secp256r1
+ *    的简单签名，不支持多签和zkLogin签名
+ *
+ * 所有方案签名的消息都是域分隔标签加上加密对象的BCS编码，参见`SignedEncryptedObject::message`。
+ */
+
+use crate::EncryptedObject;
+use fastcrypto::ed25519::{Ed25519KeyPair, Ed25519PublicKey, Ed25519Signature};
+use fastcrypto::error::FastCryptoError::{GeneralError, InvalidInput};
+use fastcrypto::error::FastCryptoResult;
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use fastcrypto::secp256k1::{Secp256k1KeyPair, Secp256k1PublicKey, Secp256k1Signature};
+use fastcrypto::secp256r1::{Secp256r1PublicKey, Secp256r1Signature};
+use fastcrypto::traits::{KeyPair, Signer, ToFromBytes, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+/// 签名消息的域分隔标签
+const DST_SIGNED_OBJECT: &[u8] = b"SUI-SEAL-SIGNED-OBJECT-00";
+
+/// Sui签名方案的标志
+const SUI_FLAG_ED25519: u8 = 0x00;
+const SUI_FLAG_SECP256K1: u8 = 0x01;
+const SUI_FLAG_SECP256R1: u8 = 0x02;
+
+/// Sui简单签名中签名部分的长度，三种方案都是64字节
+const SUI_SIGNATURE_LENGTH: usize = 64;
+
+/// Sui地址，即签名方案标志和公钥的Blake2b-256哈希
+pub type SuiAddress = [u8; 32];
+
+/// 发送者对加密对象的签名
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SenderSignature {
+    /// Ed25519签名及签名者的公钥
+    Ed25519 {
+        public_key: Ed25519PublicKey,
+        signature: Ed25519Signature,
+    },
+    /// secp256k1签名及签名者的公钥
+    Secp256k1 {
+        public_key: Secp256k1PublicKey,
+        signature: Secp256k1Signature,
+    },
+    /// 序列化的Sui `GenericSignature`，对个人消息签名
+    Sui(Vec<u8>),
+}
+
+/// 签名验证后得到的发送者身份
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Sender {
+    Ed25519(Ed25519PublicKey),
+    Secp256k1(Secp256k1PublicKey),
+    Sui(SuiAddress),
+}
+
+/// 用于签名加密对象的密钥
+pub enum SigningKey<'a> {
+    /// 生成Ed25519签名
+    Ed25519(&'a Ed25519KeyPair),
+    /// 生成secp256k1签名
+    Secp256k1(&'a Secp256k1KeyPair),
+    /// 使用Ed25519密钥生成Sui个人消息签名
+    SuiEd25519(&'a Ed25519KeyPair),
+    /// 使用secp256k1密钥生成Sui个人消息签名
+    SuiSecp256k1(&'a Secp256k1KeyPair),
+}
+
+/// 带有发送者签名的加密对象
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedEncryptedObject {
+    /// 被签名的加密对象
+    pub encrypted_object: EncryptedObject,
+    /// 发送者对`SignedEncryptedObject::message`的签名
+    pub signature: SenderSignature,
+}
+
+impl SignedEncryptedObject {
+    /**
+     * 返回需要签名的消息：域分隔标签加上加密对象的BCS编码。
+     * 使用Sui钱包签名时，将这个消息作为个人消息签名。
+     *
+     * 参数:
+     * @param encrypted_object - 要签名的加密对象
+     *
+     * 返回:
+     * 需要签名的消息
+     */
+    pub fn message(encrypted_object: &EncryptedObject) -> FastCryptoResult<Vec<u8>> {
+        let mut message = DST_SIGNED_OBJECT.to_vec();
+        message.extend(encrypted_object.to_bytes()?);
+        Ok(message)
+    }
+
+    /**
+     * 使用给定的密钥签名加密对象
+     *
+     * 参数:
+     * @param encrypted_object - 要签名的加密对象
+     * @param key - 发送者的签名密钥
+     *
+     * 返回:
+     * 带有签名的加密对象
+     */
+    pub fn sign(encrypted_object: EncryptedObject, key: &SigningKey) -> FastCryptoResult<Self> {
+        let message = Self::message(&encrypted_object)?;
+        let signature = match key {
+            SigningKey::Ed25519(key_pair) => SenderSignature::Ed25519 {
+                public_key: key_pair.public().clone(),
+                signature: key_pair.sign(&message),
+            },
+            SigningKey::Secp256k1(key_pair) => SenderSignature::Secp256k1 {
+                public_key: key_pair.public().clone(),
+                signature: key_pair.sign(&message),
+            },
+            SigningKey::SuiEd25519(key_pair) => SenderSignature::Sui(sui_signature(
+                SUI_FLAG_ED25519,
+                key_pair.sign(&personal_message_digest(&message)).as_ref(),
+                key_pair.public().as_ref(),
+            )),
+            SigningKey::SuiSecp256k1(key_pair) => SenderSignature::Sui(sui_signature(
+                SUI_FLAG_SECP256K1,
+                key_pair.sign(&personal_message_digest(&message)).as_ref(),
+                key_pair.public().as_ref(),
+            )),
+        };
+        Ok(Self {
+            encrypted_object,
+            signature,
+        })
+    }
+
+    /**
+     * 验证签名并返回发送者的身份。调用者需要检查发送者是否是预期的发送者。
+     *
+     * 返回:
+     * 签名有效时返回发送者的身份，否则返回错误
+     */
+    pub fn verify(&self) -> FastCryptoResult<Sender> {
+        let message = Self::message(&self.encrypted_object)?;
+        match &self.signature {
+            SenderSignature::Ed25519 {
+                public_key,
+                signature,
+            } => public_key
+                .verify(&message, signature)
+                .map(|()| Sender::Ed25519(public_key.clone())),
+            SenderSignature::Secp256k1 {
+                public_key,
+                signature,
+            } => public_key
+                .verify(&message, signature)
+                .map(|()| Sender::Secp256k1(public_key.clone())),
+            SenderSignature::Sui(signature) => {
+                verify_sui_signature(signature, &message).map(Sender::Sui)
+            }
+        }
+    }
+}
+
+/**
+ * 计算Sui地址
+ *
+ * 参数:
+ * @param flag - 签名方案的标志
+ * @param public_key - 公钥的字节
+ *
+ * 返回:
+ * Sui地址
+ */
+pub fn sui_address(flag: u8, public_key: &[u8]) -> SuiAddress {
+    let mut hash = Blake2b256::new();
+    hash.update([flag]);
+    hash.update(public_key);
+    hash.finalize().digest
+}
+
+/// Sui个人消息的摘要：IntentMessage { intent: (PersonalMessage, V0, Sui), value: message }
+/// 的BCS编码的Blake2b-256哈希
+fn personal_message_digest(message: &[u8]) -> [u8; 32] {
+    let mut hash = Blake2b256::new();
+    hash.update([3, 0, 0]);
+    hash.update(bcs::to_bytes(message).expect("Never fails"));
+    hash.finalize().digest
+}
+
+/// 将Sui简单签名序列化为flag || 签名 || 公钥
+fn sui_signature(flag: u8, signature: &[u8], public_key: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![flag];
+    bytes.extend_from_slice(signature);
+    bytes.extend_from_slice(public_key);
+    bytes
+}
+
+/// 验证Sui简单签名，返回签名者的Sui地址
+fn verify_sui_signature(signature: &[u8], message: &[u8]) -> FastCryptoResult<SuiAddress> {
+    let (flag, rest) = signature.split_first().ok_or(InvalidInput)?;
+    if rest.len() <= SUI_SIGNATURE_LENGTH {
+        return Err(InvalidInput);
+    }
+    let (signature, public_key) = rest.split_at(SUI_SIGNATURE_LENGTH);
+    let digest = personal_message_digest(message);
+    match *flag {
+        SUI_FLAG_ED25519 => Ed25519PublicKey::from_bytes(public_key)?
+            .verify(&digest, &Ed25519Signature::from_bytes(signature)?)?,
+        SUI_FLAG_SECP256K1 => Secp256k1PublicKey::from_bytes(public_key)?
+            .verify(&digest, &Secp256k1Signature::from_bytes(signature)?)?,
+        SUI_FLAG_SECP256R1 => Secp256r1PublicKey::from_bytes(public_key)?
+            .verify(&digest, &Secp256r1Signature::from_bytes(signature)?)?,
+        _ => {
+            return Err(GeneralError(format!(
+                "Unsupported Sui signature scheme {}",
+                flag
+            )))
+        }
+    }
+    Ok(sui_address(*flag, public_key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ibe;
+    use crate::{seal_encrypt, EncryptionInput, IBEPublicKeys, ObjectID};
+    use rand::thread_rng;
+
+    /// 测试所有签名方案：签名可以被验证并得到正确的发送者，修改加密对象或签名后验证失败
+    #[test]
+    fn test_sign_and_verify() {
+        let mut rng = thread_rng();
+        let (_, pk) = ibe::generate_key_pair(&mut rng);
+        let (encrypted_object, _) = seal_encrypt(
+            ObjectID::random(),
+            vec![1, 2, 3],
+            vec![ObjectID::random()],
+            &IBEPublicKeys::BonehFranklinBLS12381(vec![pk]),
+            1,
+            EncryptionInput::Plain,
+        )
+        .unwrap();
+
+        let ed25519 = Ed25519KeyPair::generate(&mut rng);
+        let secp256k1 = Secp256k1KeyPair::generate(&mut rng);
+        let cases = [
+            (
+                SigningKey::Ed25519(&ed25519),
+                Sender::Ed25519(ed25519.public().clone()),
+            ),
+            (
+                SigningKey::Secp256k1(&secp256k1),
+                Sender::Secp256k1(secp256k1.public().clone()),
+            ),
+            (
+                SigningKey::SuiEd25519(&ed25519),
+                Sender::Sui(sui_address(SUI_FLAG_ED25519, ed25519.public().as_ref())),
+            ),
+            (
+                SigningKey::SuiSecp256k1(&secp256k1),
+                Sender::Sui(sui_address(SUI_FLAG_SECP256K1, secp256k1.public().as_ref())),
+            ),
+        ];
+        for (key, sender) in cases {
+            let signed = SignedEncryptedObject::sign(encrypted_object.clone(), &key).unwrap();
+            assert_eq!(signed.verify().unwrap(), sender);
+
+            // BCS编码后仍然可以验证
+            let decoded: SignedEncryptedObject =
+                bcs::from_bytes(&bcs::to_bytes(&signed).unwrap()).unwrap();
+            assert_eq!(decoded.verify().unwrap(), sender);
+
+            let mut modified = signed.clone();
+            modified.encrypted_object.id = vec![4, 5, 6];
+            assert!(modified.verify().is_err());
+        }
+
+        // 不支持的Sui签名方案
+        let mut signed =
+            SignedEncryptedObject::sign(encrypted_object, &SigningKey::SuiEd25519(&ed25519))
+                .unwrap();
+        if let SenderSignature::Sui(signature) = &mut signed.signature {
+            signature[0] = 0x05;
+        }
+        assert!(signed.verify().is_err());
+    }
+
+    /// 测试Sui个人消息摘要与Sui实现一致：空消息的IntentMessage编码为[3, 0, 0, 0]
+    #[test]
+    fn test_personal_message_digest() {
+        assert_eq!(
+            personal_message_digest(b""),
+            Blake2b256::digest([3, 0, 0, 0]).digest
+        );
+    }
+}
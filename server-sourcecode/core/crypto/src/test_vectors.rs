@@ -0,0 +1,379 @@
+// Copyright (c), Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/**
+ * 可复现的测试向量生成模块
+ *
+ * 使用从种子初始化的`ChaCha20Rng`生成密钥服务器的主密钥、对象ID和加密时的所有随机性，
+ * 因此相同的种子总是生成完全相同的测试向量。`StdRng`的算法在不同的rand版本之间可能改变，
+ * 而ChaCha20的输出流是固定的，其他语言的实现也可以复现：
+ * 64位的种子按小端序写入32字节ChaCha20密钥的前8个字节，其余字节为0，
+ * nonce（流编号）为0，块计数器从0开始，每个64字节的块按32位小端序的字依次输出。
+ *
+ * 输出流到各个值的映射:
+ * - 字节数组（对象ID和内部ID）遵循rand 0.8的`Standard`分布，每个字节单独取一个32位字的最低字节，
+ *   即每个字节消耗输出流中的4个字节，例如种子0的包ID以 76 a0 40 53 开头
+ * - 标量（主密钥以及加密时的随机标量）遵循fastcrypto的`Scalar::rand`：取输出流中接下来的64个字节，
+ *   按大端序解释为整数后对BLS12-381的群阶r取模
+ *
+ * 因此包ID消耗输出流的前128个字节，内部ID消耗接下来的16个字节，
+ * 第一个主密钥为第144到207字节按上述方式得到的标量。
+ * 每种`Ciphertext`类型都会生成一个向量，其中包含解密所需的全部信息，用于与TypeScript SDK和Move实现进行一致性测试。
+ * `tests::test_golden_vector`固定了种子0的部分结果，依赖项改变输出流或序列化方式时该测试会失败。
+ *
+ * 所有二进制数据都使用Hex编码，群元素和标量使用BCS编码（即压缩形式）。
+ */
+
+use crate::{
+    create_full_id, ibe, seal_decrypt, seal_encrypt_with_rng, Ciphertext, EncryptedObject,
+    EncryptionInput, IBEPublicKeys, IBEUserSecretKeys, ObjectID, KEY_SIZE,
+};
+use fastcrypto::error::{FastCryptoError, FastCryptoResult};
+use fastcrypto::traits::AllowedRng;
+use rand::{CryptoRng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// 测试向量中使用的明文
+const PLAINTEXT: &[u8] = b"My super secret message";
+
+/// 测试向量中使用的额外认证数据
+const AAD: &[u8] = b"Seal test vector";
+
+/// 分块模式测试向量的块大小，小于明文长度以覆盖多个块
+const CHUNK_SIZE: u32 = 8;
+
+/// 密钥服务器的数量
+const NUMBER_OF_SERVERS: usize = 3;
+
+/// 解密阈值
+const THRESHOLD: u8 = 2;
+
+/// 生成测试向量使用的随机数生成器。fastcrypto只接受白名单中的生成器，因此包装`ChaCha20Rng`
+struct TestVectorRng(ChaCha20Rng);
+
+impl TestVectorRng {
+    /// 按照模块说明将64位种子映射为ChaCha20密钥
+    fn from_seed(seed: u64) -> Self {
+        let mut key = [0u8; 32];
+        key[..8].copy_from_slice(&seed.to_le_bytes());
+        Self(ChaCha20Rng::from_seed(key))
+    }
+}
+
+impl RngCore for TestVectorRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+impl CryptoRng for TestVectorRng {}
+
+impl AllowedRng for TestVectorRng {}
+
+/// 单个测试向量
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVector {
+    /// 测试向量的名称，对应`Ciphertext`的类型
+    pub name: String,
+    /// 包ID
+    pub package_id: String,
+    /// 内部ID（Hex编码）
+    pub id: String,
+    /// 密钥服务器的对象ID
+    pub services: Vec<String>,
+    /// 密钥服务器的主密钥（Hex编码）
+    pub master_keys: Vec<String>,
+    /// 密钥服务器的公钥（Hex编码）
+    pub public_keys: Vec<String>,
+    /// 解密阈值
+    pub threshold: u8,
+    /// 明文（Hex编码），在明文模式下为空
+    pub plaintext: String,
+    /// 额外认证数据（Hex编码）
+    pub aad: Option<String>,
+    /// BCS编码的加密对象（Hex编码）
+    pub encrypted_object: String,
+    /// 派生的对称密钥（Hex编码）
+    pub dem_key: String,
+}
+
+/**
+ * 从给定的种子生成覆盖所有`Ciphertext`类型的测试向量
+ *
+ * 参数:
+ * @param seed - 随机数生成器的种子，映射到ChaCha20密钥的方式见模块说明
+ *
+ * 返回:
+ * 每种`Ciphertext`类型一个测试向量
+ */
+pub fn generate(seed: u64) -> FastCryptoResult<Vec<TestVector>> {
+    let mut rng = TestVectorRng::from_seed(seed);
+
+    let package_id = ObjectID::new(rng.gen());
+    let id = rng.gen::<[u8; 4]>().to_vec();
+    let keys = (0..NUMBER_OF_SERVERS)
+        .map(|_| ibe::generate_key_pair(&mut rng))
+        .collect::<Vec<_>>();
+    let services = (0..NUMBER_OF_SERVERS)
+        .map(|_| ObjectID::new(rng.gen()))
+        .collect::<Vec<_>>();
+    let public_keys =
+        IBEPublicKeys::BonehFranklinBLS12381(keys.iter().map(|(_, pk)| *pk).collect());
+
+    let inputs = [
+        (
+            "Aes256Gcm",
+            EncryptionInput::Aes256Gcm {
+                data: PLAINTEXT.to_vec(),
+                aad: Some(AAD.to_vec()),
+            },
+        ),
+        (
+            "Hmac256Ctr",
+            EncryptionInput::Hmac256Ctr {
+                data: PLAINTEXT.to_vec(),
+                aad: Some(AAD.to_vec()),
+            },
+        ),
+        ("Plain", EncryptionInput::Plain),
+        (
+            "Aes256GcmChunked",
+            EncryptionInput::Aes256GcmChunked {
+                data: PLAINTEXT.to_vec(),
+                aad: Some(AAD.to_vec()),
+                chunk_size: CHUNK_SIZE,
+            },
+        ),
+        (
+            "ChaCha20Poly1305",
+            EncryptionInput::ChaCha20Poly1305 {
+                data: PLAINTEXT.to_vec(),
+                aad: Some(AAD.to_vec()),
+            },
+        ),
+        (
+            "Aes256GcmCommitting",
+            EncryptionInput::Aes256GcmCommitting {
+                data: PLAINTEXT.to_vec(),
+                aad: Some(AAD.to_vec()),
+            },
+        ),
+    ];
+
+    inputs
+        .into_iter()
+        .map(|(name, input)| {
+            let (plaintext, aad) = match &input {
+                EncryptionInput::Plain => (vec![], None),
+                _ => (PLAINTEXT.to_vec(), Some(hex::encode(AAD))),
+            };
+            let (encrypted_object, dem_key) = seal_encrypt_with_rng(
+                &mut rng,
+                package_id,
+                id.clone(),
+                services.clone(),
+                &public_keys,
+                THRESHOLD,
+                input,
+            )?;
+            Ok(TestVector {
+                name: name.to_string(),
+                package_id: package_id.to_string(),
+                id: hex::encode(&id),
+                services: services.iter().map(ToString::to_string).collect(),
+                master_keys: keys.iter().map(|(sk, _)| to_hex(sk)).collect(),
+                public_keys: keys.iter().map(|(_, pk)| to_hex(pk)).collect(),
+                threshold: THRESHOLD,
+                plaintext: hex::encode(plaintext),
+                aad,
+                encrypted_object: hex::encode(encrypted_object.to_bytes()?),
+                dem_key: hex::encode(dem_key.expose()),
+            })
+        })
+        .collect()
+}
+
+/**
+ * 从给定的种子生成测试向量，并编码为格式化的JSON
+ *
+ * 参数:
+ * @param seed - 随机数生成器的种子
+ *
+ * 返回:
+ * JSON格式的测试向量列表
+ */
+pub fn generate_json(seed: u64) -> FastCryptoResult<String> {
+    serde_json::to_string_pretty(&generate(seed)?)
+        .map_err(|e| FastCryptoError::GeneralError(e.to_string()))
+}
+
+/**
+ * 验证测试向量
+ *
+ * 使用向量中的主密钥提取用户私钥，解密加密对象，并检查明文（或明文模式下的派生密钥）
+ * 是否与向量中的值一致。可用于验证其他实现生成的测试向量。
+ *
+ * 参数:
+ * @param vector - 要验证的测试向量
+ *
+ * 返回:
+ * 如果向量有效则返回Ok，否则返回错误
+ */
+pub fn verify(vector: &TestVector) -> FastCryptoResult<()> {
+    let encrypted_object = EncryptedObject::from_bytes(&from_hex(&vector.encrypted_object)?)?;
+    let package_id =
+        ObjectID::from_str(&vector.package_id).map_err(|_| FastCryptoError::InvalidInput)?;
+    let full_id = create_full_id(&package_id, &from_hex(&vector.id)?);
+
+    let master_keys = vector
+        .master_keys
+        .iter()
+        .map(|sk| from_bcs_hex::<ibe::MasterKey>(sk))
+        .collect::<FastCryptoResult<Vec<_>>>()?;
+    let public_keys = vector
+        .public_keys
+        .iter()
+        .map(|pk| from_bcs_hex::<ibe::PublicKey>(pk))
+        .collect::<FastCryptoResult<Vec<_>>>()?;
+    let services = vector
+        .services
+        .iter()
+        .map(|s| ObjectID::from_str(s).map_err(|_| FastCryptoError::InvalidInput))
+        .collect::<FastCryptoResult<Vec<_>>>()?;
+    if master_keys.len() != services.len() {
+        return Err(FastCryptoError::InvalidInput);
+    }
+    let user_secret_keys = IBEUserSecretKeys::BonehFranklinBLS12381(
+        services
+            .into_iter()
+            .zip(&master_keys)
+            .map(|(s, sk)| (s, ibe::extract(sk, &full_id)))
+            .collect(),
+    );
+
+    let decrypted = seal_decrypt(
+        &encrypted_object,
+        &user_secret_keys,
+        Some(&IBEPublicKeys::BonehFranklinBLS12381(public_keys)),
+    )?;
+    let dem_key: [u8; KEY_SIZE] = from_hex(&vector.dem_key)?
+        .try_into()
+        .map_err(|_| FastCryptoError::InvalidInput)?;
+    let expected = match encrypted_object.ciphertext {
+        Ciphertext::Plain => dem_key.to_vec(),
+        _ => from_hex(&vector.plaintext)?,
+    };
+    if decrypted != expected {
+        return Err(FastCryptoError::GeneralError(format!(
+            "Test vector {} decrypts to an unexpected value",
+            vector.name
+        )));
+    }
+    Ok(())
+}
+
+/// 将可序列化的值编码为BCS字节的Hex字符串
+fn to_hex<T: Serialize>(value: &T) -> String {
+    hex::encode(bcs::to_bytes(value).expect("Never fails"))
+}
+
+/// 解码Hex字符串
+fn from_hex(value: &str) -> FastCryptoResult<Vec<u8>> {
+    hex::decode(value).map_err(|_| FastCryptoError::InvalidInput)
+}
+
+/// 解码BCS编码的Hex字符串
+fn from_bcs_hex<T: for<'a> Deserialize<'a>>(value: &str) -> FastCryptoResult<T> {
+    bcs::from_bytes(&from_hex(value)?).map_err(|_| FastCryptoError::InvalidInput)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试相同的种子生成相同的测试向量，并且所有向量都能被验证
+    #[test]
+    fn test_generate_and_verify() {
+        let vectors = generate(42).unwrap();
+        assert_eq!(vectors.len(), 6);
+        assert_eq!(vectors, generate(42).unwrap());
+        assert_ne!(vectors, generate(43).unwrap());
+        vectors.iter().for_each(|v| verify(v).unwrap());
+
+        // JSON编码可以被解析回相同的向量
+        let parsed: Vec<TestVector> = serde_json::from_str(&generate_json(42).unwrap()).unwrap();
+        assert_eq!(parsed, vectors);
+
+        // 篡改过的向量无法通过验证
+        let mut modified = vectors[0].clone();
+        modified.plaintext = hex::encode(b"Something else");
+        assert!(verify(&modified).is_err());
+        let mut modified = vectors[2].clone();
+        modified.dem_key = hex::encode([0u8; KEY_SIZE]);
+        assert!(verify(&modified).is_err());
+    }
+
+    /// 固定种子0生成的`Aes256Gcm`向量，防止依赖项的改变悄悄改变其他语言使用的测试向量
+    #[test]
+    fn test_golden_vector() {
+        let vector = &generate(0).unwrap()[0];
+        assert_eq!(vector.name, "Aes256Gcm");
+        assert_eq!(
+            vector.package_id,
+            "0x76a04053bda0a88bda5177b86a15c3b29f559873cb481232299cd5743151ac4b"
+        );
+        assert_eq!(vector.id, "2d63ae19");
+        assert_eq!(
+            vector.master_keys[0],
+            "125ab8c240a7845127b0dea5ea6bdd30749865f7119d3b7981961093314949d2"
+        );
+        assert_eq!(
+            vector.public_keys[0],
+            "b7fd706169f96dab8028a9e76174cf07d8b3d21299402f141e601d39307358b3ce9e61900ef2a32c42d03b9faa6253820e7cb84092d48f44a51a7e8e76d5f7a556af616e4e36a979f1f65a0edaabca7b60b60f9033045114f69f2adce24833e7"
+        );
+        assert_eq!(
+            vector.dem_key,
+            "8ed650049f57162dbc757f3941e4265a1113cd1609a502b04e8baeca408c67f8"
+        );
+
+        let encrypted_object =
+            EncryptedObject::from_bytes(&from_hex(&vector.encrypted_object).unwrap()).unwrap();
+        match &encrypted_object.ciphertext {
+            Ciphertext::Aes256Gcm { blob, .. } => assert_eq!(
+                hex::encode(blob),
+                "7bf121d78d24caed00eeb73fd8f26d68f31a057b8928d4baf6f2cbd1791c67dff9d18f2e13ed71"
+            ),
+            _ => panic!(),
+        }
+        assert_eq!(
+            vector.encrypted_object,
+            "0076a04053bda0a88bda5177b86a15c3b29f559873cb481232299cd5743151ac4b042d63ae19031eb219025c4c87a67c4aaa86f20ac0aa792bc121ee42e2c326127061eda1559901cb5db3db870bea5aecf353161c3cb528b0c5d98050c4570bfc942d8b19ed7b0c02bba5725e03e5f0b7e30db36b6df82ac151f668f5f80a5e2a9cac7c64991dd6a60302009206e5210c1e9afdb399bc9906a7a98cb4a9df871d754392cb66e41c4e47903000fc84d60cc934df624b5af3c36ba0860481eb83a5b00b6ffe577b88f9a3578a6d09119dcf7fca3d39f3e4c0009081cf0fbe8e13f382dde92e7a5b8569ebaec6036956323addf3c8b039edda8b72ddbd0870673bf6e93fd48c4e840085569affec4a86415aa972d25bec332e13d154d3b4137da27db963a0c5d2d7a61dda88911f414a6e4c7dedc8440de0eb0661029675f15073fed835d9a72bff061ee0da9e3fab4d8ea74589c4176b5e95d080a4cfbbb74e086f494f8638a2104ca625b5daa600277bf121d78d24caed00eeb73fd8f26d68f31a057b8928d4baf6f2cbd1791c67dff9d18f2e13ed7101105365616c207465737420766563746f72"
+        );
+    }
+
+    /// 种子0对应全零的ChaCha20密钥，输出流与RFC 8439附录A.1的第一个测试向量相同
+    #[test]
+    fn test_seed_to_stream() {
+        let mut stream = [0u8; 16];
+        TestVectorRng::from_seed(0).fill_bytes(&mut stream);
+        assert_eq!(hex::encode(stream), "76b8e0ada0f13d90405d6ae55386bd28");
+
+        let mut other = [0u8; 16];
+        TestVectorRng::from_seed(1).fill_bytes(&mut other);
+        assert_ne!(stream, other);
+    }
+}
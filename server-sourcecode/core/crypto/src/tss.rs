@@ -12,17 +12,33 @@
  * 1. 将秘密分割成多个份额
  * 2. 从足够数量的份额重构秘密
  * 3. 在指定点评估插值多项式
+ * 4. 可验证的秘密共享：为每个份额发布哈希承诺，在重构之前单独验证每个份额
+ * 5. 访问树：嵌套的阈值结构，例如"{A,B,C}中的2个 且 {D,E}中的1个"。
+ *    每个内部节点使用Shamir共享将自己的秘密分给子节点，子节点的索引为其位置加1
+ * 6. 加权共享：权重为w的参与者获得w个份额
+ * 7. 纠错解码：使用Berlekamp-Welch算法从包含少量错误份额的集合中重构秘密
  */
 
 use crate::gf256::GF256;
 use crate::polynomial::Polynomial;
-use crate::utils::transpose;
-use fastcrypto::error::FastCryptoError::InvalidInput;
+use crate::utils::{generate_random_bytes, transpose};
+use fastcrypto::error::FastCryptoError::{GeneralError, InvalidInput};
 use fastcrypto::error::FastCryptoResult;
+use fastcrypto::hash::{HashFunction, Sha3_256};
 use fastcrypto::traits::AllowedRng;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::iter::repeat_with;
 
+/// 份额承诺的域分隔标签
+const DST_SHARE_COMMITMENT: &[u8] = b"SUI-SEAL-TSS-SHARE-COMMITMENT-00";
+
+/// 份额的哈希承诺
+pub type ShareCommitment = [u8; 32];
+
+/// 加权共享中一个参与者的份额，每个元素为(索引, 份额内容)对
+pub type WeightedShares<const N: usize> = Vec<(u8, [u8; N])>;
+
 /// 秘密共享结构体，包含原始秘密、份额索引和份额内容
 pub struct SecretSharing<const N: usize> {
     pub(crate) secret: [u8; N],        // 原始秘密
@@ -90,6 +106,42 @@ pub fn split<R: AllowedRng, const N: usize>(
     })
 }
 
+/**
+ * 加权秘密共享：权重为w的参与者获得w个份额，重构秘密需要的是份额数量而不是参与者数量
+ *
+ * 份额索引按照参与者的顺序连续分配，例如权重为[2, 1]时，第一个参与者获得索引1和2，
+ * 第二个参与者获得索引3。
+ *
+ * 参数:
+ * @param rng - 随机数生成器，用于生成随机多项式系数
+ * @param secret - 要共享的秘密
+ * @param threshold - 重构秘密所需的最小份额数量（即权重之和）
+ * @param weights - 每个参与者的权重，必须大于0，且总和不超过255
+ *
+ * 返回:
+ * 每个参与者的份额列表
+ */
+pub fn split_weighted<R: AllowedRng, const N: usize>(
+    rng: &mut R,
+    secret: [u8; N],
+    threshold: u8,
+    weights: &[u8],
+) -> FastCryptoResult<Vec<WeightedShares<N>>> {
+    let total_weight = weights.iter().map(|w| *w as usize).sum::<usize>();
+    if weights.contains(&0) || total_weight > u8::MAX as usize {
+        return Err(InvalidInput);
+    }
+
+    let SecretSharing {
+        indices, shares, ..
+    } = split(rng, secret, threshold, total_weight as u8)?;
+    let mut shares = indices.into_iter().zip(shares);
+    Ok(weights
+        .iter()
+        .map(|w| shares.by_ref().take(*w as usize).collect())
+        .collect())
+}
+
 /**
  * 根据份额集合插值多项式，并返回一个闭包用于在指定点评估多项式
  * 
@@ -163,6 +215,162 @@ pub fn combine<const N: usize>(shares: &[(u8, [u8; N])]) -> FastCryptoResult<[u8
         .expect("fixed length"))
 }
 
+/**
+ * 使用Berlekamp-Welch算法从可能包含错误份额的集合中重构秘密
+ * 
+ * 对秘密的每个字节，n个份额中最多有⌊(n - threshold) / 2⌋个错误时总能找到正确的多项式，
+ * 计算量为n的多项式级别，不需要尝试所有阈值大小的子集。错误更多时返回错误，
+ * 但错误的份额也可能恰好构成另一个多项式，因此调用者应该独立验证重构的秘密。
+ * 
+ * 参数:
+ * @param shares - 份额集合，每个元素为(索引, 份额内容)对
+ * @param threshold - 重构秘密需要的份额数量
+ * 
+ * 返回:
+ * 重构的秘密，以及与解码出的多项式不一致的份额索引
+ */
+pub fn decode<const N: usize>(
+    shares: &[(u8, [u8; N])],
+    threshold: u8,
+) -> FastCryptoResult<([u8; N], Vec<u8>)> {
+    if threshold == 0
+        || shares.len() < threshold as usize
+        || shares.iter().any(|(i, _)| *i == 0)
+        || !shares.iter().map(|(i, _)| i).all_unique()
+    {
+        return Err(InvalidInput);
+    }
+
+    let polynomials = (0..N)
+        .map(|i| {
+            decode_byte(
+                &shares
+                    .iter()
+                    .map(|(index, share)| (GF256(*index), GF256(share[i])))
+                    .collect_vec(),
+                threshold as usize,
+            )
+        })
+        .collect::<FastCryptoResult<Vec<_>>>()?;
+    let secret = polynomials
+        .iter()
+        .map(|p| p.evaluate(&GF256::zero()).into())
+        .collect_vec()
+        .try_into()
+        .expect("fixed length");
+    let invalid = shares
+        .iter()
+        .filter(|(index, share)| {
+            polynomials
+                .iter()
+                .zip(share)
+                .any(|(p, byte)| p.evaluate(&GF256(*index)) != GF256(*byte))
+        })
+        .map(|(index, _)| *index)
+        .collect();
+    Ok((secret, invalid))
+}
+
+/**
+ * 使用Berlekamp-Welch算法解码一个字节的份额
+ * 
+ * 设e = ⌊(n - k) / 2⌋，求解线性方程组Q(xᵢ) = yᵢ·E(xᵢ)，其中E是e次首一多项式，
+ * Q的次数小于e + k。错误不超过e个时，P = Q / E就是正确的多项式。
+ * 
+ * 参数:
+ * @param points - 份额集合，每个点表示为(索引, 份额值)对，索引互不相同
+ * @param threshold - 多项式的次数加1
+ * 
+ * 返回:
+ * 次数小于threshold、且最多与e个点不一致的多项式，如果不存在则返回错误
+ */
+fn decode_byte(points: &[(GF256, GF256)], threshold: usize) -> FastCryptoResult<Polynomial> {
+    let errors = (points.len() - threshold) / 2;
+    let q_length = errors + threshold;
+
+    // 每个点一行：Q的系数，E除最高次项外的系数，以及右侧的yᵢ·xᵢᵉ。
+    // 在特征为2的域中减法与加法相同，因此方程可以写为Q(xᵢ) + yᵢ·(E(xᵢ) - xᵢᵉ) = yᵢ·xᵢᵉ
+    let rows = points
+        .iter()
+        .map(|(x, y)| {
+            let powers = std::iter::successors(Some(GF256::one()), |p| Some(p * x))
+                .take(q_length + 1)
+                .collect_vec();
+            powers[..q_length]
+                .iter()
+                .copied()
+                .chain(powers[..errors].iter().map(|p| y * p))
+                .chain(std::iter::once(y * &powers[errors]))
+                .collect_vec()
+        })
+        .collect_vec();
+    let solution = solve(rows, q_length + errors)?;
+
+    let q = Polynomial(solution[..q_length].to_vec());
+    let e = Polynomial(
+        solution[q_length..]
+            .iter()
+            .copied()
+            .chain(std::iter::once(GF256::one()))
+            .collect(),
+    );
+    let (p, remainder) = q.div_rem(&e)?;
+    if remainder != Polynomial::zero()
+        || p.0.len() > threshold
+        || points.iter().filter(|(x, y)| p.evaluate(x) != *y).count() > errors
+    {
+        return Err(GeneralError("Too many invalid shares".to_string()));
+    }
+    Ok(p)
+}
+
+/**
+ * 使用高斯消元法求解GF256上的线性方程组
+ * 
+ * 参数:
+ * @param rows - 增广矩阵，每行为方程的系数加上右侧的值
+ * @param unknowns - 未知数的数量
+ * 
+ * 返回:
+ * 方程组的一个解，自由变量取零，如果无解则返回错误
+ */
+fn solve(mut rows: Vec<Vec<GF256>>, unknowns: usize) -> FastCryptoResult<Vec<GF256>> {
+    let mut pivots = vec![];
+    for column in 0..unknowns {
+        let r = pivots.len();
+        let Some(pivot) = (r..rows.len()).find(|&i| rows[i][column] != GF256::zero()) else {
+            continue;
+        };
+        rows.swap(r, pivot);
+        let inverse = (&GF256::one() / &rows[r][column])?;
+        rows[r] = rows[r].iter().map(|a| a * &inverse).collect();
+        for i in 0..rows.len() {
+            let factor = rows[i][column];
+            if i != r && factor != GF256::zero() {
+                let reduced = rows[i]
+                    .iter()
+                    .zip(&rows[r])
+                    .map(|(a, b)| a - &(&factor * b))
+                    .collect();
+                rows[i] = reduced;
+            }
+        }
+        pivots.push(column);
+    }
+    if rows[pivots.len()..]
+        .iter()
+        .any(|row| row[unknowns] != GF256::zero())
+    {
+        return Err(GeneralError("Too many invalid shares".to_string()));
+    }
+
+    let mut solution = vec![GF256::zero(); unknowns];
+    for (row, column) in rows.iter().zip(pivots) {
+        solution[column] = row[unknowns];
+    }
+    Ok(solution)
+}
+
 /**
  * 使用给定的份额创建秘密共享
  * 
@@ -206,6 +414,393 @@ pub fn split_with_given_shares<const N: usize>(
     })
 }
 
+/// 可验证的份额，包含份额索引、份额内容和用于打开承诺的随机盲化值
+#[derive(Clone, PartialEq, Eq)]
+pub struct VerifiableShare<const N: usize> {
+    pub index: u8,
+    pub share: [u8; N],
+    pub blinding: [u8; 32],
+}
+
+/// 可验证的秘密共享结构体，包含原始秘密、份额以及可以公开发布的份额承诺
+pub struct VerifiableSecretSharing<const N: usize> {
+    pub(crate) secret: [u8; N],               // 原始秘密
+    pub(crate) shares: Vec<VerifiableShare<N>>, // 份额及其盲化值
+    pub(crate) commitments: Vec<ShareCommitment>, // 第i个承诺对应索引为i+1的份额
+}
+
+impl<const N: usize> VerifiableSecretSharing<N> {
+    /// 获取所有份额
+    pub fn shares(&self) -> &[VerifiableShare<N>] {
+        &self.shares
+    }
+
+    /// 获取所有份额承诺，可以公开发布
+    pub fn commitments(&self) -> &[ShareCommitment] {
+        &self.commitments
+    }
+
+    /// 获取原始秘密
+    pub fn secret(&self) -> &[u8; N] {
+        &self.secret
+    }
+}
+
+/**
+ * 将秘密分割成可验证的份额
+ * 
+ * 与`split`相同，但同时为每个份额计算哈希承诺 H(DST || index || blinding || share)。
+ * 承诺可以与加密对象一起公开发布，份额持有者或重构者可以在`combine`之前单独验证每个份额。
+ * 随机盲化值保证承诺不泄露份额的任何信息。
+ * 
+ * 注意：承诺只能证明份额与分发者发布的一致，不能证明诚实的分发者以外的份额位于同一多项式上。
+ * 
+ * 参数:
+ * @param rng - 随机数生成器，用于生成随机多项式系数和盲化值
+ * @param secret - 要共享的秘密
+ * @param threshold - 重构秘密所需的最小份额数量
+ * @param number_of_shares - 要生成的份额总数
+ * 
+ * 返回:
+ * 包含秘密、份额和承诺的VerifiableSecretSharing结构
+ */
+pub fn split_verifiable<R: AllowedRng, const N: usize>(
+    rng: &mut R,
+    secret: [u8; N],
+    threshold: u8,
+    number_of_shares: u8,
+) -> FastCryptoResult<VerifiableSecretSharing<N>> {
+    let SecretSharing {
+        secret,
+        indices,
+        shares,
+    } = split(rng, secret, threshold, number_of_shares)?;
+
+    let shares = indices
+        .into_iter()
+        .zip(shares)
+        .map(|(index, share)| VerifiableShare {
+            index,
+            share,
+            blinding: generate_random_bytes(rng),
+        })
+        .collect_vec();
+    let commitments = shares.iter().map(commit_share).collect();
+
+    Ok(VerifiableSecretSharing {
+        secret,
+        shares,
+        commitments,
+    })
+}
+
+/**
+ * 根据公开的承诺验证单个份额
+ * 
+ * 参数:
+ * @param commitments - 分发者发布的份额承诺
+ * @param share - 要验证的份额
+ * 
+ * 返回:
+ * 如果份额的索引无效或与承诺不一致，则返回错误
+ */
+pub fn verify_share<const N: usize>(
+    commitments: &[ShareCommitment],
+    share: &VerifiableShare<N>,
+) -> FastCryptoResult<()> {
+    let commitment = (share.index as usize)
+        .checked_sub(1)
+        .and_then(|i| commitments.get(i))
+        .ok_or(InvalidInput)?;
+    if commit_share(share) != *commitment {
+        return Err(GeneralError(format!(
+            "Invalid share with index {}",
+            share.index
+        )));
+    }
+    Ok(())
+}
+
+/**
+ * 找出所有与承诺不一致的份额
+ * 
+ * 参数:
+ * @param commitments - 分发者发布的份额承诺
+ * @param shares - 要验证的份额集合
+ * 
+ * 返回:
+ * 无效份额的索引列表，如果所有份额都有效则为空
+ */
+pub fn find_invalid_shares<const N: usize>(
+    commitments: &[ShareCommitment],
+    shares: &[VerifiableShare<N>],
+) -> Vec<u8> {
+    shares
+        .iter()
+        .filter(|share| verify_share(commitments, share).is_err())
+        .map(|share| share.index)
+        .collect()
+}
+
+/**
+ * 验证所有份额后重构秘密
+ * 
+ * 与`combine`相同，但在重构之前根据承诺验证每个份额。
+ * 如果有份额无效，返回的错误中包含所有无效份额的索引，调用者可以丢弃这些份额后使用其他份额重试。
+ * 
+ * 参数:
+ * @param commitments - 分发者发布的份额承诺
+ * @param shares - 份额集合
+ * 
+ * 返回:
+ * 重构的秘密
+ */
+pub fn combine_verified<const N: usize>(
+    commitments: &[ShareCommitment],
+    shares: &[VerifiableShare<N>],
+) -> FastCryptoResult<[u8; N]> {
+    let invalid = find_invalid_shares(commitments, shares);
+    if !invalid.is_empty() {
+        return Err(GeneralError(format!(
+            "Invalid shares with indices {:?}",
+            invalid
+        )));
+    }
+    combine(
+        &shares
+            .iter()
+            .map(|share| (share.index, share.share))
+            .collect_vec(),
+    )
+}
+
+/// 访问树，描述哪些叶子（密钥服务器）的组合可以重构秘密
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccessTree {
+    /// 叶子节点，值为对应份额在叶子列表中的位置（从0开始）
+    Leaf(u8),
+    /// 阈值节点，至少`threshold`个子节点满足时该节点满足
+    Threshold {
+        threshold: u8,
+        children: Vec<AccessTree>,
+    },
+}
+
+impl AccessTree {
+    /**
+     * 检查访问树是否有效
+     *
+     * 每个阈值节点的阈值必须在1和子节点数量之间，并且叶子恰好是0..number_of_leaves的一个排列。
+     *
+     * 参数:
+     * @param number_of_leaves - 叶子（份额）的数量
+     *
+     * 返回:
+     * 如果访问树无效则返回InvalidInput错误
+     */
+    pub fn validate(&self, number_of_leaves: usize) -> FastCryptoResult<()> {
+        let mut leaves = vec![];
+        self.collect_leaves(&mut leaves)?;
+        if !leaves.into_iter().sorted().eq(0..number_of_leaves) {
+            return Err(InvalidInput);
+        }
+        Ok(())
+    }
+
+    /// 满足访问树所需的最少叶子数量
+    pub fn min_leaves(&self) -> usize {
+        match self {
+            AccessTree::Leaf(_) => 1,
+            AccessTree::Threshold {
+                threshold,
+                children,
+            } => children
+                .iter()
+                .map(AccessTree::min_leaves)
+                .sorted()
+                .take(*threshold as usize)
+                .sum(),
+        }
+    }
+
+    /// 判断给定的叶子集合是否满足访问树
+    pub fn is_satisfied_by(&self, leaves: &[u8]) -> bool {
+        match self {
+            AccessTree::Leaf(leaf) => leaves.contains(leaf),
+            AccessTree::Threshold {
+                threshold,
+                children,
+            } => {
+                children
+                    .iter()
+                    .filter(|child| child.is_satisfied_by(leaves))
+                    .count()
+                    >= *threshold as usize
+            }
+        }
+    }
+
+    /// 按深度优先顺序收集所有叶子，同时检查阈值节点
+    fn collect_leaves(&self, leaves: &mut Vec<usize>) -> FastCryptoResult<()> {
+        match self {
+            AccessTree::Leaf(leaf) => leaves.push(*leaf as usize),
+            AccessTree::Threshold {
+                threshold,
+                children,
+            } => {
+                if *threshold == 0 || *threshold as usize > children.len() || children.len() > 255 {
+                    return Err(InvalidInput);
+                }
+                children
+                    .iter()
+                    .try_for_each(|child| child.collect_leaves(leaves))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/**
+ * 按照访问树分割秘密
+ *
+ * 根节点的秘密为`secret`，每个阈值节点将自己的秘密分割给子节点，叶子节点得到最终的份额。
+ *
+ * 参数:
+ * @param rng - 随机数生成器
+ * @param secret - 要共享的秘密
+ * @param tree - 访问树
+ * @param number_of_leaves - 叶子的数量
+ *
+ * 返回:
+ * 按叶子位置排列的份额
+ */
+pub fn split_tree<R: AllowedRng, const N: usize>(
+    rng: &mut R,
+    secret: [u8; N],
+    tree: &AccessTree,
+    number_of_leaves: usize,
+) -> FastCryptoResult<Vec<[u8; N]>> {
+    tree.validate(number_of_leaves)?;
+    let mut shares = vec![[0u8; N]; number_of_leaves];
+    split_node(rng, secret, tree, &mut shares)?;
+    Ok(shares)
+}
+
+/**
+ * 按照访问树从一组叶子份额重构秘密
+ *
+ * 每个阈值节点使用前`threshold`个可以重构的子节点。
+ * 与`combine`相同，如果某些份额无效，结果将是错误的，但不会返回错误。
+ *
+ * 参数:
+ * @param tree - 访问树
+ * @param shares - 叶子份额集合，每个元素为(叶子位置, 份额内容)对
+ *
+ * 返回:
+ * 重构的秘密，如果给定的叶子不满足访问树则返回错误
+ */
+pub fn combine_tree<const N: usize>(
+    tree: &AccessTree,
+    shares: &[(u8, [u8; N])],
+) -> FastCryptoResult<[u8; N]> {
+    match tree {
+        AccessTree::Leaf(leaf) => shares
+            .iter()
+            .find(|(i, _)| i == leaf)
+            .map(|(_, share)| *share)
+            .ok_or(InvalidInput),
+        AccessTree::Threshold {
+            threshold,
+            children,
+        } => {
+            let child_shares = children
+                .iter()
+                .enumerate()
+                .filter_map(|(i, child)| {
+                    combine_tree(child, shares)
+                        .ok()
+                        .map(|share| (i as u8 + 1, share))
+                })
+                .take(*threshold as usize)
+                .collect_vec();
+            if child_shares.len() < *threshold as usize {
+                return Err(InvalidInput);
+            }
+            combine(&child_shares)
+        }
+    }
+}
+
+/**
+ * 使用所有叶子份额重构秘密，并检查每个阈值节点的所有子节点是否一致
+ *
+ * 参数:
+ * @param tree - 访问树
+ * @param shares - 按叶子位置排列的所有份额
+ *
+ * 返回:
+ * 重构的秘密，如果任何阈值节点的子节点不在同一个多项式上则返回错误
+ */
+pub fn combine_tree_consistent<const N: usize>(
+    tree: &AccessTree,
+    shares: &[[u8; N]],
+) -> FastCryptoResult<[u8; N]> {
+    match tree {
+        AccessTree::Leaf(leaf) => shares.get(*leaf as usize).copied().ok_or(InvalidInput),
+        AccessTree::Threshold {
+            threshold,
+            children,
+        } => {
+            let child_shares = children
+                .iter()
+                .enumerate()
+                .map(|(i, child)| Ok((i as u8 + 1, combine_tree_consistent(child, shares)?)))
+                .collect::<FastCryptoResult<Vec<_>>>()?;
+            let polynomial = interpolate(&child_shares[..*threshold as usize])?;
+            if child_shares
+                .iter()
+                .any(|(i, share)| polynomial(*i) != *share)
+            {
+                return Err(GeneralError("Inconsistent shares".to_string()));
+            }
+            Ok(polynomial(0))
+        }
+    }
+}
+
+/// 将节点的秘密分割给子节点，叶子节点的秘密写入对应位置
+fn split_node<R: AllowedRng, const N: usize>(
+    rng: &mut R,
+    secret: [u8; N],
+    node: &AccessTree,
+    shares: &mut [[u8; N]],
+) -> FastCryptoResult<()> {
+    match node {
+        AccessTree::Leaf(leaf) => shares[*leaf as usize] = secret,
+        AccessTree::Threshold {
+            threshold,
+            children,
+        } => {
+            let sharing = split(rng, secret, *threshold, children.len() as u8)?;
+            children
+                .iter()
+                .zip(sharing.shares)
+                .try_for_each(|(child, share)| split_node(rng, share, child, shares))?;
+        }
+    }
+    Ok(())
+}
+
+/// 计算份额的哈希承诺 H(DST || index || blinding || share)
+fn commit_share<const N: usize>(share: &VerifiableShare<N>) -> ShareCommitment {
+    let mut hash = Sha3_256::default();
+    hash.update(DST_SHARE_COMMITMENT);
+    hash.update([share.index]);
+    hash.update(share.blinding);
+    hash.update(share.share);
+    hash.finalize().digest
+}
+
 /**
  * 内部函数：共享单个字节的秘密
  * 
@@ -335,6 +930,69 @@ mod tests {
     use fastcrypto::encoding::{Base64, Encoding};
     use rand::thread_rng;
 
+    /// 测试访问树 "{0,1,2}中的2个 且 {3,4}中的1个"
+    /// 1. 满足访问树的任意叶子集合都可以重构秘密，不满足的则不能
+    /// 2. 一致性检查可以发现被篡改的份额
+    /// 3. 无效的访问树被拒绝
+    #[test]
+    fn test_access_tree() {
+        let tree = AccessTree::Threshold {
+            threshold: 2,
+            children: vec![
+                AccessTree::Threshold {
+                    threshold: 2,
+                    children: vec![
+                        AccessTree::Leaf(0),
+                        AccessTree::Leaf(1),
+                        AccessTree::Leaf(2),
+                    ],
+                },
+                AccessTree::Threshold {
+                    threshold: 1,
+                    children: vec![AccessTree::Leaf(3), AccessTree::Leaf(4)],
+                },
+            ],
+        };
+        assert_eq!(tree.min_leaves(), 3);
+
+        let secret = [7u8; 32];
+        let shares = split_tree(&mut thread_rng(), secret, &tree, 5).unwrap();
+        let subset = |leaves: &[u8]| {
+            leaves
+                .iter()
+                .map(|&i| (i, shares[i as usize]))
+                .collect_vec()
+        };
+        for leaves in [&[0, 1, 3][..], &[1, 2, 4], &[0, 1, 2, 3, 4]] {
+            assert!(tree.is_satisfied_by(leaves));
+            assert_eq!(combine_tree(&tree, &subset(leaves)).unwrap(), secret);
+        }
+        for leaves in [&[0, 3, 4][..], &[0, 1, 2], &[2]] {
+            assert!(!tree.is_satisfied_by(leaves));
+            assert!(combine_tree(&tree, &subset(leaves)).is_err());
+        }
+
+        assert_eq!(combine_tree_consistent(&tree, &shares).unwrap(), secret);
+        let mut modified = shares.clone();
+        modified[4][0] ^= 1;
+        assert!(combine_tree_consistent(&tree, &modified).is_err());
+
+        // 叶子不是0..n的排列，或者阈值无效
+        assert!(tree.validate(6).is_err());
+        assert!(AccessTree::Threshold {
+            threshold: 2,
+            children: vec![AccessTree::Leaf(0), AccessTree::Leaf(0)],
+        }
+        .validate(2)
+        .is_err());
+        assert!(AccessTree::Threshold {
+            threshold: 3,
+            children: vec![AccessTree::Leaf(0), AccessTree::Leaf(1)],
+        }
+        .validate(2)
+        .is_err());
+    }
+
     /// 测试单字节组合函数
     /// 验证combine_byte函数能够正确重建秘密值
     #[test]
@@ -387,6 +1045,71 @@ mod tests {
         assert!(combine(&[(indices[0], shares[0]), (indices[0], shares[0])]).is_err());
     }
 
+    /// 测试纠错解码
+    /// 1. 7个份额、阈值3时每个字节最多纠正2个错误，并指出错误份额的索引
+    /// 2. 错误更多时不会得到正确的秘密，无效输入返回错误
+    #[test]
+    fn test_decode() {
+        let secret = *b"For sale: baby shoes, never worn";
+        let SecretSharing {
+            indices, shares, ..
+        } = split(&mut thread_rng(), secret, 3, 7).unwrap();
+        let mut shares = indices.into_iter().zip(shares).collect_vec();
+        assert_eq!(decode(&shares, 3).unwrap(), (secret, vec![]));
+
+        shares[1].1[0] ^= 1;
+        shares[4].1 = [0; 32];
+        assert_eq!(decode(&shares, 3).unwrap(), (secret, vec![2, 5]));
+        assert_eq!(
+            decode(&[shares[0], shares[2], shares[3]], 3).unwrap(),
+            (secret, vec![])
+        );
+
+        // 每个字节分别纠错，因此不同字节中的错误可以分布在更多份额上
+        shares[6].1[31] ^= 1;
+        assert_eq!(decode(&shares, 3).unwrap(), (secret, vec![2, 5, 7]));
+
+        shares[6].1[0] ^= 1;
+        assert!(decode(&shares, 3).map_or(true, |(decoded, _)| decoded != secret));
+
+        assert!(decode(&shares[..2], 3).is_err());
+        assert!(decode(&shares, 0).is_err());
+        assert!(decode(&[shares[0], shares[0], shares[1]], 3).is_err());
+    }
+
+    /// 测试加权秘密共享：权重为2的参与者单独和另一个参与者一起即可达到阈值3
+    #[test]
+    fn test_weighted_secret_sharing() {
+        let secret = *b"For sale: baby shoes, never worn";
+        let shares = split_weighted(&mut thread_rng(), secret, 3, &[2, 1, 1]).unwrap();
+        assert_eq!(
+            shares
+                .iter()
+                .map(|s| s.iter().map(|(i, _)| *i).collect_vec())
+                .collect_vec(),
+            vec![vec![1, 2], vec![3], vec![4]]
+        );
+
+        let combined = |parties: &[usize]| {
+            combine(
+                &parties
+                    .iter()
+                    .flat_map(|p| shares[*p].clone())
+                    .collect_vec(),
+            )
+            .unwrap()
+        };
+        assert_eq!(secret, combined(&[0, 1]));
+        assert_eq!(secret, combined(&[0, 2]));
+        assert_eq!(secret, combined(&[0, 1, 2]));
+        assert_ne!(secret, combined(&[1, 2]));
+
+        // 权重为0或总和超过255时返回错误
+        assert!(split_weighted(&mut thread_rng(), secret, 1, &[1, 0]).is_err());
+        assert!(split_weighted(&mut thread_rng(), secret, 1, &[200, 100]).is_err());
+        assert!(split_weighted(&mut thread_rng(), secret, 5, &[2, 2]).is_err());
+    }
+
     /// 测试无效份额输入的错误处理
     /// 验证combine函数对非法输入的正确处理
     #[test]
@@ -456,6 +1179,49 @@ mod tests {
         assert_eq!(reconstructed, secret);
     }
 
+    /// 测试可验证的秘密共享
+    /// 1. 所有份额都能通过验证，并且可以重建秘密
+    /// 2. 被篡改的份额、盲化值或索引无法通过验证，并且能识别出无效份额的索引
+    #[test]
+    fn test_verifiable_secret_sharing() {
+        let secret = *b"For sale: baby shoes, never worn";
+        let vss = split_verifiable(&mut thread_rng(), secret, 3, 5).unwrap();
+        assert_eq!(vss.secret(), &secret);
+        assert_eq!(vss.commitments().len(), 5);
+
+        let commitments = vss.commitments();
+        let shares = vss.shares();
+        assert!(shares.iter().all(|s| verify_share(commitments, s).is_ok()));
+        assert_eq!(
+            combine_verified(commitments, &shares[1..4]).unwrap(),
+            secret
+        );
+
+        // 篡改份额内容
+        let mut corrupted = shares.to_vec();
+        corrupted[2].share[0] ^= 1;
+        assert!(verify_share(commitments, &corrupted[2]).is_err());
+        assert_eq!(find_invalid_shares(commitments, &corrupted), vec![3]);
+        assert!(combine_verified(commitments, &corrupted[..3]).is_err());
+
+        // 丢弃无效份额后使用其他份额重试
+        let retry = [&corrupted[..2], &corrupted[3..4]].concat();
+        assert_eq!(combine_verified(commitments, &retry).unwrap(), secret);
+
+        // 篡改盲化值
+        corrupted[4].blinding[0] ^= 1;
+        assert_eq!(find_invalid_shares(commitments, &corrupted), vec![3, 5]);
+
+        // 交换索引或使用超出范围的索引
+        let mut swapped = shares[0].clone();
+        swapped.index = 2;
+        assert!(verify_share(commitments, &swapped).is_err());
+        swapped.index = 0;
+        assert!(verify_share(commitments, &swapped).is_err());
+        swapped.index = 6;
+        assert!(verify_share(commitments, &swapped).is_err());
+    }
+
     /// 测试带有预定义份额的秘密分割
     /// 验证split_with_given_shares函数的正确性
     #[test]
@@ -8,6 +8,7 @@
  * 1. 字节数组异或操作
  * 2. 随机字节生成
  * 3. 矩阵转置（用于秘密共享中处理字节矩阵）
+ * 4. 启用`parallel`特性时在线程池上并行迭代
  * 
  * 这些工具函数主要供密钥管理系统的其他组件内部使用。
  */
@@ -17,6 +18,26 @@ use fastcrypto::error::FastCryptoResult;
 use fastcrypto::traits::AllowedRng;
 use itertools::Itertools;
 
+/// 启用`parallel`特性时返回rayon的并行迭代器，否则返回普通迭代器。
+/// 调用处需要在启用特性时导入`rayon::prelude::*`以使用并行迭代器的方法。
+#[cfg(feature = "parallel")]
+macro_rules! maybe_par_iter {
+    ($e:expr) => {
+        rayon::iter::IntoParallelIterator::into_par_iter($e)
+    };
+}
+
+/// 启用`parallel`特性时返回rayon的并行迭代器，否则返回普通迭代器。
+/// 调用处需要在启用特性时导入`rayon::prelude::*`以使用并行迭代器的方法。
+#[cfg(not(feature = "parallel"))]
+macro_rules! maybe_par_iter {
+    ($e:expr) => {
+        IntoIterator::into_iter($e)
+    };
+}
+
+pub(crate) use maybe_par_iter;
+
 /**
  * 对两个固定长度的字节数组执行异或操作
  * 
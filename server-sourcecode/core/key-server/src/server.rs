@@ -20,13 +20,13 @@ use axum::http::HeaderMap;
 use axum::routing::{get, post};
 use axum::{extract::State, Json};
 use core::time::Duration;
-use crypto::elgamal::encrypt;
 use crypto::ibe;
 use crypto::ibe::create_proof_of_possession;
 use errors::InternalError;
 use externals::get_latest_checkpoint_timestamp;
 use fastcrypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
 use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::groups::bls12381::Scalar;
 use fastcrypto::serde_helpers::ToFromByteArray;
 use fastcrypto::traits::VerifyingKey;
 use mysten_service::get_mysten_service;
@@ -39,6 +39,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::env;
 use std::future::Future;
+use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Instant;
 use sui_sdk::error::SuiRpcResult;
@@ -52,8 +53,12 @@ use tap::tap::TapFallible;
 use tokio::sync::watch::{channel, Receiver};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{debug, info, warn};
-use types::{ElGamalPublicKey, ElgamalEncryption, ElgamalVerificationKey, IbeMasterKey, Network};
+use types::{
+    ElGamalPublicKey, ElgamalEncryption, ElgamalEncryptionProof, ElgamalVerificationKey,
+    IbeMasterKey, IbePublicKey, Network,
+};
 use valid_ptb::ValidPtb;
+use zeroize::Zeroizing;
 
 // 内部模块
 mod cache;        // 缓存系统，优化性能
@@ -135,11 +140,16 @@ type Timestamp = u64;
  * 
  * 包含密钥ID和加密后的密钥
  * 返回给客户端用于解密其数据
+ * 
+ * 客户端可以在解密之前使用`ibe::verify_encrypted_user_secret_key`验证证明，
+ * 旧版本的服务器不返回证明，因此该字段是可选的
  */
 #[derive(Serialize, Deserialize)]
 struct DecryptionKey {
     id: KeyId,                      // 密钥标识符
     encrypted_key: ElgamalEncryption, // 加密的密钥
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    proof: Option<ElgamalEncryptionProof>, // 加密正确性证明
 }
 
 /**
@@ -161,7 +171,8 @@ struct FetchKeyResponse {
 struct Server {
     sui_client: SuiClient,              // Sui客户端
     network: Network,                   // 网络配置
-    master_key: IbeMasterKey,           // IBE主密钥
+    master_key: Arc<LockedMasterKey>,   // IBE主密钥，锁定在物理内存中
+    public_key: IbePublicKey,           // IBE公钥
     key_server_object_id: ObjectID,     // 密钥服务器对象ID
     key_server_object_id_sig: MasterKeyPOP, // 主密钥持有证明
 }
@@ -174,6 +185,7 @@ impl Server {
      * 
      * 参数:
      * @param master_key - IBE主密钥
+     * @param allow_unlocked_master_key - 无法锁定主密钥所在的内存页时是否只记录警告而不是停止启动
      * @param network - 网络配置
      * @param key_server_object_id - 服务器对象ID
      * 
@@ -182,6 +194,7 @@ impl Server {
      */
     async fn new(
         master_key: IbeMasterKey,
+        allow_unlocked_master_key: bool,
         network: Network,
         key_server_object_id: ObjectID,
     ) -> Self {
@@ -189,21 +202,23 @@ impl Server {
             .build(&network.node_url())
             .await
             .expect("SuiClientBuilder should not failed unless provided with invalid network url");
+        let public_key = ibe::public_key_from_master_key(&master_key);
         info!(
             "Server started with public key: {:?} and network: {:?}",
-            Base64::encode(
-                bcs::to_bytes(&ibe::public_key_from_master_key(&master_key)).expect("valid pk")
-            ),
+            Base64::encode(bcs::to_bytes(&public_key).expect("valid pk")),
             network
         );
 
+        let master_key = LockedMasterKey::new(master_key, allow_unlocked_master_key)
+            .expect("Failed to lock master key in memory");
         let key_server_object_id_sig =
             create_proof_of_possession(&master_key, &key_server_object_id.into_bytes());
 
         Server {
             sui_client,
             network,
-            master_key,
+            master_key: Arc::new(master_key),
+            public_key,
             key_server_object_id,
             key_server_object_id_sig,
         }
@@ -438,7 +453,7 @@ impl Server {
     /**
      * 创建响应
      * 
-     * 为每个密钥ID生成加密的解密密钥
+     * 为每个密钥ID生成加密的解密密钥，并附带加密正确性证明
      * 
      * 参数:
      * @param ids - 密钥ID列表
@@ -452,13 +467,18 @@ impl Server {
         let decryption_keys = ids
             .iter()
             .map(|id| {
-                // 请求的密钥
-                let key = ibe::extract(&self.master_key, id);
-                // 使用用户的公钥对密钥进行ElGamal加密
-                let encrypted_key = encrypt(&mut thread_rng(), &key, enc_key);
+                // 提取请求的密钥，使用用户的公钥对其进行ElGamal加密并生成证明
+                let (encrypted_key, proof) = ibe::extract_and_encrypt(
+                    &mut thread_rng(),
+                    &self.master_key,
+                    &self.public_key,
+                    id,
+                    enc_key,
+                );
                 DecryptionKey {
                     id: id.to_owned(),
                     encrypted_key,
+                    proof: Some(proof),
                 }
             })
             .collect();
@@ -607,6 +627,75 @@ impl Server {
     }
 }
 
+/**
+ * 锁定在物理内存中的IBE主密钥
+ * 
+ * 主密钥所在的内存页被mlock锁定，防止被交换到磁盘。释放时先清零主密钥，再使用munlock解除锁定，
+ * 这样主密钥在清零之前始终不会被换出。服务器的所有克隆通过`Arc`共享同一个实例。
+ */
+struct LockedMasterKey {
+    master_key: Option<IbeMasterKey>, // 主密钥，只在释放时取出
+    locked: bool,                     // 内存页是否已被锁定
+}
+
+impl LockedMasterKey {
+    /**
+     * 锁定主密钥所在的内存页
+     * 
+     * 参数:
+     * @param master_key - IBE主密钥，存储在地址固定的堆内存中
+     * @param allow_unlocked - 锁定失败时（例如超出RLIMIT_MEMLOCK限制）是否只记录警告
+     * 
+     * 返回:
+     * 锁定的主密钥。如果锁定失败且不允许未锁定的主密钥，则返回错误
+     */
+    fn new(master_key: IbeMasterKey, allow_unlocked: bool) -> std::io::Result<Self> {
+        // 安全性：mlock只读取指针所指的地址范围，该内存在master_key的整个生命周期内有效
+        let result = unsafe {
+            libc::mlock(
+                master_key.as_ptr() as *const libc::c_void,
+                std::mem::size_of_val(master_key.expose()),
+            )
+        };
+        let locked = result == 0;
+        if !locked {
+            let error = std::io::Error::last_os_error();
+            if !allow_unlocked {
+                return Err(error);
+            }
+            warn!("Failed to lock master key in memory: {}", error);
+        }
+        Ok(Self {
+            master_key: Some(master_key),
+            locked,
+        })
+    }
+}
+
+impl Deref for LockedMasterKey {
+    type Target = IbeMasterKey;
+
+    fn deref(&self) -> &IbeMasterKey {
+        self.master_key.as_ref().expect("只在释放时取出")
+    }
+}
+
+impl Drop for LockedMasterKey {
+    fn drop(&mut self) {
+        let master_key = self.master_key.take().expect("只在释放时取出");
+        let ptr = master_key.as_ptr() as *const libc::c_void;
+        let len = std::mem::size_of_val(master_key.expose());
+        // 主密钥在释放时清零，之后才解除锁定
+        drop(master_key);
+        if self.locked {
+            // 安全性：munlock只修改地址范围所在内存页的锁定状态，不读写其中的内容
+            unsafe {
+                libc::munlock(ptr, len);
+            }
+        }
+    }
+}
+
 /**
  * 处理获取密钥请求
  * 
@@ -739,18 +828,55 @@ impl MyState {
 /**
  * 主函数
  * 
- * 初始化并启动密钥服务器
+ * 读取主密钥后构建异步运行时并启动密钥服务器
  * 
  * 返回:
  * 操作结果
  */
-#[tokio::main]
-async fn main() -> Result<()> {
-    let master_key = env::var("MASTER_KEY").expect("MASTER_KEY must be set");
+fn main() -> Result<()> {
+    // 主密钥的编码形式在解析后立即清零，并从环境变量中移除，只是为了避免被之后启动的子进程继承。
+    // 这不能阻止通过/proc/<pid>/environ读取：它显示进程启动时的原始环境变量，remove_var不会修改它。
+    // 修改环境变量时不能有其他线程在运行，因此必须在构建tokio运行时之前完成
+    let master_key = {
+        let encoded = Zeroizing::new(env::var("MASTER_KEY").expect("MASTER_KEY must be set"));
+        env::remove_var("MASTER_KEY");
+        let bytes = Zeroizing::new(
+            Base64::decode(&encoded).expect("MASTER_KEY should be base64 encoded"),
+        );
+        IbeMasterKey::new(
+            Scalar::from_byte_array(
+                bytes
+                    .as_slice()
+                    .try_into()
+                    .expect("Invalid MASTER_KEY length"),
+            )
+            .expect("Invalid MASTER_KEY value"),
+        )
+    };
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(run(master_key))
+}
+
+/**
+ * 初始化并启动密钥服务器
+ *
+ * 参数:
+ * @param master_key - 从环境变量读取的主密钥
+ *
+ * 返回:
+ * 操作结果
+ */
+async fn run(master_key: IbeMasterKey) -> Result<()> {
     let object_id = env::var("KEY_SERVER_OBJECT_ID").expect("KEY_SERVER_OBJECT_ID must be set");
     let network = env::var("NETWORK")
         .map(|n| Network::from_str(&n))
         .unwrap_or(Network::Testnet);
+    // 默认情况下无法锁定主密钥所在的内存页时停止启动，只有显式设置时才允许未锁定的主密钥
+    let allow_unlocked_master_key = env::var("ALLOW_UNLOCKED_MASTER_KEY")
+        .map(|v| v == "true")
+        .unwrap_or(false);
 
     let _guard = mysten_service::logging::init();
     info!("Logging set up, setting up metrics");
@@ -764,13 +890,8 @@ async fn main() -> Result<()> {
     info!("Starting server, version {}", PACKAGE_VERSION);
 
     let s = Server::new(
-        IbeMasterKey::from_byte_array(
-            &Base64::decode(&master_key)
-                .expect("MASTER_KEY should be base64 encoded")
-                .try_into()
-                .expect("Invalid MASTER_KEY length"),
-        )
-        .expect("Invalid MASTER_KEY value"),
+        master_key,
+        allow_unlocked_master_key,
         network,
         ObjectID::from_hex_literal(&object_id).expect("Invalid KEY_SERVER_OBJECT_ID"),
     )
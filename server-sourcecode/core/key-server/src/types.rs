@@ -14,12 +14,12 @@ use crypto::elgamal;
 use crypto::ibe;
 
 /// 基于身份的加密相关类型
-/// IBE主密钥，用于生成用户私钥，应安全存储
+/// IBE主密钥，用于生成用户私钥，释放时自动清零
 pub type IbeMasterKey = ibe::MasterKey;
 /// IBE派生密钥，为特定用户生成的私钥
 type IbeDerivedKey = ibe::UserSecretKey;
 /// IBE公钥，公开发布
-type IbePublicKey = ibe::PublicKey;
+pub type IbePublicKey = ibe::PublicKey;
 
 /// ElGamal加密相关类型
 /// ElGamal公钥，用于加密IBE派生密钥
@@ -28,6 +28,8 @@ pub type ElGamalPublicKey = elgamal::PublicKey<IbeDerivedKey>;
 pub type ElgamalEncryption = elgamal::Encryption<IbeDerivedKey>;
 /// ElGamal验证密钥，用于验证加密通信
 pub type ElgamalVerificationKey = elgamal::VerificationKey<IbePublicKey>;
+/// 加密正确性证明，证明ElGamal密文中的IBE派生密钥对应于服务器的公钥
+pub type ElgamalEncryptionProof = ibe::EncryptedUserSecretKeyProof;
 
 /// 主密钥持有证明，证明服务器确实拥有声称的主密钥
 pub type MasterKeyPOP = ibe::ProofOfPossession;
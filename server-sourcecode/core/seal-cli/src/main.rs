@@ -10,28 +10,48 @@
  * - 生成IBE密钥对
  * - 提取用户私钥
  * - 验证用户私钥
- * - 使用Seal进行加密和解密操作
+ * - 使用Seal进行加密和解密操作，并可以压缩明文或填充明文以隐藏其长度
+ * - 使用Seal流式加密和解密大文件
  * - 解析和查看加密对象的结构
+ * - 将加密对象的密钥重新分享给新的密钥服务器集合
+ * - 将旧的加密对象升级到版本1格式
+ * - 签名加密对象，并在解密前验证发送者
  * 
  * 该CLI是Seal密码学核心库的前端，使开发者能够在命令行环境中测试和使用
  * 所有密码功能，而无需编写额外代码。
  */
 
-use clap::{Parser, Subcommand};
-use crypto::dem::{Aes256Gcm, Hmac256Ctr};
+use clap::{Parser, Subcommand, ValueEnum};
+use crypto::compression::{Compression, DEFAULT_MAX_DECOMPRESSED_SIZE};
+use crypto::dem::{
+    Aes256Gcm, Aes256GcmChunked, Aes256GcmCommitting, ChaCha20Poly1305, Hmac256Ctr, Padding,
+};
+use crypto::dkg::{self, KeyShare, PartySecretKey};
+use crypto::ibe::{MasterKey, PartialUserSecretKey};
+use crypto::secret::Secret;
+use crypto::signed::{Sender, SignedEncryptedObject, SigningKey};
 use crypto::EncryptionInput::Plain;
 use crypto::{
-    create_full_id, ibe, seal_decrypt, seal_encrypt, Ciphertext, EncryptedObject, EncryptionInput,
+    create_full_id, ibe, reshare_with_user_secret_keys, seal_decrypt_stream,
+    seal_decrypt_with_max_size, seal_encrypt, seal_encrypt_context_bound, seal_encrypt_stream,
+    upgrade_to_v1, verify_and_decrypt_with_max_size, Ciphertext, EncryptedObject, EncryptionInput,
     IBEEncryptions, IBEPublicKeys, IBEUserSecretKeys, ObjectID,
 };
 use fastcrypto::encoding::Encoding;
 use fastcrypto::encoding::Hex;
 use fastcrypto::error::{FastCryptoError, FastCryptoResult};
-use fastcrypto::groups::bls12381::{G1Element, G2Element, Scalar};
+use fastcrypto::ed25519::{Ed25519KeyPair, Ed25519PrivateKey, Ed25519PublicKey};
+use fastcrypto::groups::bls12381::{G1Element, G2Element};
+use fastcrypto::secp256k1::{Secp256k1KeyPair, Secp256k1PrivateKey, Secp256k1PublicKey};
+use fastcrypto::traits::ToFromBytes;
 use rand::thread_rng;
 use serde::Deserialize;
 use serde::Serialize;
 use std::fmt::{Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 /// 密钥长度常量（字节）
@@ -80,8 +100,8 @@ enum Command {
         id: EncodedBytes,
         
         /// 主密钥。BLS12-381标量的Hex编码
-        #[arg(long, value_parser = parse_serializable::<Scalar, DefaultEncoding>)]
-        master_key: Scalar,
+        #[arg(long, value_parser = parse_serializable::<MasterKey, DefaultEncoding>)]
+        master_key: MasterKey,
     },
     
     /// 验证用户私钥是否与公钥匹配
@@ -144,6 +164,22 @@ enum Command {
         #[arg(long)]
         aad: Option<EncodedBytes>,
         
+        /// 使用带有密钥承诺的AES-256-GCM，密文只能用唯一的密钥解密
+        #[arg(long)]
+        committing: bool,
+        
+        /// 将头部摘要绑定到额外认证数据中，修改头部后密文将无法解密
+        #[arg(long)]
+        bind_context: bool,
+        
+        /// 在加密前压缩消息："deflate"或"zstd"。同时指定填充时先压缩再填充
+        #[arg(long, value_parser = parse_compression)]
+        compress: Option<Compression>,
+        
+        /// 在加密前填充消息以隐藏其长度："bucket:<字节数>"、"power-of-two"或"padme"
+        #[arg(long, value_parser = parse_padding)]
+        pad: Option<Padding>,
+        
         /// Sui网络上处理此加密的KMS包的地址
         #[arg(long)]
         package_id: ObjectID,
@@ -178,6 +214,112 @@ enum Command {
         #[arg(long)]
         aad: Option<EncodedBytes>,
         
+        /// 将头部摘要绑定到额外认证数据中，修改头部后密文将无法解密
+        #[arg(long)]
+        bind_context: bool,
+        
+        /// 在加密前压缩消息："deflate"或"zstd"。同时指定填充时先压缩再填充
+        #[arg(long, value_parser = parse_compression)]
+        compress: Option<Compression>,
+        
+        /// 在加密前填充消息以隐藏其长度："bucket:<字节数>"、"power-of-two"或"padme"
+        #[arg(long, value_parser = parse_padding)]
+        pad: Option<Padding>,
+        
+        /// Sui网络上处理此加密的KMS包的地址
+        #[arg(long)]
+        package_id: ObjectID,
+        
+        /// 用于此加密的密钥ID
+        #[arg(long)]
+        id: EncodedBytes,
+        
+        /// 解密所需的密钥服务器最小数量（阈值）
+        #[arg(long)]
+        threshold: u8,
+        
+        /// 密钥服务器的Hex编码公钥列表
+        #[arg(value_parser = parse_serializable::<G2Element, DefaultEncoding>, num_args = 1..)]
+        public_keys: Vec<G2Element>,
+        
+        /// 表示密钥服务器的Move对象地址列表
+        #[arg(num_args = 1.., last = true)]
+        object_ids: Vec<ObjectID>,
+    },
+    
+    /// 使用Seal和ChaCha20-Poly1305加密消息
+    /// 
+    /// 使用基于身份的密钥封装机制(IBKEM)派生密钥，然后使用ChaCha20-Poly1305加密消息。
+    /// 在没有AES硬件加速的设备上，该模式通常比AES-256-GCM更快。
+    /// 该命令输出可以公开共享的加密对象和应私密保存的派生对称密钥。
+    EncryptChacha {
+        /// 要加密的消息（Hex编码字节）
+        #[arg(long)]
+        message: EncodedBytes,
+        
+        /// 可选的额外认证数据（Hex编码字节）
+        #[arg(long)]
+        aad: Option<EncodedBytes>,
+        
+        /// 将头部摘要绑定到额外认证数据中，修改头部后密文将无法解密
+        #[arg(long)]
+        bind_context: bool,
+        
+        /// 在加密前压缩消息："deflate"或"zstd"。同时指定填充时先压缩再填充
+        #[arg(long, value_parser = parse_compression)]
+        compress: Option<Compression>,
+        
+        /// 在加密前填充消息以隐藏其长度："bucket:<字节数>"、"power-of-two"或"padme"
+        #[arg(long, value_parser = parse_padding)]
+        pad: Option<Padding>,
+        
+        /// Sui网络上处理此加密的KMS包的地址
+        #[arg(long)]
+        package_id: ObjectID,
+        
+        /// 用于此加密的密钥ID
+        #[arg(long)]
+        id: EncodedBytes,
+        
+        /// 解密所需的密钥服务器最小数量（阈值）
+        #[arg(long)]
+        threshold: u8,
+        
+        /// 密钥服务器的Hex编码公钥列表
+        #[arg(value_parser = parse_serializable::<G2Element, DefaultEncoding>, num_args = 1..)]
+        public_keys: Vec<G2Element>,
+        
+        /// 表示密钥服务器的Move对象地址列表
+        #[arg(num_args = 1.., last = true)]
+        object_ids: Vec<ObjectID>,
+    },
+    
+    /// 使用Seal和分块AES-256-GCM流式加密文件
+    /// 
+    /// 逐块读取输入文件并将密文写入输出文件，因此不需要把整个文件读入内存。
+    /// 该命令输出不含密文的加密对象（Hex编码的BCS序列化形式）和派生的对称密钥，
+    /// 解密时需要同时提供加密对象和密文文件。
+    EncryptFile {
+        /// 要加密的文件路径
+        #[arg(long)]
+        input: PathBuf,
+        
+        /// 密文输出文件路径
+        #[arg(long)]
+        output: PathBuf,
+        
+        /// 可选的额外认证数据（Hex编码字节）
+        #[arg(long)]
+        aad: Option<EncodedBytes>,
+        
+        /// 明文块大小（字节），不能超过16 MiB
+        #[arg(long, default_value_t = Aes256GcmChunked::DEFAULT_CHUNK_SIZE)]
+        chunk_size: u32,
+        
+        /// 在加密前填充文件以隐藏其长度："bucket:<字节数>"、"power-of-two"或"padme"
+        #[arg(long, value_parser = parse_padding)]
+        pad: Option<Padding>,
+        
         /// Sui网络上处理此加密的KMS包的地址
         #[arg(long)]
         package_id: ObjectID,
@@ -199,6 +341,32 @@ enum Command {
         object_ids: Vec<ObjectID>,
     },
     
+    /// 流式解密由encrypt-file生成的文件
+    /// 
+    /// 使用提供的密钥服务器私钥重建密钥，逐块解密输入文件并写入输出文件。
+    /// 如果解密失败，输出文件的内容不可信，应当被删除。
+    DecryptFile {
+        /// encrypt-file输出的加密对象（Hex编码字节）
+        #[arg(value_parser = parse_serializable::<EncryptedObject, DefaultEncoding>)]
+        encrypted_object: EncryptedObject,
+        
+        /// 密文文件路径
+        #[arg(long)]
+        input: PathBuf,
+        
+        /// 明文输出文件路径
+        #[arg(long)]
+        output: PathBuf,
+        
+        /// 密钥服务器的私钥列表。私钥顺序必须与object_ids字段中的密钥服务器顺序匹配
+        #[arg(value_parser = parse_serializable::<G1Element, DefaultEncoding>, num_args = 1..)]
+        secret_keys: Vec<G1Element>,
+        
+        /// 用于此解密的密钥服务器Move对象地址列表
+        #[arg(num_args = 1.., last = true)]
+        object_ids: Vec<ObjectID>,
+    },
+    
     /// 解密Seal加密对象
     /// 
     /// 使用提供的密钥服务器私钥解密加密对象。如果加密对象包含消息，则返回该消息。
@@ -212,6 +380,10 @@ enum Command {
         #[arg(value_parser = parse_serializable::<G1Element, DefaultEncoding>, num_args = 1..)]
         secret_keys: Vec<G1Element>,
         
+        /// 解压缩后明文的最大长度（字节），用于防止解压缩炸弹
+        #[arg(long, default_value_t = DEFAULT_MAX_DECOMPRESSED_SIZE)]
+        max_decompressed_size: usize,
+        
         /// 用于此解密的密钥服务器Move对象地址列表
         #[arg(num_args = 1.., last = true)]
         object_ids: Vec<ObjectID>,
@@ -226,6 +398,54 @@ enum Command {
         encrypted_object: EncryptedObject,
     },
     
+    /// 将加密对象的密钥重新分享给新的密钥服务器集合
+    /// 
+    /// 使用旧的密钥服务器的私钥重建密钥，然后用新的密钥服务器的公钥和阈值重新分享，
+    /// 密文保持不变，因此不需要重新加密数据。旧的密钥服务器仍然可以解密原来的加密对象。
+    Reshare {
+        /// 加密对象（Hex编码字节）
+        #[arg(value_parser = parse_serializable::<EncryptedObject, DefaultEncoding>)]
+        encrypted_object: EncryptedObject,
+        
+        /// 旧的密钥服务器的私钥列表。私钥顺序必须与old_object_ids字段中的密钥服务器顺序匹配
+        #[arg(long, value_parser = parse_serializable::<G1Element, DefaultEncoding>, num_args = 1.., required = true)]
+        secret_keys: Vec<G1Element>,
+        
+        /// 旧的密钥服务器Move对象地址列表
+        #[arg(long, num_args = 1.., required = true)]
+        old_object_ids: Vec<ObjectID>,
+        
+        /// 解密所需的新密钥服务器最小数量（阈值）
+        #[arg(long)]
+        threshold: u8,
+        
+        /// 新的密钥服务器的Hex编码公钥列表
+        #[arg(value_parser = parse_serializable::<G2Element, DefaultEncoding>, num_args = 1..)]
+        public_keys: Vec<G2Element>,
+        
+        /// 新的密钥服务器Move对象地址列表
+        #[arg(num_args = 1.., last = true)]
+        object_ids: Vec<ObjectID>,
+    },
+    
+    /// 将加密对象升级到版本1
+    /// 
+    /// 升级不需要明文或任何密钥，升级后的对象可以使用相同的私钥解密。
+    /// 可以同时设置版本1头部中的创建时间和内容类型。
+    Upgrade {
+        /// 加密对象（Hex编码字节）
+        #[arg(value_parser = parse_serializable::<EncryptedObject, DefaultEncoding>)]
+        encrypted_object: EncryptedObject,
+        
+        /// 可选的创建时间（自Unix纪元起的毫秒数）
+        #[arg(long)]
+        created_at: Option<u64>,
+        
+        /// 可选的明文内容类型提示，例如"video/mp4"
+        #[arg(long)]
+        content_type: Option<String>,
+    },
+    
     /// 使用对称密钥直接解密加密对象
     /// 
     /// 当已知派生的对称密钥时，可以直接解密加密对象而无需使用私钥重建密钥
@@ -237,30 +457,236 @@ enum Command {
         /// 加密时派生的对称密钥
         #[arg(long)]
         key: EncodedBytes,
+        
+        /// 解压缩后明文的最大长度（字节），用于防止解压缩炸弹
+        #[arg(long, default_value_t = DEFAULT_MAX_DECOMPRESSED_SIZE)]
+        max_decompressed_size: usize,
+    },
+    
+    /// 生成可复现的测试向量
+    /// 
+    /// 使用给定的种子生成覆盖所有密文类型的JSON测试向量，相同的种子总是生成相同的输出
+    TestVectors {
+        /// 随机数生成器的种子
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    
+    /// 分布式密钥生成（DKG）
+    /// 
+    /// N个运营者通过交换文件共同生成主密钥的阈值份额，任何一方都不知道完整的主密钥。
+    /// 每个参与方依次运行init、deal和finalize，并在每轮结束后公开生成的公钥文件或消息文件。
+    Dkg {
+        #[command(subcommand)]
+        command: DkgCommand,
+    },
+    
+    /// 使用DKG生成的主密钥份额提取部分用户私钥
+    /// 
+    /// 阈值数量的部分私钥可以通过aggregate命令聚合为普通的用户私钥
+    ExtractPartial {
+        /// Sui网络上处理此密钥的KMS包的地址
+        #[arg(long)]
+        package_id: ObjectID,
+        
+        /// 应派生密钥的ID
+        #[arg(long)]
+        id: EncodedBytes,
+        
+        /// dkg finalize生成的主密钥份额文件路径
+        #[arg(long)]
+        key_share: PathBuf,
     },
+    
+    /// 聚合部分用户私钥
+    /// 
+    /// 在指数上进行拉格朗日插值，结果与使用完整主密钥提取的用户私钥相同
+    Aggregate {
+        /// 部分私钥的份额索引列表。顺序必须与partials字段中的部分私钥顺序匹配
+        #[arg(long, num_args = 1..)]
+        indices: Vec<u8>,
+        
+        /// Hex编码的部分用户私钥列表
+        #[arg(value_parser = parse_serializable::<G1Element, DefaultEncoding>, num_args = 1..)]
+        partials: Vec<G1Element>,
+    },
+    
+    /// 使用发送者的私钥签名加密对象
+    /// 
+    /// 接收者可以使用verify-and-decrypt命令验证加密对象确实由该发送者创建
+    Sign {
+        /// 加密对象（Hex编码字节）
+        #[arg(value_parser = parse_serializable::<EncryptedObject, DefaultEncoding>)]
+        encrypted_object: EncryptedObject,
+        
+        /// 签名方案
+        #[arg(long, value_enum)]
+        scheme: SignatureScheme,
+        
+        /// 发送者的Hex编码私钥
+        #[arg(long)]
+        private_key: EncodedBytes,
+    },
+    
+    /// 验证签名的加密对象的发送者并解密
+    /// 
+    /// 签名无效或者发送者不是预期的发送者时不会解密
+    VerifyAndDecrypt {
+        /// 签名的加密对象（Hex编码字节）
+        #[arg(value_parser = parse_serializable::<SignedEncryptedObject, DefaultEncoding>)]
+        signed_object: SignedEncryptedObject,
+        
+        /// 预期的发送者，格式为"ed25519:<Hex编码公钥>"、"secp256k1:<Hex编码公钥>"或"sui:<Sui地址>"
+        #[arg(long, value_parser = parse_sender)]
+        sender: Sender,
+        
+        /// 密钥服务器的私钥列表。私钥顺序必须与object_ids字段中的密钥服务器顺序匹配
+        #[arg(value_parser = parse_serializable::<G1Element, DefaultEncoding>, num_args = 1..)]
+        secret_keys: Vec<G1Element>,
+        
+        /// 解压缩后明文的最大长度（字节），用于防止解压缩炸弹
+        #[arg(long, default_value_t = DEFAULT_MAX_DECOMPRESSED_SIZE)]
+        max_decompressed_size: usize,
+        
+        /// 用于此解密的密钥服务器Move对象地址列表
+        #[arg(num_args = 1.., last = true)]
+        object_ids: Vec<ObjectID>,
+    },
+}
+
+/// 签名加密对象时使用的签名方案
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum SignatureScheme {
+    /// Ed25519签名
+    Ed25519,
+    /// secp256k1签名
+    Secp256k1,
+    /// 使用Ed25519私钥生成Sui个人消息签名
+    SuiEd25519,
+    /// 使用secp256k1私钥生成Sui个人消息签名
+    SuiSecp256k1,
+}
+
+/**
+ * DKG的各轮命令
+ * 
+ * 所有文件都包含Hex编码的BCS序列化数据。私钥文件必须保密，创建时权限为0600且不会覆盖已有文件。
+ * 分发者的消息带有签名，可以通过公开信道交换；公钥文件是签名的信任根，必须通过认证信道分发并核对。
+ */
+#[derive(Subcommand, Debug)]
+enum DkgCommand {
+    /// 第0轮：生成用于接收份额的密钥对
+    Init {
+        /// 参与方的索引，从1开始且互不相同
+        #[arg(long)]
+        index: u8,
+        
+        /// 私钥输出文件路径，必须保密
+        #[arg(long)]
+        secret: PathBuf,
+        
+        /// 公钥输出文件路径，需要发给所有参与方
+        #[arg(long)]
+        public: PathBuf,
+    },
+    
+    /// 第1轮：作为分发者生成使用自己的私钥签名的消息
+    Deal {
+        /// init生成的私钥文件路径
+        #[arg(long)]
+        secret: PathBuf,
+        
+        /// 本次运行的会话ID（Hex编码字节），所有参与方必须相同，并且每次运行都不同
+        #[arg(long)]
+        session_id: EncodedBytes,
+        
+        /// 重构主密钥所需的最小份额数量
+        #[arg(long)]
+        threshold: u8,
+        
+        /// 所有参与方（包括自己）的公钥文件路径
+        #[arg(long, num_args = 1..)]
+        parties: Vec<PathBuf>,
+        
+        /// 消息输出文件路径，需要发给所有参与方
+        #[arg(long)]
+        output: PathBuf,
+    },
+    
+    /// 第2轮：处理所有参与方的消息，得到自己的主密钥份额
+    Finalize {
+        /// init生成的私钥文件路径
+        #[arg(long)]
+        secret: PathBuf,
+        
+        /// 本次运行的会话ID（Hex编码字节），所有参与方必须相同，并且每次运行都不同
+        #[arg(long)]
+        session_id: EncodedBytes,
+        
+        /// 重构主密钥所需的最小份额数量
+        #[arg(long)]
+        threshold: u8,
+        
+        /// 所有参与方（包括自己）的公钥文件路径
+        #[arg(long, num_args = 1..)]
+        parties: Vec<PathBuf>,
+        
+        /// 所有参与方（包括自己）的消息文件路径
+        #[arg(long, num_args = 1..)]
+        messages: Vec<PathBuf>,
+        
+        /// 主密钥份额输出文件路径，必须保密
+        #[arg(long)]
+        output: PathBuf,
+    },
+}
+
+/// DKG参与方的私钥文件内容
+#[derive(Serialize, Deserialize)]
+struct DkgPartySecret {
+    index: u8,
+    secret_key: PartySecretKey,
 }
 
 /// 生成密钥命令的输出结构
-struct GenkeyOutput((Scalar, G2Element));
+struct GenkeyOutput((MasterKey, G2Element));
 
 /// 提取用户私钥命令的输出结构
 struct ExtractOutput(G1Element);
 
+/// 提取部分用户私钥命令的输出结构
+struct ExtractPartialOutput(PartialUserSecretKey);
+
 /// 验证命令的输出结构
 struct VerifyOutput(FastCryptoResult<()>);
 
 /// 加密命令的输出结构
-struct EncryptionOutput((EncryptedObject, [u8; KEY_LENGTH]));
+struct EncryptionOutput((EncryptedObject, Secret<[u8; KEY_LENGTH]>));
 
 /// 解密命令的输出结构
 struct DecryptionOutput(Vec<u8>);
 
+/// 流式解密命令的输出结构，包含写入的明文字节数
+struct DecryptFileOutput(u64);
+
 /// 解析命令的输出结构
 struct ParseOutput(EncryptedObject);
 
 /// 对称解密命令的输出结构
 struct SymmetricDecryptOutput(Vec<u8>);
 
+/// 升级和重新分享命令的输出结构，只包含加密对象
+struct EncryptedObjectOutput(EncryptedObject);
+
+/// 签名命令的输出
+struct SignOutput((SignedEncryptedObject, Sender));
+
+/// DKG前两轮命令的输出结构，包含写入的公开文件路径
+struct DkgFileOutput(PathBuf);
+
+/// DKG最后一轮命令的输出结构，包含份额和写入的文件路径
+struct DkgFinalizeOutput((KeyShare, PathBuf));
+
 /**
  * 主函数 - CLI入口点
  * 
@@ -320,21 +746,36 @@ fn main() -> FastCryptoResult<()> {
         Command::EncryptAes {
             message,
             aad,
+            committing,
+            bind_context,
+            compress,
+            pad,
             package_id,
             id,
             threshold,
             public_keys,
             object_ids,
-        } => EncryptionOutput(seal_encrypt(
+        } => EncryptionOutput(encrypt_function(bind_context)(
             package_id,
             id.0,
             object_ids,
             &IBEPublicKeys::BonehFranklinBLS12381(public_keys),
             threshold,
-            EncryptionInput::Aes256Gcm {
-                data: message.0,
-                aad: aad.map(|a| a.0),
-            },
+            with_options(
+                if committing {
+                    EncryptionInput::Aes256GcmCommitting {
+                        data: message.0,
+                        aad: aad.map(|a| a.0),
+                    }
+                } else {
+                    EncryptionInput::Aes256Gcm {
+                        data: message.0,
+                        aad: aad.map(|a| a.0),
+                    }
+                },
+                compress,
+                pad,
+            ),
         )?)
         .to_string(),
         
@@ -342,21 +783,101 @@ fn main() -> FastCryptoResult<()> {
         Command::EncryptHmac {
             message,
             aad,
+            bind_context,
+            compress,
+            pad,
             package_id,
             id,
             threshold,
             public_keys,
             object_ids,
-        } => EncryptionOutput(seal_encrypt(
+        } => EncryptionOutput(encrypt_function(bind_context)(
             package_id,
             id.0,
             object_ids,
             &IBEPublicKeys::BonehFranklinBLS12381(public_keys),
             threshold,
-            EncryptionInput::Hmac256Ctr {
-                data: message.0,
-                aad: aad.map(|a| a.0),
-            },
+            with_options(
+                EncryptionInput::Hmac256Ctr {
+                    data: message.0,
+                    aad: aad.map(|a| a.0),
+                },
+                compress,
+                pad,
+            ),
+        )?)
+        .to_string(),
+        
+        // 使用Seal和ChaCha20-Poly1305加密消息
+        Command::EncryptChacha {
+            message,
+            aad,
+            bind_context,
+            compress,
+            pad,
+            package_id,
+            id,
+            threshold,
+            public_keys,
+            object_ids,
+        } => EncryptionOutput(encrypt_function(bind_context)(
+            package_id,
+            id.0,
+            object_ids,
+            &IBEPublicKeys::BonehFranklinBLS12381(public_keys),
+            threshold,
+            with_options(
+                EncryptionInput::ChaCha20Poly1305 {
+                    data: message.0,
+                    aad: aad.map(|a| a.0),
+                },
+                compress,
+                pad,
+            ),
+        )?)
+        .to_string(),
+        
+        // 使用Seal和分块AES-256-GCM流式加密文件
+        Command::EncryptFile {
+            input,
+            output,
+            aad,
+            chunk_size,
+            pad,
+            package_id,
+            id,
+            threshold,
+            public_keys,
+            object_ids,
+        } => EncryptionOutput(seal_encrypt_stream(
+            package_id,
+            id.0,
+            object_ids,
+            &IBEPublicKeys::BonehFranklinBLS12381(public_keys),
+            threshold,
+            aad.map(|a| a.0),
+            chunk_size,
+            pad,
+            BufReader::new(File::open(input).map_err(io_error)?),
+            BufWriter::new(File::create(output).map_err(io_error)?),
+        )?)
+        .to_string(),
+        
+        // 流式解密由encrypt-file生成的文件
+        Command::DecryptFile {
+            encrypted_object,
+            input,
+            output,
+            secret_keys,
+            object_ids,
+        } => DecryptFileOutput(seal_decrypt_stream(
+            &encrypted_object,
+            &IBEUserSecretKeys::BonehFranklinBLS12381(
+                object_ids.into_iter().zip(secret_keys).collect(),
+            ),
+            None,
+            BufReader::new(File::open(input).map_err(io_error)?),
+            BufWriter::new(File::create(output).map_err(io_error)?),
         )?)
         .to_string(),
         
@@ -364,44 +885,236 @@ fn main() -> FastCryptoResult<()> {
         Command::Decrypt {
             encrypted_object,
             secret_keys,
+            max_decompressed_size,
             object_ids,
-        } => DecryptionOutput(seal_decrypt(
+        } => DecryptionOutput(seal_decrypt_with_max_size(
             &encrypted_object,
             &IBEUserSecretKeys::BonehFranklinBLS12381(
                 object_ids.into_iter().zip(secret_keys).collect(),
             ),
             None,
+            max_decompressed_size,
         )?)
         .to_string(),
         
         // 解析Seal加密对象
         Command::Parse { encrypted_object } => ParseOutput(encrypted_object).to_string(),
         
+        // 将加密对象的密钥重新分享给新的密钥服务器集合
+        Command::Reshare {
+            encrypted_object,
+            secret_keys,
+            old_object_ids,
+            threshold,
+            public_keys,
+            object_ids,
+        } => EncryptedObjectOutput(reshare_with_user_secret_keys(
+            &encrypted_object,
+            &IBEUserSecretKeys::BonehFranklinBLS12381(
+                old_object_ids.into_iter().zip(secret_keys).collect(),
+            ),
+            None,
+            object_ids,
+            &IBEPublicKeys::BonehFranklinBLS12381(public_keys),
+            threshold,
+        )?)
+        .to_string(),
+        
+        // 将加密对象升级到版本1
+        Command::Upgrade {
+            encrypted_object,
+            created_at,
+            content_type,
+        } => {
+            let mut upgraded = upgrade_to_v1(&encrypted_object);
+            if let Some(created_at) = created_at {
+                upgraded.set_created_at(created_at)?;
+            }
+            if let Some(content_type) = content_type {
+                upgraded.set_content_type(&content_type)?;
+            }
+            EncryptedObjectOutput(upgraded).to_string()
+        }
+        
         // 使用对称密钥直接解密加密对象
         Command::SymmetricDecrypt {
             encrypted_object,
             key,
+            max_decompressed_size,
         } => {
             // 转换输入密钥为正确的格式
             let dem_key = key
                 .0
                 .try_into()
                 .map_err(|_| FastCryptoError::InvalidInput)?;
-            let EncryptedObject { ciphertext, .. } = encrypted_object;
+            // 包含头部摘要（如果绑定）的额外认证数据
+            let aad = encrypted_object.dem_aad();
 
-            // 根据加密模式选择相应的解密方法
-            match ciphertext {
-                Ciphertext::Aes256Gcm { blob, aad } => {
-                    Aes256Gcm::decrypt(&blob, &aad.unwrap_or(vec![]), &dem_key)
+            // 根据加密模式选择相应的解密方法，然后移除填充并解压缩（如果有）
+            match &encrypted_object.ciphertext {
+                Ciphertext::Aes256Gcm { blob, .. } => Aes256Gcm::decrypt(blob, &aad, &dem_key),
+                Ciphertext::Hmac256Ctr { blob, mac, .. } => {
+                    Hmac256Ctr::decrypt(blob, mac, &aad, &dem_key)
                 }
-                Ciphertext::Hmac256Ctr { blob, aad, mac } => {
-                    Hmac256Ctr::decrypt(&blob, &mac, &aad.unwrap_or(vec![]), &dem_key)
+                Ciphertext::Aes256GcmChunked {
+                    blob, chunk_size, ..
+                } => Aes256GcmChunked::decrypt(blob, &aad, &dem_key, *chunk_size),
+                Ciphertext::ChaCha20Poly1305 { blob, .. } => {
+                    ChaCha20Poly1305::decrypt(blob, &aad, &dem_key)
                 }
+                Ciphertext::Aes256GcmCommitting {
+                    blob, commitment, ..
+                } => Aes256GcmCommitting::decrypt(blob, commitment, &aad, &dem_key),
                 _ => Err(FastCryptoError::InvalidInput),
             }
+            .and_then(|plaintext| {
+                encrypted_object.decode_plaintext(plaintext, max_decompressed_size)
+            })
             .map(SymmetricDecryptOutput)?
             .to_string()
         }
+        
+        // 生成可复现的测试向量
+        Command::TestVectors { seed } => crypto::test_vectors::generate_json(seed)?,
+        
+        // 分布式密钥生成
+        Command::Dkg { command } => match command {
+            DkgCommand::Init {
+                index,
+                secret,
+                public,
+            } => {
+                let (secret_key, party) = dkg::generate_party_key(&mut thread_rng(), index)?;
+                write_secret_file(&secret, &DkgPartySecret { index, secret_key })?;
+                write_file(&public, &party)?;
+                DkgFileOutput(public).to_string()
+            }
+            DkgCommand::Deal {
+                secret,
+                session_id,
+                threshold,
+                parties,
+                output,
+            } => {
+                let DkgPartySecret { index, secret_key } = read_file(&secret)?;
+                let parties = parties
+                    .iter()
+                    .map(read_file)
+                    .collect::<FastCryptoResult<Vec<_>>>()?;
+                let message = dkg::deal(
+                    &mut thread_rng(),
+                    &secret_key,
+                    &session_id.0,
+                    index,
+                    threshold,
+                    &parties,
+                )?;
+                write_file(&output, &message)?;
+                DkgFileOutput(output).to_string()
+            }
+            DkgCommand::Finalize {
+                secret,
+                session_id,
+                threshold,
+                parties,
+                messages,
+                output,
+            } => {
+                let DkgPartySecret { index, secret_key } = read_file(&secret)?;
+                let parties = parties
+                    .iter()
+                    .map(read_file)
+                    .collect::<FastCryptoResult<Vec<_>>>()?;
+                let messages = messages
+                    .iter()
+                    .map(read_file)
+                    .collect::<FastCryptoResult<Vec<_>>>()?;
+                let key_share = dkg::finalize(
+                    index,
+                    &secret_key,
+                    &session_id.0,
+                    threshold,
+                    &parties,
+                    &messages,
+                )?;
+                write_secret_file(&output, &key_share)?;
+                DkgFinalizeOutput((key_share, output)).to_string()
+            }
+        },
+        
+        // 使用主密钥份额提取部分用户私钥
+        Command::ExtractPartial {
+            package_id,
+            id,
+            key_share,
+        } => {
+            let key_share: KeyShare = read_file(&key_share)?;
+            ExtractPartialOutput(ibe::extract_partial(
+                &key_share.share,
+                key_share.index,
+                &create_full_id(&package_id, &id.0),
+            ))
+            .to_string()
+        }
+        
+        // 聚合部分用户私钥
+        Command::Aggregate { indices, partials } => {
+            if indices.len() != partials.len() {
+                return Err(FastCryptoError::InvalidInput);
+            }
+            ExtractOutput(ibe::aggregate_partials(
+                &indices.into_iter().zip(partials).collect::<Vec<_>>(),
+            )?)
+            .to_string()
+        }
+        
+        // 签名加密对象
+        Command::Sign {
+            encrypted_object,
+            scheme,
+            private_key,
+        } => {
+            let signed = match scheme {
+                SignatureScheme::Ed25519 | SignatureScheme::SuiEd25519 => {
+                    let key_pair =
+                        Ed25519KeyPair::from(Ed25519PrivateKey::from_bytes(&private_key.0)?);
+                    let signing_key = match scheme {
+                        SignatureScheme::Ed25519 => SigningKey::Ed25519(&key_pair),
+                        _ => SigningKey::SuiEd25519(&key_pair),
+                    };
+                    SignedEncryptedObject::sign(encrypted_object, &signing_key)?
+                }
+                SignatureScheme::Secp256k1 | SignatureScheme::SuiSecp256k1 => {
+                    let key_pair =
+                        Secp256k1KeyPair::from(Secp256k1PrivateKey::from_bytes(&private_key.0)?);
+                    let signing_key = match scheme {
+                        SignatureScheme::Secp256k1 => SigningKey::Secp256k1(&key_pair),
+                        _ => SigningKey::SuiSecp256k1(&key_pair),
+                    };
+                    SignedEncryptedObject::sign(encrypted_object, &signing_key)?
+                }
+            };
+            let sender = signed.verify()?;
+            SignOutput((signed, sender)).to_string()
+        }
+        
+        // 验证发送者并解密
+        Command::VerifyAndDecrypt {
+            signed_object,
+            sender,
+            secret_keys,
+            max_decompressed_size,
+            object_ids,
+        } => DecryptionOutput(verify_and_decrypt_with_max_size(
+            &signed_object,
+            &sender,
+            &IBEUserSecretKeys::BonehFranklinBLS12381(
+                object_ids.into_iter().zip(secret_keys).collect(),
+            ),
+            None,
+            max_decompressed_size,
+        )?)
+        .to_string(),
     };
     
     // 输出结果
@@ -409,6 +1122,71 @@ fn main() -> FastCryptoResult<()> {
     Ok(())
 }
 
+/// 加密消息的函数。绑定头部时使用`seal_encrypt_context_bound`，否则使用`seal_encrypt`
+type EncryptFunction = fn(
+    ObjectID,
+    Vec<u8>,
+    Vec<ObjectID>,
+    &IBEPublicKeys,
+    u8,
+    EncryptionInput,
+) -> FastCryptoResult<(EncryptedObject, Secret<[u8; KEY_LENGTH]>)>;
+
+/// 根据是否绑定头部选择加密函数
+fn encrypt_function(bind_context: bool) -> EncryptFunction {
+    if bind_context {
+        seal_encrypt_context_bound
+    } else {
+        seal_encrypt
+    }
+}
+
+/// 如果指定了压缩算法和填充策略，先压缩再填充加密输入
+fn with_options(
+    input: EncryptionInput,
+    compression: Option<Compression>,
+    padding: Option<Padding>,
+) -> EncryptionInput {
+    let input = match compression {
+        Some(compression) => input.compressed(compression),
+        None => input,
+    };
+    match padding {
+        Some(padding) => input.padded(padding),
+        None => input,
+    }
+}
+
+/// 将文件读写错误转换为FastCryptoError
+fn io_error(e: std::io::Error) -> FastCryptoError {
+    FastCryptoError::GeneralError(e.to_string())
+}
+
+/// 从文件中读取Hex编码的BCS序列化数据
+fn read_file<T: for<'a> Deserialize<'a>>(path: &PathBuf) -> FastCryptoResult<T> {
+    let content = std::fs::read_to_string(path).map_err(io_error)?;
+    parse_serializable::<T, DefaultEncoding>(content.trim()).map_err(FastCryptoError::GeneralError)
+}
+
+/// 将数据以Hex编码的BCS序列化形式写入文件
+fn write_file<T: Serialize>(path: &PathBuf, value: &T) -> FastCryptoResult<()> {
+    std::fs::write(path, serializable_to_string(value)).map_err(io_error)
+}
+
+/// 与`write_file`相同，但用于私钥和主密钥份额等秘密数据：
+/// 文件创建时权限即为0600（只有所有者可以读写），并且拒绝覆盖已存在的文件，
+/// 避免秘密数据在写入期间被其他用户读取，或者通过预先创建的文件或符号链接被窃取
+fn write_secret_file<T: Serialize>(path: &PathBuf, value: &T) -> FastCryptoResult<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .map_err(io_error)?;
+    file.write_all(serializable_to_string(value).as_bytes())
+        .map_err(io_error)
+}
+
 /// 用于CLI二进制输入的类型
 /// 
 /// 包装了一个字节向量，用于处理Hex编码的输入参数
@@ -449,6 +1227,72 @@ pub fn parse_serializable<T: for<'a> Deserialize<'a>, E: Encoding>(s: &str) -> R
     bcs::from_bytes(&bytes).map_err(|e| format!("{}", e))
 }
 
+/**
+ * 解析发送者
+ * 
+ * 格式为"ed25519:<Hex编码公钥>"、"secp256k1:<Hex编码公钥>"或"sui:<Sui地址>"
+ */
+fn parse_sender(s: &str) -> Result<Sender, String> {
+    let (scheme, value) = s
+        .split_once(':')
+        .ok_or("发送者的格式应为<方案>:<值>".to_string())?;
+    let bytes = Hex::decode(value).map_err(|e| format!("{}", e))?;
+    match scheme {
+        "ed25519" => Ed25519PublicKey::from_bytes(&bytes)
+            .map(Sender::Ed25519)
+            .map_err(|e| format!("{}", e)),
+        "secp256k1" => Secp256k1PublicKey::from_bytes(&bytes)
+            .map(Sender::Secp256k1)
+            .map_err(|e| format!("{}", e)),
+        "sui" => bytes
+            .try_into()
+            .map(Sender::Sui)
+            .map_err(|_| "无效的Sui地址".to_string()),
+        _ => Err(format!("未知的签名方案: {}", scheme)),
+    }
+}
+
+/**
+ * 解析填充策略
+ * 
+ * 格式为"bucket:<字节数>"、"power-of-two"或"padme"
+ */
+fn parse_padding(s: &str) -> Result<Padding, String> {
+    match s {
+        "power-of-two" => Ok(Padding::PowerOfTwo),
+        "padme" => Ok(Padding::Padme),
+        _ => match s.split_once(':') {
+            Some(("bucket", size)) => match size.parse::<u32>() {
+                Ok(size) if size > 0 => Ok(Padding::Bucket(size)),
+                _ => Err(format!("无效的桶大小: {}", size)),
+            },
+            _ => Err(format!("未知的填充策略: {}", s)),
+        },
+    }
+}
+
+/**
+ * 解析压缩算法
+ * 
+ * 格式为"deflate"或"zstd"
+ */
+fn parse_compression(s: &str) -> Result<Compression, String> {
+    match s {
+        "deflate" => Ok(Compression::Deflate),
+        "zstd" => Ok(Compression::Zstd),
+        _ => Err(format!("未知的压缩算法: {}", s)),
+    }
+}
+
+/// 将发送者格式化为`parse_sender`接受的格式
+fn sender_to_string(sender: &Sender) -> String {
+    match sender {
+        Sender::Ed25519(public_key) => format!("ed25519:{}", Hex::encode(public_key)),
+        Sender::Secp256k1(public_key) => format!("secp256k1:{}", Hex::encode(public_key)),
+        Sender::Sui(address) => format!("sui:{}", Hex::encode(address)),
+    }
+}
+
 // 各命令输出的格式化实现
 
 impl Display for GenkeyOutput {
@@ -468,6 +1312,17 @@ impl Display for ExtractOutput {
     }
 }
 
+impl Display for ExtractPartialOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "份额索引: {}\n部分用户私钥: {}",
+            self.0 .0,
+            serializable_to_string(&self.0 .1)
+        )
+    }
+}
+
 impl Display for VerifyOutput {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -488,7 +1343,7 @@ impl Display for EncryptionOutput {
             f,
             "加密对象 (BCS编码): {}\n对称密钥: {}",
             DefaultEncoding::encode(bcs::to_bytes(&self.0 .0).unwrap()),
-            Hex::encode(self.0 .1)
+            Hex::encode(self.0 .1.expose())
         )
     }
 }
@@ -499,6 +1354,31 @@ impl Display for DecryptionOutput {
     }
 }
 
+impl Display for DkgFileOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "已写入: {}", self.0.display())
+    }
+}
+
+impl Display for DkgFinalizeOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (key_share, path) = &self.0;
+        write!(
+            f,
+            "主密钥份额已写入: {}\n份额索引: {}\n公钥: {}",
+            path.display(),
+            key_share.index,
+            serializable_to_string(&key_share.public_key),
+        )
+    }
+}
+
+impl Display for DecryptFileOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "解密完成，写入明文字节数: {}", self.0)
+    }
+}
+
 impl Display for ParseOutput {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "版本: {}", self.0.version)?;
@@ -509,6 +1389,37 @@ impl Display for ParseOutput {
             writeln!(f, "  {}: {}", id, index)?;
         }
         writeln!(f, "阈值: {}", self.0.threshold)?;
+        if self.0.version >= 1 {
+            writeln!(f, "头部扩展:")?;
+            if let Some(created_at) = self.0.created_at() {
+                writeln!(f, "  创建时间: {}", created_at)?;
+            }
+            if let Some(content_type) = self.0.content_type() {
+                writeln!(f, "  内容类型: {}", content_type)?;
+            }
+            if let Some(digest) = self.0.context_digest() {
+                writeln!(f, "  绑定的头部摘要: {}", Hex::encode(digest))?;
+            }
+            if let Ok(Some(compression)) = self.0.compression() {
+                writeln!(f, "  压缩算法: {:?}", compression)?;
+            }
+            if let Ok(Some(padding)) = self.0.padding() {
+                writeln!(f, "  填充策略: {:?}", padding)?;
+            }
+            for extension in &self.0.extensions {
+                writeln!(
+                    f,
+                    "  {:#06x}{}: {}",
+                    extension.tag,
+                    if extension.is_critical() {
+                        " (关键)"
+                    } else {
+                        ""
+                    },
+                    DefaultEncoding::encode(&extension.value)
+                )?;
+            }
+        }
         writeln!(f, "密文:")?;
         match &self.0.ciphertext {
             Ciphertext::Aes256Gcm { blob, aad } => {
@@ -521,6 +1432,31 @@ impl Display for ParseOutput {
                         .map_or("无".to_string(), DefaultEncoding::encode)
                 )?;
             }
+            Ciphertext::ChaCha20Poly1305 { blob, aad } => {
+                writeln!(f, "  类型: ChaCha20-Poly1305")?;
+                writeln!(f, "  数据: {}", DefaultEncoding::encode(blob))?;
+                writeln!(
+                    f,
+                    "  额外认证数据: {}",
+                    aad.as_ref()
+                        .map_or("无".to_string(), DefaultEncoding::encode)
+                )?;
+            }
+            Ciphertext::Aes256GcmCommitting {
+                blob,
+                aad,
+                commitment,
+            } => {
+                writeln!(f, "  类型: 带有密钥承诺的AES-256-GCM")?;
+                writeln!(f, "  数据: {}", DefaultEncoding::encode(blob))?;
+                writeln!(
+                    f,
+                    "  额外认证数据: {}",
+                    aad.as_ref()
+                        .map_or("无".to_string(), DefaultEncoding::encode)
+                )?;
+                writeln!(f, "  密钥承诺: {}", DefaultEncoding::encode(commitment))?;
+            }
             Ciphertext::Hmac256Ctr { blob, aad, mac } => {
                 writeln!(f, "  类型: HMAC-256-CTR")?;
                 writeln!(f, "  数据: {}", DefaultEncoding::encode(blob))?;
@@ -535,6 +1471,25 @@ impl Display for ParseOutput {
             Ciphertext::Plain => {
                 writeln!(f, "  类型: 明文")?;
             }
+            Ciphertext::Aes256GcmChunked {
+                blob,
+                aad,
+                chunk_size,
+            } => {
+                writeln!(f, "  类型: 分块AES-256-GCM")?;
+                writeln!(f, "  块大小: {}", chunk_size)?;
+                if blob.is_empty() {
+                    writeln!(f, "  数据: 单独存储")?;
+                } else {
+                    writeln!(f, "  数据: {}", DefaultEncoding::encode(blob))?;
+                }
+                writeln!(
+                    f,
+                    "  额外认证数据: {}",
+                    aad.as_ref()
+                        .map_or("无".to_string(), DefaultEncoding::encode)
+                )?;
+            }
         }
         writeln!(f, "加密份额:")?;
         match &self.0.encrypted_shares {
@@ -564,8 +1519,29 @@ impl Display for ParseOutput {
     }
 }
 
+impl Display for EncryptedObjectOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "加密对象 (BCS编码): {}",
+            DefaultEncoding::encode(bcs::to_bytes(&self.0).unwrap())
+        )
+    }
+}
+
 impl Display for SymmetricDecryptOutput {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "解密消息: {}", DefaultEncoding::encode(&self.0))
     }
 }
+
+impl Display for SignOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "签名的加密对象 (BCS编码): {}\n发送者: {}",
+            serializable_to_string(&self.0 .0),
+            sender_to_string(&self.0 .1)
+        )
+    }
+}
@@ -8,10 +8,16 @@
  * 验证从密钥服务器注册、用户请求密钥、加密到解密的整个流程。
  */
 
-use crate::tests::externals::get_key;
+use crate::tests::externals::{fetch_key, get_key};
 use crate::tests::whitelist::{add_user_to_whitelist, create_whitelist, whitelist_create_ptb};
 use crate::tests::SealTestCluster;
-use crypto::{seal_decrypt, seal_encrypt, EncryptionInput, IBEPublicKeys, IBEUserSecretKeys};
+use crate::types::{ElGamalPublicKey, ElgamalEncryptionProof, ElgamalVerificationKey};
+use crypto::{
+    elgamal, ibe, seal_decrypt, seal_encrypt, verify_user_secret_keys, EncryptionInput,
+    IBEPublicKeys, IBEUserSecretKeys,
+};
+use fastcrypto::groups::bls12381::{G1Element, Scalar};
+use fastcrypto::groups::{GroupElement, HashToGroupElement};
 use tracing_test::traced_test;
 
 /**
@@ -23,7 +29,7 @@ use tracing_test::traced_test;
  * 3. 从多个密钥服务器获取用户密钥
  * 4. 在链上注册密钥服务器
  * 5. 使用Seal系统加密消息
- * 6. 使用获取的用户密钥解密消息
+ * 6. 批量验证获取的用户密钥，并使用它们解密消息
  * 7. 验证解密结果是否与原始消息一致
  */
 #[traced_test]
@@ -123,14 +129,88 @@ async fn test_e2e() {
     .unwrap()
     .0;
 
+    // 批量验证前两个服务器提供的用户密钥
+    let usks =
+        IBEUserSecretKeys::BonehFranklinBLS12381(services.into_iter().zip([usk0, usk1]).collect());
+    verify_user_secret_keys(&encryption, &usks, &pks).unwrap();
+
     // 使用前两个服务器提供的用户密钥解密消息
     let decryption = seal_decrypt(
         &encryption,  // 加密对象
-        &IBEUserSecretKeys::BonehFranklinBLS12381(services.into_iter().zip([usk0, usk1]).collect()),  // 用户密钥
-        Some(&pks),   // 提供公钥以验证份额一致性
+        &usks,        // 用户密钥
+        Some(&pks),   // 提供公钥以验证用户密钥和份额一致性
     )
     .unwrap();
 
     // 验证解密结果是否与原始消息一致
     assert_eq!(decryption, message);
 }
+
+/**
+ * 密钥服务器响应的加密正确性证明测试
+ * 
+ * 此测试验证:
+ * 1. 服务器返回的证明可以使用服务器的公钥通过验证，解密得到有效的用户私钥
+ * 2. 被篡改的证明无法通过验证
+ * 3. 证明不能用其他密钥服务器的公钥或其他ElGamal公钥通过验证
+ */
+#[traced_test]
+#[tokio::test]
+async fn test_get_key_proof() {
+    // 创建测试集群，包含2个密钥服务器和1个用户
+    let mut tc = SealTestCluster::new(2, 1).await;
+    let (examples_package_id, _) = tc.publish("patterns").await;
+    let (whitelist, cap) = create_whitelist(tc.get_mut(), examples_package_id).await;
+    let user_address = tc.users[0].address;
+    add_user_to_whitelist(
+        tc.get_mut(),
+        examples_package_id,
+        whitelist,
+        cap,
+        user_address,
+    )
+    .await;
+    let ptb = whitelist_create_ptb(examples_package_id, whitelist, 3);
+
+    // 获取服务器的原始响应
+    let (key, sk, pk) = fetch_key(
+        &tc.servers[0].server,
+        &examples_package_id,
+        ptb,
+        &tc.users[0].keypair,
+    )
+    .await
+    .unwrap();
+    let proof = key.proof.clone().expect("服务器应该返回证明");
+    let base = G1Element::hash_to_group_element(&key.id);
+    let public_key = tc.servers[0].public_key;
+
+    // 有效的证明通过验证，解密得到的是与服务器公钥对应的用户私钥
+    elgamal::verify_encryption(&key.encrypted_key, &proof, &base, &public_key, &pk).unwrap();
+    let usk = elgamal::decrypt(&sk, &key.encrypted_key);
+    assert!(ibe::verify_user_secret_key(&usk, &key.id, &public_key).is_ok());
+
+    // 篡改证明中的一个响应
+    let (challenge, z_r, z_s): (Scalar, Scalar, Scalar) =
+        bcs::from_bytes(&bcs::to_bytes(&proof).unwrap()).unwrap();
+    let tampered: ElgamalEncryptionProof =
+        bcs::from_bytes(&bcs::to_bytes(&(challenge, z_r + Scalar::generator(), z_s)).unwrap())
+            .unwrap();
+    assert!(
+        elgamal::verify_encryption(&key.encrypted_key, &tampered, &base, &public_key, &pk)
+            .is_err()
+    );
+
+    // 使用其他密钥服务器的公钥或其他ElGamal公钥验证
+    let other_public_key = tc.servers[1].public_key;
+    assert!(
+        elgamal::verify_encryption(&key.encrypted_key, &proof, &base, &other_public_key, &pk)
+            .is_err()
+    );
+    let (_, other_pk, _): (_, ElGamalPublicKey, ElgamalVerificationKey) =
+        elgamal::genkey(&mut rand::thread_rng());
+    assert!(
+        elgamal::verify_encryption(&key.encrypted_key, &proof, &base, &public_key, &other_pk)
+            .is_err()
+    );
+}
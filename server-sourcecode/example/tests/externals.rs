@@ -13,12 +13,13 @@ use crate::signed_message::signed_request;
 use crate::{
     signed_message,
     types::{ElGamalPublicKey, ElgamalVerificationKey},
-    Certificate, Server,
+    Certificate, DecryptionKey, Server,
 };
-use crypto::elgamal;
+use crypto::{elgamal, ibe};
 use fastcrypto::ed25519::Ed25519Signature;
 use fastcrypto::encoding::{Base64, Encoding};
 use fastcrypto::traits::{KeyPair, Signer};
+use fastcrypto::error::FastCryptoError;
 use fastcrypto::{ed25519::Ed25519KeyPair, error::FastCryptoResult, groups::bls12381::G1Element};
 use rand::thread_rng;
 use shared_crypto::intent::{Intent, IntentMessage, PersonalMessage};
@@ -88,34 +89,37 @@ pub(super) fn sign(
 }
 
 /**
- * 从密钥服务器获取密钥
+ * 向密钥服务器请求密钥，返回服务器的原始响应
  * 
  * 此函数完成以下步骤：
  * 1. 生成ElGamal密钥对用于加密通信
  * 2. 创建并签名证书和请求
  * 3. 向密钥服务器发送请求
- * 4. 解密服务器响应获取密钥
  * 
  * @param server - 密钥服务器实例
  * @param pkg_id - 包ID，标识密钥服务器使用的Move包
  * @param ptb - 要发送的可编程事务
  * @param kp - 用户的Ed25519密钥对，用于签名
- * @return 成功时返回解密的用户密钥(G1Element)，失败时返回错误
+ * @return 成功时返回加密的密钥（包含加密正确性证明）以及请求使用的ElGamal私钥和公钥，失败时返回错误
  */
-pub(crate) async fn get_key(
+pub(crate) async fn fetch_key(
     server: &Server,
     pkg_id: &ObjectID,
     ptb: ProgrammableTransaction,
     kp: &Ed25519KeyPair,
-) -> FastCryptoResult<G1Element> {
+) -> FastCryptoResult<(
+    DecryptionKey,
+    elgamal::SecretKey<G1Element>,
+    ElGamalPublicKey,
+)> {
     // 生成ElGamal密钥对用于加密通信
     let (sk, pk, vk) = elgamal::genkey(&mut thread_rng());
     
     // 创建证书和请求签名
     let (cert, req_sig) = sign(pkg_id, &ptb, &pk, &vk, kp, current_epoch_time(), 1);
     
-    // 向服务器发送请求并处理响应
-    server
+    // 向服务器发送请求
+    let ids = server
         .check_request(
             &ptb_to_base64(&ptb),
             &pk,
@@ -127,12 +131,43 @@ pub(crate) async fn get_key(
             None, // 无白名单证明
         )
         .await
-        .map(|ids| {
-            // 解密服务器返回的加密密钥
-            elgamal::decrypt(
-                &sk,
-                &server.create_response(&ids, &pk).decryption_keys[0].encrypted_key,
-            )
-        })
-        .map_err(|_| fastcrypto::error::FastCryptoError::GeneralOpaqueError)
+        .map_err(|_| FastCryptoError::GeneralOpaqueError)?;
+    let key = server
+        .create_response(&ids, &pk)
+        .decryption_keys
+        .into_iter()
+        .next()
+        .ok_or(FastCryptoError::GeneralOpaqueError)?;
+    Ok((key, sk, pk))
+}
+
+/**
+ * 从密钥服务器获取密钥
+ * 
+ * 在`fetch_key`的基础上，先使用服务器的公钥验证加密正确性证明，再解密服务器响应获取密钥。
+ * 缺少证明或者证明无效时返回错误。
+ * 
+ * @param server - 密钥服务器实例
+ * @param pkg_id - 包ID，标识密钥服务器使用的Move包
+ * @param ptb - 要发送的可编程事务
+ * @param kp - 用户的Ed25519密钥对，用于签名
+ * @return 成功时返回解密的用户密钥(G1Element)，失败时返回错误
+ */
+pub(crate) async fn get_key(
+    server: &Server,
+    pkg_id: &ObjectID,
+    ptb: ProgrammableTransaction,
+    kp: &Ed25519KeyPair,
+) -> FastCryptoResult<G1Element> {
+    let (key, sk, pk) = fetch_key(server, pkg_id, ptb, kp).await?;
+
+    // 解密之前验证服务器返回的加密正确性证明
+    ibe::verify_encrypted_user_secret_key(
+        &key.encrypted_key,
+        key.proof.as_ref().ok_or(FastCryptoError::GeneralOpaqueError)?,
+        &key.id,
+        &server.public_key,
+        &pk,
+    )?;
+    Ok(elgamal::decrypt(&sk, &key.encrypted_key))
 }
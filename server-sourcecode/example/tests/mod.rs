@@ -18,7 +18,7 @@
 
 use crate::externals::{add_latest, add_package};
 use crate::types::Network;
-use crate::Server;
+use crate::{LockedMasterKey, Server};
 use crypto::ibe;
 use fastcrypto::ed25519::Ed25519KeyPair;
 use fastcrypto::groups::bls12381::G1Element;
@@ -28,6 +28,7 @@ use rand::thread_rng;
 use serde_json::json;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::Arc;
 use sui_move_build::BuildConfig;
 use sui_sdk::json::SuiJsonValue;
 use sui_sdk::rpc_types::{ObjectChange, SuiData, SuiObjectDataOptions};
@@ -109,7 +110,10 @@ impl SealTestCluster {
                 server: Server {
                     sui_client: cluster.sui_client().clone(),
                     network: Network::TestCluster,
-                    master_key,
+                    master_key: Arc::new(
+                        LockedMasterKey::new(master_key, true).expect("Never fails"),
+                    ),
+                    public_key,
                     key_server_object_id: ObjectID::ZERO,
                     key_server_object_id_sig: G1Element::generator(),
                 },